@@ -0,0 +1,544 @@
+//! Module exposant une API de pilotage pour les bots.
+//!
+//! Avec `--api PORT`, le jeu accepte sur ce port TCP local un bot écrit dans
+//! n'importe quel langage. Les messages sont des lignes JSON : le jeu envoie
+//! l'état de la partie à chaque frame, le bot répond par des commandes.
+//!
+//! ```text
+//! < {"type": "state", "tick": 0, "player": 0, "state": "running", ...}
+//! > {"cmd": "command", "rotate": -1, "thrust": 1, "fire": true, "hyperspace": false}
+//! > {"cmd": "state"}
+//! < {"type": "state", "tick": 1, ...}
+//! ```
+//!
+//! `rotate` vaut -1 (gauche), 0 ou 1 (droite) et `thrust` 1 (accélérer), 0 ou
+//! -1 (freiner). Sans `--lockstep`, la partie avance en temps réel et la
+//! dernière commande reçue reste appliquée. Avec `--lockstep`, la partie
+//! attend une commande du bot à chaque frame, qui ne vaut que pour cette
+//! frame. Un message invalide reçoit `{"type": "error", "error": "..."}`.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+
+use serde::{Deserialize, Serialize};
+
+use crate::input::{Controller, Input};
+use crate::world::World;
+
+/// Message envoyé par le bot
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+enum Request {
+    State,
+    Command(Command),
+}
+
+/// Commandes du vaisseau envoyées par le bot
+///
+/// # Champs
+/// * `rotate` - Rotation : -1 à gauche, 1 à droite
+/// * `thrust` - Poussée : 1 pour accélérer, -1 pour freiner
+/// * `fire` - Tirer un missile
+/// * `hyperspace` - Sauter en hyperespace
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct Command {
+    rotate: i8,
+    thrust: i8,
+    fire: bool,
+    hyperspace: bool,
+}
+
+impl From<Command> for Input {
+    fn from(command: Command) -> Self {
+        Self {
+            left: command.rotate < 0,
+            right: command.rotate > 0,
+            up: command.thrust > 0,
+            down: command.thrust < 0,
+            fire: command.fire,
+            hyperspace: command.hyperspace,
+        }
+    }
+}
+
+/// Message envoyé au bot
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum Event {
+    State(State),
+    Error { error: String },
+}
+
+/// Vaisseau d'un joueur
+#[derive(Debug, Serialize)]
+struct ShipState {
+    alive: bool,
+    position: [f32; 2],
+    speed: [f32; 2],
+    orientation: f32,
+    shield: u8,
+    invincibility: f32,
+    score: u32,
+}
+
+/// Astéroïde de l'arène
+#[derive(Debug, Serialize)]
+struct AsteroidState {
+    position: [f32; 2],
+    speed: [f32; 2],
+    width: f32,
+}
+
+/// Missile en vol
+#[derive(Debug, Serialize)]
+struct BulletState {
+    position: [f32; 2],
+    speed: [f32; 2],
+    owner: usize,
+}
+
+/// État complet de la partie envoyé au bot
+///
+/// # Champs
+/// * `tick` - Frame de la partie, la prochaine commande s'applique à la suivante
+/// * `player` - Numéro du vaisseau piloté par le bot dans `ships`
+/// * `state` - État de la partie (`running`, `won` ou `lost`)
+/// * `can_fire` / `can_jump` - Le tir et l'hyperespace sont disponibles
+#[derive(Debug, Serialize)]
+struct State {
+    tick: u64,
+    player: usize,
+    state: &'static str,
+    wave: u32,
+    arena: [f32; 2],
+    can_fire: bool,
+    can_jump: bool,
+    ships: Vec<ShipState>,
+    asteroids: Vec<AsteroidState>,
+    bullets: Vec<BulletState>,
+}
+
+impl State {
+    fn new(world: &World, player: usize) -> Self {
+        Self {
+            tick: world.frame,
+            player,
+            state: world.state.name(),
+            wave: world.wave,
+            arena: world.size.into(),
+            can_fire: world.can_fire(player),
+            can_jump: world.can_jump(player),
+            ships: world
+                .players
+                .iter()
+                .map(|p| ShipState {
+                    alive: p.is_alive(),
+                    position: p.spaceship.get_position().into(),
+                    speed: p.spaceship.get_speed().into(),
+                    orientation: p.spaceship.get_orientation(),
+                    shield: p.spaceship.get_shield(),
+                    invincibility: p.spaceship.get_invicibility().max(0.),
+                    score: p.score,
+                })
+                .collect(),
+            asteroids: world
+                .asteroids
+                .iter()
+                .map(|a| AsteroidState {
+                    position: a.get_position().into(),
+                    speed: a.get_speed().into(),
+                    width: a.get_width(),
+                })
+                .collect(),
+            bullets: world
+                .bullets
+                .iter()
+                .map(|b| BulletState {
+                    position: b.get_position().into(),
+                    speed: b.get_speed().into(),
+                    owner: b.get_owner(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Interprète une ligne envoyée par un bot
+///
+/// # Retourne
+/// Les commandes du bot, `None` s'il demande l'état de la partie, ou une
+/// erreur si la ligne est invalide
+pub fn parse_request(line: &str) -> Result<Option<Input>, String> {
+    match serde_json::from_str::<Request>(line) {
+        Ok(Request::State) => Ok(None),
+        Ok(Request::Command(command)) => Ok(Some(command.into())),
+        Err(e) => Err(format!("message invalide ({e})")),
+    }
+}
+
+/// Ligne JSON décrivant la partie au bot qui pilote le vaisseau `player`
+pub fn state_message(world: &World, player: usize) -> String {
+    serde_json::to_string(&Event::State(State::new(world, player))).unwrap_or_default()
+}
+
+/// Ligne JSON signalant une erreur au bot
+pub fn error_message(error: String) -> String {
+    serde_json::to_string(&Event::Error { error }).unwrap_or_default()
+}
+
+/// Connexion d'un bot
+///
+/// # Champs
+/// * `stream` - Connexion TCP non bloquante
+/// * `received` - Données reçues dont la ligne n'est pas encore complète
+/// * `pending` - Données pas encore envoyées
+struct Connection {
+    stream: TcpStream,
+    received: Vec<u8>,
+    pending: Vec<u8>,
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Option<Self> {
+        stream.set_nonblocking(true).ok()?;
+        let _ = stream.set_nodelay(true);
+        Some(Self {
+            stream,
+            received: Vec::new(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Envoie un message sur une ligne
+    ///
+    /// # Retourne
+    /// `false` si le bot s'est déconnecté
+    fn send(&mut self, line: &str) -> bool {
+        self.pending.extend_from_slice(line.as_bytes());
+        self.pending.push(b'\n');
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => return false,
+                Ok(len) => {
+                    self.pending.drain(..len);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return false,
+            }
+        }
+        true
+    }
+
+    /// Attend l'envoi de toutes les données en attente
+    ///
+    /// # Retourne
+    /// `false` si le bot s'est déconnecté
+    fn flush(&mut self) -> bool {
+        if self.pending.is_empty() {
+            return true;
+        }
+        if self.stream.set_nonblocking(false).is_err() {
+            return false;
+        }
+        let written = self.stream.write_all(&self.pending);
+        self.pending.clear();
+        self.stream.set_nonblocking(true).is_ok() && written.is_ok()
+    }
+
+    /// Lit les lignes complètes reçues
+    ///
+    /// # Arguments
+    /// * `block` - Attendre qu'au moins une ligne soit reçue
+    ///
+    /// # Retourne
+    /// Les lignes reçues, ou `None` si le bot s'est déconnecté
+    fn receive(&mut self, block: bool) -> Option<Vec<String>> {
+        // Le bot ne répondra qu'après avoir reçu tout l'état de la frame
+        if block && !self.flush() {
+            return None;
+        }
+        let mut chunk = [0; 4096];
+        let mut closed = false;
+        loop {
+            let waiting = block && !self.received.contains(&b'\n');
+            if waiting {
+                self.stream.set_nonblocking(false).ok()?;
+            }
+            let read = self.stream.read(&mut chunk);
+            if waiting {
+                self.stream.set_nonblocking(true).ok()?;
+            }
+            match read {
+                Ok(0) => {
+                    closed = true;
+                    break;
+                }
+                Ok(len) => self.received.extend_from_slice(&chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(_) => return None,
+            }
+        }
+        let mut lines = Vec::new();
+        while let Some(end) = self.received.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.received.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line).trim().to_string();
+            if !line.is_empty() {
+                lines.push(line);
+            }
+        }
+        // Les dernières lignes d'un bot déconnecté sont encore traitées
+        if closed && lines.is_empty() {
+            return None;
+        }
+        Some(lines)
+    }
+}
+
+/// Vaisseau piloté par un bot connecté à l'API
+///
+/// # Champs
+/// * `listener` - Port d'écoute du bot
+/// * `bot` - Bot connecté, un seul à la fois
+/// * `lockstep` - La partie attend une commande du bot à chaque frame
+/// * `command` - Commandes à appliquer
+/// * `ready` - Une commande a été reçue pour la prochaine frame (lockstep)
+/// * `sent` - Dernière frame dont l'état a été envoyé
+pub struct RemoteBot {
+    listener: TcpListener,
+    bot: Option<Connection>,
+    lockstep: bool,
+    command: Input,
+    ready: bool,
+    sent: Option<u64>,
+}
+
+impl RemoteBot {
+    /// Ouvre l'API sur le port local donné (0 pour un port libre quelconque)
+    pub fn bind(port: u16, lockstep: bool) -> Result<Self, String> {
+        let listener = TcpListener::bind(SocketAddr::from(([127, 0, 0, 1], port)))
+            .map_err(|e| format!("impossible d'ouvrir le port {port} ({e})"))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        Ok(Self {
+            listener,
+            bot: None,
+            lockstep,
+            command: Input::default(),
+            ready: false,
+            sent: None,
+        })
+    }
+
+    /// Port de l'API
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().map_or(0, |addr| addr.port())
+    }
+
+    /// Attend les commandes du bot pour la prochaine frame (mode sans fenêtre)
+    ///
+    /// Sans fenêtre, la partie attend toujours le bot, comme en lockstep.
+    ///
+    /// # Retourne
+    /// Les commandes, ou une erreur si le bot se déconnecte
+    pub fn wait(&mut self, world: &World, player: usize) -> Result<Input, String> {
+        if self.bot.is_none() {
+            self.listener
+                .set_nonblocking(false)
+                .map_err(|e| e.to_string())?;
+            let accepted = self.listener.accept();
+            self.listener
+                .set_nonblocking(true)
+                .map_err(|e| e.to_string())?;
+            let (stream, _) = accepted.map_err(|e| e.to_string())?;
+            self.bot = Connection::new(stream);
+        }
+        self.send_state(world, player);
+        while !self.ready {
+            if self.bot.is_none() {
+                return Err("le bot s'est déconnecté".to_string());
+            }
+            self.poll(world, player, true);
+        }
+        self.ready = false;
+        Ok(std::mem::take(&mut self.command))
+    }
+
+    /// Envoie au bot l'état final de la partie avant de fermer la connexion
+    pub fn finish(&mut self, world: &World, player: usize) {
+        self.send_state(world, player);
+        if let Some(bot) = &mut self.bot {
+            bot.flush();
+        }
+        self.bot = None;
+    }
+
+    /// Accueille un nouveau bot si aucun n'est connecté
+    fn accept(&mut self) {
+        if self.bot.is_none() {
+            if let Ok((stream, _)) = self.listener.accept() {
+                self.bot = Connection::new(stream);
+                self.sent = None;
+                self.ready = false;
+            }
+        }
+    }
+
+    /// Envoie au bot l'état de la frame, une seule fois par frame
+    fn send_state(&mut self, world: &World, player: usize) {
+        if self.sent == Some(world.frame) {
+            return;
+        }
+        self.sent = Some(world.frame);
+        self.reply(&state_message(world, player));
+    }
+
+    /// Envoie un message au bot, qui est oublié s'il s'est déconnecté
+    fn reply(&mut self, line: &str) {
+        if let Some(bot) = &mut self.bot {
+            if !bot.send(line) {
+                self.bot = None;
+            }
+        }
+    }
+
+    /// Traite les messages reçus du bot
+    fn poll(&mut self, world: &World, player: usize, block: bool) {
+        let Some(bot) = &mut self.bot else {
+            return;
+        };
+        let Some(lines) = bot.receive(block) else {
+            self.bot = None;
+            return;
+        };
+        for line in lines {
+            match parse_request(&line) {
+                Ok(Some(command)) => {
+                    self.command = command;
+                    self.ready = true;
+                }
+                Ok(None) => self.reply(&state_message(world, player)),
+                Err(e) => self.reply(&error_message(e)),
+            }
+        }
+    }
+}
+
+impl Controller for RemoteBot {
+    fn is_ready(&mut self, world: &World, player: usize) -> bool {
+        if !self.lockstep {
+            return true;
+        }
+        self.accept();
+        self.send_state(world, player);
+        self.poll(world, player, false);
+        self.ready
+    }
+
+    fn input(&mut self, world: &World, player: usize) -> Input {
+        // En lockstep, la commande a été lue par `is_ready` et ne vaut qu'une frame
+        if self.lockstep {
+            self.ready = false;
+            return std::mem::take(&mut self.command);
+        }
+        self.accept();
+        self.send_state(world, player);
+        self.poll(world, player, false);
+        if self.bot.is_none() {
+            self.command = Input::default();
+        }
+        self.command
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::{BufRead, BufReader};
+    use std::thread;
+
+    use macroquad::prelude::*;
+    use serde_json::Value;
+
+    use crate::world::DEFAULT_ARENA;
+    use crate::Difficulty;
+
+    /// Bot de test : tourne à gauche et tire tant que la partie tourne
+    fn bot(port: u16, frames: u64) -> thread::JoinHandle<Vec<Value>> {
+        thread::spawn(move || {
+            let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut states = Vec::new();
+            let mut line = String::new();
+            while states.len() < frames as usize {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                let state: Value = serde_json::from_str(&line).unwrap();
+                states.push(state);
+                writeln!(
+                    writer,
+                    r#"{{"cmd": "command", "rotate": -1, "fire": true}}"#
+                )
+                .unwrap();
+            }
+            states
+        })
+    }
+
+    #[test]
+    fn pilotage_en_lockstep() {
+        let mut remote = RemoteBot::bind(0, true).unwrap();
+        let bot = bot(remote.port(), 10);
+        let mut world = World::new(3, Difficulty::Easy, DEFAULT_ARENA);
+        let orientation = world.spaceship().get_orientation();
+        for _ in 0..10 {
+            let input = remote.wait(&world, 0).unwrap();
+            world.step(&input);
+        }
+
+        // Chaque état correspond à une frame et chaque commande a été appliquée
+        let states = bot.join().unwrap();
+        let ticks: Vec<u64> = states.iter().map(|s| s["tick"].as_u64().unwrap()).collect();
+        assert_eq!(ticks, (0..10).collect::<Vec<_>>());
+        assert_eq!(states[0]["type"], "state");
+        assert_eq!(states[0]["ships"][0]["shield"], 10);
+        assert_eq!(states[0]["asteroids"].as_array().unwrap().len(), 3);
+        assert_eq!(world.frame, 10);
+        assert!(world.spaceship().get_orientation() < orientation);
+        assert!(!world.bullets.is_empty());
+    }
+
+    #[test]
+    fn commandes_invalides() {
+        let mut remote = RemoteBot::bind(0, true).unwrap();
+        let port = remote.port();
+        let client = thread::spawn(move || {
+            let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = stream;
+            let mut lines = vec![String::new(); 3];
+            reader.read_line(&mut lines[0]).unwrap();
+            writeln!(writer, "pas du json").unwrap();
+            reader.read_line(&mut lines[1]).unwrap();
+            writeln!(writer, r#"{{"cmd": "state"}}"#).unwrap();
+            reader.read_line(&mut lines[2]).unwrap();
+            writeln!(writer, r#"{{"cmd": "command", "hyperspace": true}}"#).unwrap();
+            lines
+        });
+        let world = World::new_sandbox(1, vec2(400., 300.));
+        let input = remote.wait(&world, 0).unwrap();
+        assert!(input.hyperspace && input.is_idle());
+
+        let lines: Vec<Value> = client
+            .join()
+            .unwrap()
+            .iter()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines[1]["type"], "error");
+        assert_eq!(lines[2]["tick"], 0);
+        assert_eq!(lines[2]["arena"][0], 400.);
+    }
+}
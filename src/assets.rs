@@ -0,0 +1,196 @@
+//! Module gérant le chargement des ressources du jeu (police et images).
+//!
+//! Les fichiers sont cherchés dans le dossier de données configuré, puis à
+//! côté de l'exécutable (et dans ses dossiers parents, pour `target/debug`),
+//! puis dans le dossier courant. Une ressource introuvable ou invalide n'arrête
+//! pas le jeu : l'erreur est conservée pour être affichée et le jeu utilise la
+//! police par défaut ou des formes vectorielles à la place.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use macroquad::prelude::*;
+
+use crate::locale::Strings;
+
+/// Variable d'environnement pouvant indiquer le dossier de données
+pub const DATA_DIR_ENV: &str = "ASTEROID_DATA";
+
+/// Chemins des ressources, relatifs au dossier de données
+pub const FONT_PATH: &str = "font/BebasNeue-Regular.ttf";
+pub const BACKGROUND_PATH: &str = "img/bg_3.png";
+pub const SPACESHIP_PATH: &str = "img/image_vaisseau.png";
+pub const ASTEROID_PATH: &str = "img/image_asteroide.png";
+
+/// Nombre de dossiers parents de l'exécutable explorés
+const EXE_ANCESTORS: usize = 3;
+
+/// Ressources chargées au démarrage
+///
+/// # Champs
+/// * `font` - Police du jeu, `None` pour la police par défaut
+/// * `background` - Image de fond, `None` pour un fond noir
+/// * `spaceship` - Image du vaisseau, `None` pour un triangle
+/// * `asteroid` - Image des astéroïdes, `None` pour un polygone
+/// * `errors` - Erreurs rencontrées pendant le chargement
+pub struct Assets {
+    pub font: Option<Font>,
+    pub background: Option<Texture2D>,
+    pub spaceship: Option<Texture2D>,
+    pub asteroid: Option<Texture2D>,
+    pub errors: Vec<String>,
+}
+
+impl Assets {
+    /// Charge toutes les ressources du jeu
+    ///
+    /// # Arguments
+    /// * `data_dir` - Dossier de données configuré (ligne de commande ou configuration)
+    pub fn load(data_dir: Option<&str>) -> Self {
+        let dirs = search_dirs(data_dir);
+        let mut errors = Vec::new();
+
+        let font = report(
+            read_asset(&dirs, FONT_PATH).and_then(|bytes| {
+                load_ttf_font_from_bytes(&bytes).map_err(|e| format!("{FONT_PATH} : {e}"))
+            }),
+            &mut errors,
+        );
+        let mut texture = |path: &str| {
+            report(
+                read_asset(&dirs, path).and_then(|bytes| {
+                    Image::from_file_with_format(&bytes, None)
+                        .map(|image| Texture2D::from_image(&image))
+                        .map_err(|e| format!("{path} : {e}"))
+                }),
+                &mut errors,
+            )
+        };
+        let background = texture(BACKGROUND_PATH);
+        let spaceship = texture(SPACESHIP_PATH);
+        let asteroid = texture(ASTEROID_PATH);
+
+        Self {
+            font,
+            background,
+            spaceship,
+            asteroid,
+            errors,
+        }
+    }
+
+    /// Police à utiliser pour les textes (`None` pour la police par défaut)
+    pub fn font(&self) -> Option<&Font> {
+        self.font.as_ref()
+    }
+
+    /// Affiche les erreurs de chargement jusqu'à ce que le joueur appuie sur Entrée
+    ///
+    /// Ne fait rien si toutes les ressources ont été chargées.
+    ///
+    /// # Arguments
+    /// * `strings` - Textes de l'interface
+    pub async fn show_errors(&self, strings: &Strings) {
+        if self.errors.is_empty() {
+            return;
+        }
+        for error in &self.errors {
+            eprintln!("{}", strings.format("assets.missing", &[error]));
+        }
+
+        while !is_key_pressed(KeyCode::Enter) && !is_key_pressed(KeyCode::Escape) {
+            clear_background(BLACK);
+            let params = TextParams {
+                font_size: 20,
+                font: self.font(),
+                color: WHITE,
+                ..Default::default()
+            };
+            draw_text_ex(
+                strings.get("assets.errors"),
+                40.,
+                60.,
+                TextParams {
+                    color: RED,
+                    ..params.clone()
+                },
+            );
+            for (i, error) in self.errors.iter().enumerate() {
+                draw_text_ex(error, 40., 100. + i as f32 * 30., params.clone());
+            }
+            draw_text_ex(
+                &strings.format("assets.help", &[&DATA_DIR_ENV]),
+                40.,
+                screen_height() - 40.,
+                params.clone(),
+            );
+            next_frame().await;
+        }
+    }
+}
+
+/// Conserve la ressource ou ajoute l'erreur à la liste
+fn report<T>(result: Result<T, String>, errors: &mut Vec<String>) -> Option<T> {
+    result.map_err(|e| errors.push(e)).ok()
+}
+
+/// Lit le contenu d'une ressource
+fn read_asset(dirs: &[PathBuf], relative: &str) -> Result<Vec<u8>, String> {
+    let path =
+        resolve(dirs, relative).ok_or_else(|| format!("{relative} : fichier introuvable"))?;
+    fs::read(&path).map_err(|e| format!("{} : {e}", path.display()))
+}
+
+/// Dossiers dans lesquels les ressources sont cherchées, par ordre de priorité
+pub fn search_dirs(data_dir: Option<&str>) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(dir) = data_dir {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Ok(dir) = env::var(DATA_DIR_ENV) {
+        dirs.push(PathBuf::from(dir));
+    }
+    if let Some(exe_dir) = env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    {
+        dirs.extend(
+            exe_dir
+                .ancestors()
+                .take(EXE_ANCESTORS + 1)
+                .map(Path::to_path_buf),
+        );
+    }
+    if let Ok(dir) = env::current_dir() {
+        dirs.push(dir);
+    }
+    dirs
+}
+
+/// Cherche le premier dossier contenant la ressource
+///
+/// # Retourne
+/// Le chemin complet de la ressource ou `None` si elle est introuvable
+pub fn resolve(dirs: &[PathBuf], relative: &str) -> Option<PathBuf> {
+    dirs.iter()
+        .map(|dir| dir.join(relative))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn recherche_ressources() {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        let dirs = vec![PathBuf::from("/dossier/inexistant"), root.clone()];
+        assert_eq!(
+            resolve(&dirs, SPACESHIP_PATH),
+            Some(root.join(SPACESHIP_PATH))
+        );
+        assert_eq!(resolve(&dirs, "img/absente.png"), None);
+        assert!(read_asset(&dirs, "img/absente.png").is_err());
+    }
+}
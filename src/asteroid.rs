@@ -5,8 +5,9 @@
 
 use std::f32::consts::PI;
 
-use ::rand::{thread_rng, Rng};
+use ::rand::Rng;
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Représente un astéroïde dans le jeu
 ///
@@ -16,12 +17,17 @@ use macroquad::prelude::*;
 /// * `width` - Rayon de l'astéroïde
 /// * `collided` - État de collision de l'astéroïde
 /// * `touched` - État de touché de l'astéroïde
+/// * `shape` - Graine de la forme de l'astéroïde (mode de rendu vectoriel)
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Asteroid {
+    #[serde(with = "crate::save::vec2")]
     pub position: Vec2,
+    #[serde(with = "crate::save::vec2")]
     speed: Vec2,
     width: f32,
     collided: bool,
     touched: bool,
+    shape: u32,
 }
 
 impl Asteroid {
@@ -29,27 +35,39 @@ impl Asteroid {
     pub const ASTEROID_INIT_SIZE: f32 = 50.0;
 
     /// Crée un nouvel astéroïde avec une position et une vitesse aléatoires
-    pub fn new() -> Self {
+    ///
+    /// # Arguments
+    /// * `rng` - Générateur aléatoire de la partie
+    /// * `arena` - Dimensions de la zone de jeu
+    pub fn new(rng: &mut impl Rng, arena: Vec2) -> Self {
         Self {
-            position: Self::new_alea_pos(),
-            speed: Self::new_alea_speed(),
+            position: Self::new_alea_pos(rng, arena),
+            speed: Self::new_alea_speed(rng),
             width: Asteroid::ASTEROID_INIT_SIZE,
             collided: false,
             touched: false,
+            shape: rng.gen(),
         }
     }
 
     /// Crée un nouvel astéroïde avec une vitesse aléatoires, une position et une taille donné
-    pub fn new2(position: Vec2, width: f32) -> Self {
+    pub fn new2(rng: &mut impl Rng, position: Vec2, width: f32) -> Self {
         Self {
             position,
-            speed: Self::new_alea_speed(),
+            speed: Self::new_alea_speed(rng),
             width,
             collided: false,
             touched: false,
+            shape: rng.gen(),
         }
     }
 
+    /// Multiplie la vitesse de l'astéroïde (voir `CustomDifficulty::speed`)
+    pub fn with_speed_factor(mut self, factor: f32) -> Self {
+        self.speed *= factor;
+        self
+    }
+
     pub fn get_position(&self) -> Vec2 {
         self.position
     }
@@ -66,6 +84,10 @@ impl Asteroid {
         self.touched
     }
 
+    pub fn get_shape(&self) -> u32 {
+        self.shape
+    }
+
     /// Change la valeur de collided
     pub fn set_collision(&mut self, bool: bool) {
         self.collided = bool;
@@ -80,20 +102,18 @@ impl Asteroid {
     }
 
     /// Génère une position aléatoire près de l'un des bords.
-    fn new_alea_pos() -> Vec2 {
-        let mut rng = thread_rng();
-
+    fn new_alea_pos(rng: &mut impl Rng, arena: Vec2) -> Vec2 {
         let nearpos: f32 = rng.gen_range(Self::ASTEROID_INIT_SIZE / 2.0..=Self::ASTEROID_INIT_SIZE);
         let nearside = rng.gen_range(1..=4); // 1 = top, 2 = right, 3 = down, 4 = left
         let xpos: f32 = match nearside {
-            2 => screen_width() - nearpos,
+            2 => arena.x - nearpos,
             4 => nearpos,
-            _ => rng.gen_range(0.0..=screen_width()),
+            _ => rng.gen_range(0.0..=arena.x),
         };
         let ypos: f32 = match nearside {
             1 => nearpos,
-            3 => screen_height() - nearpos,
-            _ => rng.gen_range(0.0..=screen_height()),
+            3 => arena.y - nearpos,
+            _ => rng.gen_range(0.0..=arena.y),
         };
         vec2(xpos, ypos)
     }
@@ -103,17 +123,15 @@ impl Asteroid {
     ///            = π/2: vers le haut
     ///            = π: vers le gauche
     ///            = 3π/2: vers le bas
-    fn new_alea_speed() -> Vec2 {
-        let mut rng = thread_rng();
-
+    fn new_alea_speed(rng: &mut impl Rng) -> Vec2 {
         let angle: f32 = rng.gen_range(0.0..=2.0 * PI);
         Vec2::from_angle(angle)
     }
 
-    /// Fait apparaitre l'astéroïde de l'autre coté de l'écran si il sort
-    pub fn bound_pos(&mut self) -> Vec2 {
-        self.position.x = Self::bound_to(self.position.x, screen_width());
-        self.position.y = Self::bound_to(self.position.y, screen_height());
+    /// Fait apparaitre l'astéroïde de l'autre coté de l'arène si il sort
+    pub fn bound_pos(&mut self, arena: Vec2) -> Vec2 {
+        self.position.x = Self::bound_to(self.position.x, arena.x);
+        self.position.y = Self::bound_to(self.position.y, arena.y);
         self.position
     }
 
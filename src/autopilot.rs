@@ -0,0 +1,248 @@
+//! Module contenant un pilote automatique du vaisseau.
+//!
+//! Le pilote produit les mêmes commandes (`Input`) que le clavier à partir de
+//! l'état de la partie. Il sert de démonstration sur l'écran du menu et à
+//! tester l'équilibrage des difficultés en mode headless.
+//!
+//! À chaque frame :
+//! * il cherche l'astéroïde qui va passer le plus tôt trop près du vaisseau et
+//!   accélère (en avant ou en arrière) pour s'en écarter ;
+//! * il calcule le point d'interception de chaque cible par un missile (les
+//!   astéroïdes, ou le vaisseau adverse en duel), tourne vers la plus rapide à
+//!   atteindre et tire une fois aligné.
+
+use std::f32::consts::PI;
+
+use macroquad::prelude::*;
+
+use crate::input::{Controller, Input};
+use crate::spaceship::Spaceship;
+use crate::world::{wrapped_delta, GameMode, World, BULLET_SPEED};
+
+/// Nombre de frames à l'avance pendant lesquelles une collision est anticipée
+const DANGER_HORIZON: f32 = 90.0;
+
+/// Distance gardée en plus des rayons de collision lors d'un évitement
+const SAFETY_MARGIN: f32 = 20.0;
+
+/// Pilote automatique du vaisseau
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Autopilot;
+
+impl Autopilot {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Controller for Autopilot {
+    fn input(&mut self, world: &World, player: usize) -> Input {
+        let mut input = Input::default();
+        if !world.players[player].is_alive() {
+            return input;
+        }
+        let spaceship = &world.players[player].spaceship;
+        let orientation = spaceship.get_orientation();
+
+        // Évitement : pousser le vaisseau loin de la menace la plus proche
+        if let Some(away) = escape_direction(world, spaceship) {
+            if direction(orientation).dot(away) >= 0. {
+                input.up = true;
+            } else {
+                input.down = true;
+            }
+        }
+
+        // Visée : tourner vers le point d'interception et tirer une fois aligné
+        if let Some((angle, tolerance)) = aim(world, player) {
+            let diff = angle_diff(angle, orientation);
+            if diff > Spaceship::SPACESHIP_ROTATION / 2. {
+                input.right = true;
+            } else if diff < -Spaceship::SPACESHIP_ROTATION / 2. {
+                input.left = true;
+            }
+            input.fire = diff.abs() <= tolerance;
+        }
+        input
+    }
+}
+
+/// Direction vers laquelle pointe une orientation (0 = vers le haut)
+fn direction(orientation: f32) -> Vec2 {
+    vec2(orientation.sin(), -orientation.cos())
+}
+
+/// Écart signé entre deux angles, ramené dans `[-PI, PI]`
+fn angle_diff(target: f32, current: f32) -> f32 {
+    (target - current + PI).rem_euclid(2. * PI) - PI
+}
+
+/// Direction dans laquelle le vaisseau doit accélérer pour éviter une collision
+///
+/// # Retourne
+/// `None` si aucun astéroïde ne menace le vaisseau dans les `DANGER_HORIZON`
+/// prochaines frames
+fn escape_direction(world: &World, spaceship: &Spaceship) -> Option<Vec2> {
+    world
+        .asteroids
+        .iter()
+        .filter_map(|asteroid| {
+            // Mouvement de l'astéroïde vu depuis le vaisseau
+            let delta = wrapped_delta(
+                spaceship.get_position(),
+                asteroid.get_position(),
+                world.size,
+            );
+            let speed = asteroid.get_speed() - spaceship.get_speed();
+            let time = if speed.length_squared() > f32::EPSILON {
+                (-delta.dot(speed) / speed.length_squared()).clamp(0., DANGER_HORIZON)
+            } else {
+                0.
+            };
+            let closest = delta + speed * time;
+            let danger = spaceship.get_height() + asteroid.get_width() + SAFETY_MARGIN;
+            if closest.length() >= danger {
+                return None;
+            }
+            // De face, on s'écarte perpendiculairement à la trajectoire
+            let away = if closest.length() > 1. {
+                -closest.normalize()
+            } else {
+                speed.perp().normalize_or_zero()
+            };
+            Some((time, away))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, away)| away)
+}
+
+/// Cibles du pilote : position, vitesse et rayon
+///
+/// En duel, le pilote vise les vaisseaux adverses (les astéroïdes sont
+/// indestructibles) ; sinon il vise les astéroïdes.
+fn targets(world: &World, player: usize) -> Vec<(Vec2, Vec2, f32)> {
+    match world.mode {
+        GameMode::Versus => world
+            .players
+            .iter()
+            .enumerate()
+            .filter(|(i, other)| *i != player && other.is_alive())
+            .map(|(_, other)| {
+                let spaceship = &other.spaceship;
+                (
+                    spaceship.get_position(),
+                    spaceship.get_speed(),
+                    spaceship.get_height(),
+                )
+            })
+            .collect(),
+        GameMode::Classic => world
+            .asteroids
+            .iter()
+            .map(|asteroid| {
+                (
+                    asteroid.get_position(),
+                    asteroid.get_speed(),
+                    asteroid.get_width(),
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Choisit la cible à viser
+///
+/// Les missiles ne traversent pas les bords : seuls les points d'interception
+/// à l'intérieur de l'arène sont retenus. La cible choisie est celle qui sera
+/// touchée le plus tôt en comptant le temps pour tourner le vaisseau.
+///
+/// # Retourne
+/// L'orientation à prendre et l'écart d'angle toléré pour tirer
+fn aim(world: &World, player: usize) -> Option<(f32, f32)> {
+    let spaceship = &world.players[player].spaceship;
+    let origin = spaceship.get_position();
+    let arena = Rect::new(0., 0., world.size.x, world.size.y);
+    targets(world, player)
+        .into_iter()
+        .filter_map(|(position, speed, radius)| {
+            let time = intercept_time(position - origin, speed, BULLET_SPEED)?;
+            let target = position + speed * time;
+            if !arena.contains(target) {
+                return None;
+            }
+            let delta = target - origin;
+            let angle = delta.x.atan2(-delta.y);
+            let tolerance = (radius / delta.length().max(1.)).min(1.).asin() * 0.8;
+            let turn = angle_diff(angle, spaceship.get_orientation()).abs()
+                / Spaceship::SPACESHIP_ROTATION;
+            Some((turn + time, angle, tolerance))
+        })
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .map(|(_, angle, tolerance)| (angle, tolerance))
+}
+
+/// Temps au bout duquel un missile tiré maintenant rejoint une cible mobile
+///
+/// Résout `|delta + speed * t| = bullet_speed * t`.
+///
+/// # Arguments
+/// * `delta` - Position de la cible par rapport au tireur
+/// * `speed` - Vitesse de la cible
+/// * `bullet_speed` - Vitesse du missile
+fn intercept_time(delta: Vec2, speed: Vec2, bullet_speed: f32) -> Option<f32> {
+    let a = speed.length_squared() - bullet_speed * bullet_speed;
+    let b = 2. * delta.dot(speed);
+    let c = delta.length_squared();
+    if a.abs() < f32::EPSILON {
+        return (b < 0.).then(|| -c / b);
+    }
+    let discriminant = b * b - 4. * a * c;
+    if discriminant < 0. {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    [(-b - root) / (2. * a), (-b + root) / (2. * a)]
+        .into_iter()
+        .filter(|t| *t >= 0.)
+        .min_by(|a, b| a.total_cmp(b))
+}
+
+#[cfg(test)]
+mod test {
+    use ::rand::rngs::SmallRng;
+    use ::rand::SeedableRng;
+
+    use super::*;
+    use crate::asteroid::Asteroid;
+    use crate::world::DEFAULT_ARENA;
+    use crate::Difficulty;
+
+    #[test]
+    fn point_interception() {
+        // Cible immobile à 100 pixels : atteinte en 25 frames
+        let time = intercept_time(vec2(0., -100.), Vec2::ZERO, 4.).unwrap();
+        assert!((time - 25.).abs() < 1e-3);
+
+        // Cible qui traverse : le missile et la cible se retrouvent au même point
+        let (delta, speed) = (vec2(-100., -100.), vec2(1., 0.));
+        let time = intercept_time(delta, speed, 4.).unwrap();
+        assert!(((delta + speed * time).length() - 4. * time).abs() < 1e-2);
+
+        assert!((angle_diff(0.1, 2. * PI - 0.1) - 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn vise_et_detruit() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let mut world = World::new(0, Difficulty::Easy, DEFAULT_ARENA);
+        // Un astéroïde sur le côté, hors de la trajectoire du vaisseau
+        world.asteroids = vec![Asteroid::new2(&mut rng, vec2(650., 300.), 15.)];
+
+        let mut autopilot = Autopilot::new();
+        for _ in 0..300 {
+            let input = autopilot.input(&world, 0);
+            world.step(&input);
+        }
+        assert!(world.score() > 0);
+    }
+}
@@ -4,28 +4,37 @@
 //! leur déplacement et leur détection de sortie d'écran.
 
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::spaceship::Spaceship;
 
 /// Représente un projectile dans le jeu
 ///
 /// # Champs
 /// * `position` - Position actuelle du projectile
 /// * `speed` - Vecteur vitesse du projectile
-///
+/// * `touched` - Le projectile a touché un objet et doit disparaître
+/// * `owner` - Numéro du joueur qui a tiré
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Bullet {
+    #[serde(with = "crate::save::vec2")]
     pub position: Vec2,
+    #[serde(with = "crate::save::vec2")]
     speed: Vec2,
     touched: bool,
+    owner: usize,
 }
 
 impl Bullet {
     pub const BULLET_INIT_SIZE: f32 = 5.0;
 
     /// Crée un nouveau projectile
-    pub fn new(position: Vec2, speed: Vec2) -> Self {
+    pub fn new(position: Vec2, speed: Vec2, owner: usize) -> Self {
         Self {
             position,
             speed,
             touched: false,
+            owner,
         }
     }
 
@@ -41,6 +50,10 @@ impl Bullet {
         self.speed
     }
 
+    pub fn get_owner(&self) -> usize {
+        self.owner
+    }
+
     pub fn get_touched(&self) -> bool {
         self.touched
     }
@@ -53,12 +66,26 @@ impl Bullet {
         }
     }
 
-    /// Vérifie si un missile est en dehors des bordures de l'écran
-    pub fn is_out(&self) -> bool {
+    /// Vérifie si un missile est en dehors des bordures de l'arène
+    pub fn is_out(&self, arena: Vec2) -> bool {
         self.position.x < 0.0
-            || self.position.x > screen_width()
+            || self.position.x > arena.x
             || self.position.y < 0.0
-            || self.position.y > screen_height()
+            || self.position.y > arena.y
+    }
+
+    /// Vérifie si le projectile touche un cercle
+    ///
+    /// # Arguments
+    /// * `center` - Centre du cercle
+    /// * `radius` - Rayon du cercle
+    pub fn touches(&self, center: Vec2, radius: f32) -> bool {
+        self.position.distance(center) < self.get_size() + radius
+    }
+
+    /// Vérifie si le projectile touche un vaisseau
+    pub fn hits(&self, spaceship: &Spaceship) -> bool {
+        self.touches(spaceship.get_position(), spaceship.get_height())
     }
 
     pub fn handle_collision(&mut self) {
@@ -2,77 +2,184 @@
 //!
 //! Ce module implémente les boutons cliquables de l'interface,
 //! incluant leur affichage et leur interaction avec la souris.
+//! Les couleurs de survol, d'appui et de désactivation font partie du style
+//! du bouton : l'appelant n'a plus à recolorer le bouton lui-même.
+//! Un bouton qui a le focus est encadré et s'active aussi avec Entrée ou le
+//! bouton A de la manette.
 
 use macroquad::prelude::*;
 
+use crate::ui::{draw_focus, NavAction, Navigation};
+
+/// Couleurs et taille du texte d'un bouton
+///
+/// # Champs
+/// * `color` - Couleur au repos
+/// * `hover` - Couleur survolée par la souris
+/// * `pressed` - Couleur pendant l'appui
+/// * `disabled` - Couleur d'un bouton désactivé
+/// * `text` - Couleur du texte
+/// * `font_size` - Taille du texte
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ButtonStyle {
+    pub color: Color,
+    pub hover: Color,
+    pub pressed: Color,
+    pub disabled: Color,
+    pub text: Color,
+    pub font_size: u16,
+}
+
+impl ButtonStyle {
+    /// Style d'un bouton de la couleur donnée, plus sombre survolé puis appuyé
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            hover: darken(color, 0.75),
+            pressed: darken(color, 0.5),
+            disabled: Color::new(0.3, 0.3, 0.3, 0.6),
+            text: BLACK,
+            font_size: 20,
+        }
+    }
+}
+
+/// Assombrit une couleur sans changer sa transparence
+fn darken(color: Color, factor: f32) -> Color {
+    Color::new(
+        color.r * factor,
+        color.g * factor,
+        color.b * factor,
+        color.a,
+    )
+}
+
 /// Représente un bouton cliquable de l'interface
 ///
 /// # Champs
-/// * `position` - Position du bouton
-/// * `width` - Largeur du bouton
-/// * `height` - Hauteur du bouton
-/// * `color` - Couleur du bouton
+/// * `rect` - Position et taille du bouton
+/// * `style` - Couleurs du bouton selon son état
 /// * `text` - Texte affiché sur le bouton
+/// * `enabled` - Le bouton peut être cliqué
+/// * `focused` - Le bouton a le focus du clavier et de la manette
 pub struct Button {
-    position: Vec2,
-    width: f32,
-    height: f32,
-    color: Color,
+    rect: Rect,
+    style: ButtonStyle,
     text: String,
+    enabled: bool,
+    focused: bool,
 }
 
 impl Button {
     /// Crée un nouveau bouton
     pub fn new(position: Vec2, width: f32, height: f32, color: Color, text: &str) -> Self {
-        Button {
-            position,
-            width,
-            height,
+        Self::from_rect(
+            Rect::new(position.x, position.y, width, height),
             color,
+            text,
+        )
+    }
+
+    /// Crée un bouton occupant le rectangle donné (voir `ui::Column`)
+    pub fn from_rect(rect: Rect, color: Color, text: &str) -> Self {
+        Button {
+            rect,
+            style: ButtonStyle::new(color),
             text: text.to_string(),
+            enabled: true,
+            focused: false,
         }
     }
 
-    /// Changer la couleur
-    pub fn set_color(&mut self, color: Color) {
-        self.color = color;
+    /// Déplace le bouton, quand la fenêtre change de taille
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    /// Change le texte du bouton, quand la langue de l'interface change
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    /// Remplace le style du bouton
+    pub fn with_style(mut self, style: ButtonStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Active ou désactive le bouton
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Donne ou retire le focus
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Couleur du bouton dans son état actuel
+    fn color(&self) -> Color {
+        if !self.enabled {
+            self.style.disabled
+        } else if self.is_hovered() && is_mouse_button_down(MouseButton::Left) {
+            self.style.pressed
+        } else if self.is_hovered() {
+            self.style.hover
+        } else {
+            self.style.color
+        }
     }
 
     /// Dessiner un bouton avec le texte centré
-    pub fn draw(&self, font: &Font) {
-        draw_rectangle(
-            self.position.x,
-            self.position.y,
-            self.width,
-            self.height,
-            self.color,
-        );
+    ///
+    /// `font` vaut `None` pour utiliser la police par défaut
+    pub fn draw(&self, font: Option<&Font>) {
+        let rect = self.rect;
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, self.color());
 
-        let text_size = measure_text(&self.text, Some(font), 20, 1.0);
+        let font_size = self.style.font_size;
+        let text_size = measure_text(&self.text, font, font_size, 1.0);
         draw_text_ex(
             &self.text,
-            self.position.x + (self.width - text_size.width) / 2.0,
-            self.position.y + (self.height + text_size.height) / 2.0,
+            rect.x + (rect.w - text_size.width) / 2.0,
+            rect.y + (rect.h + text_size.height) / 2.0,
             TextParams {
-                font_size: 20,
-                font: Some(font),
-                color: BLACK,
+                font_size,
+                font,
+                color: self.style.text,
                 ..Default::default()
             },
         );
+        if self.focused {
+            draw_focus(rect);
+        }
     }
 
     /// Détecte si un bouton est survolé
     pub fn is_hovered(&self) -> bool {
-        let mouse_pos = mouse_position();
-        mouse_pos.0 >= self.position.x
-            && mouse_pos.0 <= self.position.x + self.width
-            && mouse_pos.1 >= self.position.y
-            && mouse_pos.1 <= self.position.y + self.height
+        self.rect.contains(mouse_position().into())
     }
 
-    /// Détecte si un bouton est cliqué
+    /// Détecte si un bouton actif est cliqué
     pub fn is_clicked(&self) -> bool {
-        self.is_hovered() && is_mouse_button_pressed(MouseButton::Left)
+        self.enabled && self.is_hovered() && is_mouse_button_pressed(MouseButton::Left)
+    }
+
+    /// Détecte si un bouton actif est cliqué, ou activé alors qu'il a le focus
+    pub fn is_activated(&self, nav: &Navigation) -> bool {
+        self.is_clicked() || (self.enabled && self.focused && nav.pressed(NavAction::Activate))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn style_par_defaut() {
+        let style = ButtonStyle::new(Color::new(1., 0.5, 0.25, 1.));
+        assert_eq!(style.hover, Color::new(0.75, 0.375, 0.1875, 1.));
+        assert_eq!(style.pressed, Color::new(0.5, 0.25, 0.125, 1.));
+        assert_eq!(style.text, BLACK);
     }
 }
@@ -0,0 +1,425 @@
+//! Module gérant les options de la ligne de commande.
+//!
+//! Les options passées en ligne de commande sont prioritaires sur celles
+//! du fichier de configuration (`--config`), elles-mêmes prioritaires sur les
+//! préférences enregistrées depuis l'écran des paramètres.
+
+use crate::config::{parse_value, Config};
+use crate::net::DEFAULT_PORT;
+use crate::render::RenderMode;
+use crate::rollback::DEFAULT_INPUT_DELAY;
+use crate::settings::{Language, Settings};
+use crate::world::{DEFAULT_ARENA, MAX_PLAYERS};
+use crate::Difficulty;
+
+/// Texte d'aide affiché par `--help`
+pub const USAGE: &str = "\
+Usage : asteroid [OPTIONS]
+
+Options :
+  --seed N              Graine du générateur aléatoire
+  --difficulty NIVEAU   Lance directement une partie (easy, medium, hard, ou code
+                        d'une difficulté personnalisée comme 6-150-10-500-4-2-3)
+  --width W             Largeur de la fenêtre (défaut : 800)
+  --height H            Hauteur de la fenêtre (défaut : 600)
+  --fullscreen          Plein écran
+  --players N           Nombre de joueurs : 1 ou 2 (coopération, WASD et F)
+  --friendly-fire       Les missiles touchent aussi le coéquipier
+  --versus N            Duel à deux joueurs au meilleur de N manches
+  --host                Ouvre une partie en réseau à deux (coopération, ou duel avec --versus)
+  --port N              Port de la partie en réseau (défaut : 7777)
+  --join ADRESSE        Rejoint une partie en réseau (adresse[:port])
+  --peer ADRESSE        Partie en pair à pair avec retour en arrière (adresse[:port])
+  --input-delay N       Retard des commandes locales en pair à pair (défaut : 2 frames)
+  --loopback N          Simule N frames de deux pairs dans le même processus
+  --broadcast PORT      Diffuse la partie jouée aux spectateurs sur ce port TCP
+  --watch ADRESSE       Regarde une partie diffusée (adresse[:port], port 7778 par défaut)
+  --api PORT            Un bot pilote un vaisseau par l'API JSON sur ce port TCP local
+  --api-player N        Vaisseau de la partie piloté par le bot (défaut : 1)
+  --lockstep            La partie attend la commande du bot à chaque frame
+  --latency MS          Retard simulé des paquets envoyés en réseau
+  --jitter MS           Variation aléatoire simulée du retard des paquets
+  --packet-loss PCT     Pourcentage simulé de paquets perdus
+  --render MODE         Mode de rendu : sprites (défaut) ou vector
+  --language CODE       Langue de l'interface : fr (défaut) ou en
+  --config FICHIER      Fichier de configuration (clé = valeur)
+  --data-dir DOSSIER    Dossier contenant les ressources (font/, img/)
+  --replay FICHIER      Rejoue une partie enregistrée
+  --record FICHIER      Enregistre la partie jouée dans un replay
+  --save-file FICHIER   Fichier de sauvegarde du menu pause (touche P)
+  --autopilot           Le vaisseau est piloté automatiquement
+  --balance N           Simule N parties par difficulté avec le pilote automatique
+  --tournament N        Tournoi : N parties par bot et par difficulté, sans fenêtre
+  --bot COMMANDE        Bot du tournoi (programme parlant le protocole de l'API, ou autopilot)
+  --report FICHIER      Rapport du tournoi en CSV, ou en JSON si le fichier finit par .json
+  --no-demo             Pas de partie de démonstration derrière le menu
+  --debug               Affiche l'overlay de débogage au démarrage (touche F3)
+  --headless            Simule sans fenêtre et affiche un résumé
+  --gym                 Environnement d'apprentissage JSON sur l'entrée et la sortie standard
+  --frames N            Nombre de frames simulées en mode headless (défaut : 3600)
+  --snapshot FICHIER    Enregistre la dernière frame headless dans une image PNG
+  --ascii               Affiche la dernière frame en mode texte après le résumé headless
+  -h, --help            Affiche cette aide";
+
+/// Nombre de frames simulées par défaut en mode headless (une minute)
+pub const DEFAULT_FRAMES: u64 = 3600;
+
+/// Options de lancement du jeu
+///
+/// # Champs
+/// * `seed` - Graine imposée, sinon une graine aléatoire est tirée
+/// * `difficulty` - Difficulté de départ, le menu est sauté si présente
+/// * `width` / `height` - Taille de la fenêtre ou de l'arène headless
+/// * `fullscreen` - Lancer en plein écran
+/// * `players` - Nombre de joueurs
+/// * `friendly_fire` - Tir allié en coopération
+/// * `versus` - Nombre de manches d'un duel, `None` hors duel
+/// * `host` - Ouvrir une partie en réseau
+/// * `port` - Port de la partie en réseau
+/// * `join` - Adresse de la partie en réseau à rejoindre
+/// * `peer` - Adresse du pair d'une partie en pair à pair
+/// * `input_delay` - Retard des commandes locales en pair à pair, en frames
+/// * `loopback` - Nombre de frames simulées par deux pairs dans le même processus
+/// * `broadcast` - Port TCP de diffusion de la partie aux spectateurs
+/// * `watch` - Adresse d'une partie diffusée à regarder
+/// * `api` - Port TCP local de l'API de pilotage des bots
+/// * `api_player` - Numéro (à partir de 1) du vaisseau piloté par le bot
+/// * `lockstep` - Attendre la commande du bot à chaque frame
+/// * `latency` - Retard simulé des paquets envoyés, en millisecondes
+/// * `jitter` - Variation aléatoire maximale du retard simulé, en millisecondes
+/// * `packet_loss` - Pourcentage simulé de paquets perdus
+/// * `render_mode` - Mode de rendu (images ou contours vectoriels)
+/// * `language` - Langue de l'interface
+/// * `config` - Chemin du fichier de configuration
+/// * `data_dir` - Dossier contenant les ressources
+/// * `replay` - Chemin d'un replay à rejouer
+/// * `record` - Chemin où enregistrer la partie
+/// * `save_file` - Chemin de la sauvegarde, sinon dans le dossier de l'utilisateur
+/// * `autopilot` - Piloter le vaisseau automatiquement
+/// * `balance` - Nombre de parties simulées par difficulté pour tester l'équilibrage
+/// * `tournament` - Nombre de parties par bot et par difficulté d'un tournoi
+/// * `bots` - Commandes des bots du tournoi
+/// * `report` - Chemin du rapport du tournoi, sinon affiché en CSV
+/// * `demo` - Jouer une partie de démonstration derrière le menu
+/// * `debug` - Afficher l'overlay de débogage au démarrage
+/// * `headless` - Simuler sans fenêtre
+/// * `gym` - Servir l'environnement d'apprentissage sur l'entrée et la sortie standard
+/// * `frames` - Nombre de frames à simuler en mode headless
+/// * `snapshot` - Chemin de l'image PNG de la dernière frame en mode headless
+/// * `ascii` - Afficher la dernière frame en mode texte en mode headless
+/// * `help` - Afficher l'aide
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    pub seed: Option<u64>,
+    pub difficulty: Option<Difficulty>,
+    pub width: u32,
+    pub height: u32,
+    pub fullscreen: bool,
+    pub players: usize,
+    pub friendly_fire: bool,
+    pub versus: Option<u32>,
+    pub host: bool,
+    pub port: u16,
+    pub join: Option<String>,
+    pub peer: Option<String>,
+    pub input_delay: u32,
+    pub loopback: Option<u64>,
+    pub broadcast: Option<u16>,
+    pub watch: Option<String>,
+    pub api: Option<u16>,
+    pub api_player: usize,
+    pub lockstep: bool,
+    pub latency: u64,
+    pub jitter: u64,
+    pub packet_loss: f32,
+    pub render_mode: RenderMode,
+    pub language: Language,
+    pub config: Option<String>,
+    pub data_dir: Option<String>,
+    pub replay: Option<String>,
+    pub record: Option<String>,
+    pub save_file: Option<String>,
+    pub autopilot: bool,
+    pub balance: Option<u32>,
+    pub tournament: Option<u32>,
+    pub bots: Vec<String>,
+    pub report: Option<String>,
+    pub demo: bool,
+    pub debug: bool,
+    pub headless: bool,
+    pub gym: bool,
+    pub frames: u64,
+    pub snapshot: Option<String>,
+    pub ascii: bool,
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            difficulty: None,
+            width: DEFAULT_ARENA.x as u32,
+            height: DEFAULT_ARENA.y as u32,
+            fullscreen: false,
+            players: 1,
+            friendly_fire: false,
+            versus: None,
+            host: false,
+            port: DEFAULT_PORT,
+            join: None,
+            peer: None,
+            input_delay: DEFAULT_INPUT_DELAY,
+            loopback: None,
+            broadcast: None,
+            watch: None,
+            api: None,
+            api_player: 1,
+            lockstep: false,
+            latency: 0,
+            jitter: 0,
+            packet_loss: 0.,
+            render_mode: RenderMode::Sprites,
+            language: Language::French,
+            config: None,
+            data_dir: None,
+            replay: None,
+            record: None,
+            save_file: None,
+            autopilot: false,
+            balance: None,
+            tournament: None,
+            bots: Vec::new(),
+            report: None,
+            demo: true,
+            debug: false,
+            headless: false,
+            gym: false,
+            frames: DEFAULT_FRAMES,
+            snapshot: None,
+            ascii: false,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    /// Analyse les arguments de la ligne de commande (sans le nom du programme)
+    ///
+    /// Les préférences du joueur servent de base aux options de la fenêtre,
+    /// sauf dans les modes sans fenêtre dont l'arène garde sa taille par défaut.
+    /// Si `--config` est présent, le fichier est chargé puis les autres
+    /// options de la ligne de commande sont appliquées par-dessus.
+    pub fn parse<I: IntoIterator<Item = String>>(
+        args: I,
+        settings: &Settings,
+    ) -> Result<Self, String> {
+        let args: Vec<String> = args.into_iter().collect();
+
+        let mut options = Options::default();
+        if !is_windowless(&args) {
+            options.apply_settings(settings);
+        }
+        if let Some(path) = find_config(&args)? {
+            options.apply_config(&Config::load(&path)?);
+            options.config = Some(path);
+        }
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("valeur manquante pour '{arg}'"))
+            };
+            match arg.as_str() {
+                "--seed" => options.seed = Some(parse_value("--seed", &value()?)?),
+                "--difficulty" => options.difficulty = Some(value()?.parse()?),
+                "--width" => options.width = parse_value("--width", &value()?)?,
+                "--height" => options.height = parse_value("--height", &value()?)?,
+                "--fullscreen" => options.fullscreen = true,
+                "--players" => options.players = parse_value("--players", &value()?)?,
+                "--friendly-fire" => options.friendly_fire = true,
+                "--versus" => options.versus = Some(parse_value("--versus", &value()?)?),
+                "--host" => options.host = true,
+                "--port" => options.port = parse_value("--port", &value()?)?,
+                "--join" => options.join = Some(value()?),
+                "--peer" => options.peer = Some(value()?),
+                "--input-delay" => options.input_delay = parse_value("--input-delay", &value()?)?,
+                "--loopback" => options.loopback = Some(parse_value("--loopback", &value()?)?),
+                "--broadcast" => options.broadcast = Some(parse_value("--broadcast", &value()?)?),
+                "--watch" => options.watch = Some(value()?),
+                "--api" => options.api = Some(parse_value("--api", &value()?)?),
+                "--api-player" => options.api_player = parse_value("--api-player", &value()?)?,
+                "--lockstep" => options.lockstep = true,
+                "--latency" => options.latency = parse_value("--latency", &value()?)?,
+                "--jitter" => options.jitter = parse_value("--jitter", &value()?)?,
+                "--packet-loss" => options.packet_loss = parse_value("--packet-loss", &value()?)?,
+                "--render" => options.render_mode = value()?.parse()?,
+                "--language" => options.language = value()?.parse()?,
+                "--config" => {
+                    value()?;
+                }
+                "--data-dir" => options.data_dir = Some(value()?),
+                "--replay" => options.replay = Some(value()?),
+                "--record" => options.record = Some(value()?),
+                "--save-file" => options.save_file = Some(value()?),
+                "--autopilot" => options.autopilot = true,
+                "--balance" => options.balance = Some(parse_value("--balance", &value()?)?),
+                "--tournament" => {
+                    options.tournament = Some(parse_value("--tournament", &value()?)?)
+                }
+                "--bot" => options.bots.push(value()?),
+                "--report" => options.report = Some(value()?),
+                "--no-demo" => options.demo = false,
+                "--debug" => options.debug = true,
+                "--headless" => options.headless = true,
+                "--gym" => options.gym = true,
+                "--frames" => options.frames = parse_value("--frames", &value()?)?,
+                "--snapshot" => options.snapshot = Some(value()?),
+                "--ascii" => options.ascii = true,
+                "-h" | "--help" => options.help = true,
+                _ => return Err(format!("option inconnue '{arg}'")),
+            }
+        }
+
+        if !(1..=MAX_PLAYERS).contains(&options.players) {
+            return Err(format!(
+                "le nombre de joueurs doit être entre 1 et {MAX_PLAYERS}"
+            ));
+        }
+        let network = [
+            options.host,
+            options.join.is_some(),
+            options.peer.is_some(),
+            options.watch.is_some(),
+        ];
+        if network.iter().filter(|&&used| used).count() > 1 {
+            return Err(
+                "'--host', '--join', '--peer' et '--watch' ne peuvent pas être utilisés ensemble"
+                    .to_string(),
+            );
+        }
+        if options.api.is_some() && network.contains(&true) {
+            return Err("'--api' ne peut pas être utilisé en réseau".to_string());
+        }
+        if options.api.is_none() && options.lockstep {
+            return Err("'--lockstep' nécessite '--api'".to_string());
+        }
+        // Un duel se joue toujours avec deux vaisseaux
+        let ships = if options.versus.is_some() {
+            MAX_PLAYERS
+        } else {
+            options.players
+        };
+        if !(1..=ships).contains(&options.api_player) {
+            return Err(format!(
+                "le vaisseau piloté par le bot doit être entre 1 et {ships}"
+            ));
+        }
+        if !(0.0..=100.0).contains(&options.packet_loss) {
+            return Err("le pourcentage de paquets perdus doit être entre 0 et 100".to_string());
+        }
+        if options.width == 0 || options.height == 0 {
+            return Err("la taille de la fenêtre doit être positive".to_string());
+        }
+        Ok(options)
+    }
+
+    /// Applique les préférences du joueur concernant la fenêtre
+    fn apply_settings(&mut self, settings: &Settings) {
+        self.width = settings.width;
+        self.height = settings.height;
+        self.fullscreen = settings.fullscreen;
+        self.render_mode = settings.render_mode;
+        self.language = settings.language;
+    }
+
+    /// Applique les valeurs d'un fichier de configuration
+    fn apply_config(&mut self, config: &Config) {
+        self.seed = config.seed.or(self.seed);
+        self.difficulty = config.difficulty.or(self.difficulty);
+        self.width = config.width.unwrap_or(self.width);
+        self.height = config.height.unwrap_or(self.height);
+        self.fullscreen = config.fullscreen.unwrap_or(self.fullscreen);
+        self.render_mode = config.render_mode.unwrap_or(self.render_mode);
+        self.language = config.language.unwrap_or(self.language);
+        self.data_dir = config.data_dir.clone().or(self.data_dir.take());
+    }
+}
+
+/// Vérifie si les arguments lancent un mode sans fenêtre
+fn is_windowless(args: &[String]) -> bool {
+    args.iter().any(|arg| {
+        matches!(
+            arg.as_str(),
+            "--headless" | "--gym" | "--balance" | "--tournament" | "--loopback"
+        )
+    })
+}
+
+/// Cherche l'option `--config` parmi les arguments
+fn find_config(args: &[String]) -> Result<Option<String>, String> {
+    match args.iter().position(|arg| arg == "--config") {
+        Some(i) => args
+            .get(i + 1)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| "valeur manquante pour '--config'".to_string()),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    fn parse(line: &str) -> Result<Options, String> {
+        Options::parse(args(line), &Settings::default())
+    }
+
+    #[test]
+    fn analyse_options() {
+        let options = parse("--seed 12 --difficulty hard --headless --frames 10").unwrap();
+        assert_eq!(options.seed, Some(12));
+        assert_eq!(options.difficulty, Some(Difficulty::Hard));
+        assert!(options.headless);
+        assert_eq!(options.frames, 10);
+        assert_eq!(options.width, 800);
+        let options = parse("--difficulty 6-150-10-500-4-2").unwrap();
+        assert_eq!(options.difficulty.map(|d| d.params().speed), Some(150));
+
+        assert!(parse("--seed").is_err());
+        assert!(parse("--width 0").is_err());
+        assert!(parse("--players 3").is_err());
+        assert!(parse("--inconnu").is_err());
+        assert!(parse("--host --join localhost").is_err());
+        assert!(parse("--peer localhost --host").is_err());
+        assert!(parse("--packet-loss 150").is_err());
+        assert!(parse("--lockstep").is_err());
+        assert!(parse("--api 7779 --host").is_err());
+        assert!(parse("--api 7779 --api-player 2").is_err());
+        assert!(parse("--api 7779 --api-player 2 --players 2").is_ok());
+        assert!(parse("--api 7779 --api-player 2 --versus 3").is_ok());
+    }
+
+    #[test]
+    fn preferences_du_joueur() {
+        let settings = Settings {
+            width: 1280,
+            height: 720,
+            fullscreen: true,
+            ..Default::default()
+        };
+        let options = Options::parse(args("--width 1024"), &settings).unwrap();
+        assert_eq!((options.width, options.height), (1024, 720));
+        assert!(options.fullscreen);
+
+        // L'arène d'une simulation sans fenêtre ne dépend pas des préférences
+        let options = Options::parse(args("--headless"), &settings).unwrap();
+        assert_eq!((options.width, options.height), (800, 600));
+    }
+}
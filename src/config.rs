@@ -0,0 +1,105 @@
+//! Module gérant le fichier de configuration du jeu.
+//!
+//! Le fichier est une suite de lignes `clé = valeur`. Les lignes vides et
+//! celles commençant par `#` sont ignorées.
+//!
+//! ```text
+//! # Exemple de configuration
+//! seed = 42
+//! difficulty = medium
+//! width = 1024
+//! height = 768
+//! fullscreen = false
+//! data_dir = /usr/share/asteroid
+//! render_mode = vector
+//! language = en
+//! ```
+
+use std::fs;
+
+use crate::render::RenderMode;
+use crate::settings::Language;
+use crate::Difficulty;
+
+/// Valeurs lues dans un fichier de configuration
+///
+/// Chaque champ vaut `None` s'il n'est pas présent dans le fichier.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Config {
+    pub seed: Option<u64>,
+    pub difficulty: Option<Difficulty>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fullscreen: Option<bool>,
+    pub data_dir: Option<String>,
+    pub render_mode: Option<RenderMode>,
+    pub language: Option<Language>,
+}
+
+impl Config {
+    /// Charge un fichier de configuration
+    ///
+    /// # Retourne
+    /// La configuration ou une erreur lisible si le fichier est absent ou invalide
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Impossible de lire '{path}' : {e}"))?;
+        Self::parse(&content).map_err(|e| format!("{path} : {e}"))
+    }
+
+    /// Analyse le contenu d'un fichier de configuration
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut config = Config::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) =
+                split_entry(line).ok_or_else(|| format!("ligne {} : '=' attendu", number + 1))?;
+            let error = |e: String| format!("ligne {} : {e}", number + 1);
+            match key {
+                "seed" => config.seed = Some(parse_value(key, value).map_err(error)?),
+                "difficulty" => config.difficulty = Some(value.parse().map_err(error)?),
+                "width" => config.width = Some(parse_value(key, value).map_err(error)?),
+                "height" => config.height = Some(parse_value(key, value).map_err(error)?),
+                "fullscreen" => config.fullscreen = Some(parse_value(key, value).map_err(error)?),
+                "data_dir" => config.data_dir = Some(value.to_string()),
+                "render_mode" => config.render_mode = Some(value.parse().map_err(error)?),
+                "language" => config.language = Some(value.parse().map_err(error)?),
+                _ => return Err(error(format!("clé inconnue '{key}'"))),
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Sépare une ligne `clé = valeur`
+pub fn split_entry(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once('=')?;
+    Some((key.trim(), value.trim()))
+}
+
+/// Convertit une valeur en indiquant la clé fautive en cas d'erreur
+pub fn parse_value<T: std::str::FromStr>(key: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("valeur invalide pour '{key}' : '{value}'"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lecture_configuration() {
+        let config =
+            Config::parse("# commentaire\nseed = 7\ndifficulty = hard\n\nfullscreen=true").unwrap();
+        assert_eq!(config.seed, Some(7));
+        assert_eq!(config.difficulty, Some(Difficulty::Hard));
+        assert_eq!(config.fullscreen, Some(true));
+        assert_eq!(config.width, None);
+        assert!(Config::parse("seed = abc").is_err());
+        assert!(Config::parse("inconnu = 1").is_err());
+    }
+}
@@ -0,0 +1,369 @@
+//! Module gérant les difficultés personnalisées.
+//!
+//! Une difficulté personnalisée règle le nombre d'astéroïdes de départ, leur
+//! vitesse, le bouclier des vaisseaux, le délai entre deux tirs, l'arrivée
+//! d'astéroïdes pendant une vague, la croissance des vagues et leur nombre.
+//!
+//! Une partie se joue par vagues : quand l'arène est vidée, la vague suivante
+//! arrive avec `wave_growth` astéroïdes de plus, et détruire la dernière des
+//! `waves` vagues gagne la partie. Les difficultés prédéfinies n'ont qu'une
+//! vague : elles sont gagnées dès que l'arène est vidée.
+//!
+//! Une difficulté s'échange sous la forme d'un code court, ses valeurs séparées par des
+//! tirets (le nombre de vagues, ajouté en dernier, vaut 1 s'il est absent) :
+//!
+//! ```text
+//! 6-150-10-500-4-2-3
+//! ```
+//!
+//! Les réglages préférés du joueur sont enregistrés sous un nom dans le
+//! dossier de configuration de l'utilisateur, une ligne `nom = code` par
+//! réglage.
+
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::split_entry;
+use crate::locale::Strings;
+use crate::paths::user_config_dir;
+use crate::spaceship::Spaceship;
+use crate::world::FIRE_COOLDOWN;
+
+/// Nom du fichier des réglages enregistrés
+const PRESETS_FILE: &str = "presets.cfg";
+
+/// Bornes du nombre d'astéroïdes de départ
+pub const ASTEROIDS: RangeInclusive<u32> = 1..=20;
+
+/// Bornes de la vitesse des astéroïdes, en pourcentage de la vitesse normale
+pub const SPEED: RangeInclusive<u32> = 50..=300;
+
+/// Bornes du bouclier des vaisseaux
+pub const SHIELD: RangeInclusive<u32> = 1..=30;
+
+/// Bornes du délai entre deux tirs, en millisecondes
+pub const FIRE_COOLDOWN_MS: RangeInclusive<u32> = 100..=2000;
+
+/// Bornes du nombre d'astéroïdes arrivant par minute pendant une vague
+pub const ENEMY_FREQUENCY: RangeInclusive<u32> = 0..=30;
+
+/// Nombre d'astéroïdes ajoutés à chaque vague par défaut
+pub const DEFAULT_WAVE_GROWTH: u32 = 2;
+
+/// Bornes du nombre d'astéroïdes ajoutés à chaque vague
+pub const WAVE_GROWTH_RANGE: RangeInclusive<u32> = 0..=10;
+
+/// Bornes du nombre de vagues à détruire pour gagner
+pub const WAVES: RangeInclusive<u32> = 1..=10;
+
+/// Paramètres d'une difficulté
+///
+/// Les difficultés prédéfinies ont aussi leurs paramètres (voir
+/// `Difficulty::params`). Les valeurs sont entières pour que la difficulté
+/// puisse être comparée et enregistrée telle quelle dans un replay.
+///
+/// # Champs
+/// * `asteroids` - Nombre d'astéroïdes de la première vague
+/// * `speed` - Vitesse des astéroïdes, en pourcentage de la vitesse normale
+/// * `shield` - Points de bouclier des vaisseaux
+/// * `fire_cooldown` - Délai entre deux tirs, en millisecondes
+/// * `enemy_frequency` - Astéroïdes arrivant par le bord de l'arène chaque minute d'une vague
+/// * `wave_growth` - Nombre d'astéroïdes ajoutés à chaque nouvelle vague
+/// * `waves` - Nombre de vagues à détruire pour gagner ; une seule pour les
+///   difficultés prédéfinies, qui sont gagnées dès que l'arène est vidée
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomDifficulty {
+    pub asteroids: u32,
+    pub speed: u32,
+    pub shield: u32,
+    pub fire_cooldown: u32,
+    pub enemy_frequency: u32,
+    pub wave_growth: u32,
+    #[serde(default = "single_wave")]
+    pub waves: u32,
+}
+
+impl Default for CustomDifficulty {
+    /// Paramètres de la difficulté moyenne
+    fn default() -> Self {
+        Self::standard(6)
+    }
+}
+
+impl CustomDifficulty {
+    /// Paramètres d'une difficulté prédéfinie, qui ne change que le nombre d'astéroïdes
+    pub fn standard(asteroids: u32) -> Self {
+        Self {
+            asteroids,
+            speed: 100,
+            shield: Spaceship::SPACESHIP_SHIELD as u32,
+            fire_cooldown: (FIRE_COOLDOWN * 1000.0) as u32,
+            enemy_frequency: 0,
+            wave_growth: DEFAULT_WAVE_GROWTH,
+            waves: 1,
+        }
+    }
+
+    /// Nombre d'astéroïdes d'une vague (à partir de 1)
+    pub fn wave_asteroids(&self, wave: u32) -> u32 {
+        self.asteroids + self.wave_growth * (wave.max(1) - 1)
+    }
+
+    /// Vérifie si une autre vague suit celle donnée, ou si la détruire gagne la partie
+    pub fn has_next_wave(&self, wave: u32) -> bool {
+        wave < self.waves
+    }
+
+    /// Facteur appliqué à la vitesse des astéroïdes
+    pub fn speed_factor(&self) -> f32 {
+        self.speed as f32 / 100.0
+    }
+
+    /// Délai entre deux tirs, en secondes
+    pub fn fire_cooldown_secs(&self) -> f32 {
+        self.fire_cooldown as f32 / 1000.0
+    }
+
+    /// Lit un code de partage
+    pub fn from_code(code: &str) -> Result<Self, String> {
+        let values: Vec<u32> = code
+            .trim()
+            .split('-')
+            .map(|value| value.parse::<u32>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("code de difficulté invalide '{code}' ({e})"))?;
+        let (values, waves) = match values.len() {
+            7 => (&values[..6], values[6]),
+            _ => (&values[..], 1),
+        };
+        let [asteroids, speed, shield, fire_cooldown, enemy_frequency, wave_growth] = values[..]
+        else {
+            return Err(format!(
+                "code de difficulté invalide '{code}' (6 ou 7 valeurs attendues)"
+            ));
+        };
+        let difficulty = Self {
+            asteroids,
+            speed,
+            shield,
+            fire_cooldown,
+            enemy_frequency,
+            wave_growth,
+            waves,
+        };
+        difficulty.validate()?;
+        Ok(difficulty)
+    }
+
+    /// Code de partage des paramètres, lu par `from_code`
+    pub fn code(&self) -> String {
+        format!(
+            "{}-{}-{}-{}-{}-{}-{}",
+            self.asteroids,
+            self.speed,
+            self.shield,
+            self.fire_cooldown,
+            self.enemy_frequency,
+            self.wave_growth,
+            self.waves
+        )
+    }
+
+    /// Vérifie que chaque paramètre est dans ses bornes
+    fn validate(&self) -> Result<(), String> {
+        let checks = [
+            ("astéroïdes", self.asteroids, ASTEROIDS),
+            ("vitesse", self.speed, SPEED),
+            ("bouclier", self.shield, SHIELD),
+            ("délai de tir", self.fire_cooldown, FIRE_COOLDOWN_MS),
+            (
+                "fréquence des ennemis",
+                self.enemy_frequency,
+                ENEMY_FREQUENCY,
+            ),
+            ("croissance des vagues", self.wave_growth, WAVE_GROWTH_RANGE),
+            ("nombre de vagues", self.waves, WAVES),
+        ];
+        for (name, value, range) in checks {
+            if !range.contains(&value) {
+                return Err(format!(
+                    "{name} : {value} hors des bornes {}..{}",
+                    range.start(),
+                    range.end()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Difficultés personnalisées enregistrées sous un nom
+///
+/// # Champs
+/// * `entries` - Noms et paramètres, dans l'ordre d'enregistrement
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Presets {
+    entries: Vec<(String, CustomDifficulty)>,
+}
+
+impl Presets {
+    /// Analyse le contenu d'un fichier de réglages
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut presets = Presets::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |e: String| format!("ligne {} : {e}", number + 1);
+            let (name, code) = split_entry(line).ok_or_else(|| error("'=' attendu".to_string()))?;
+            presets.insert(name, CustomDifficulty::from_code(code).map_err(error)?);
+        }
+        Ok(presets)
+    }
+
+    /// Charge les réglages de l'utilisateur, aucun s'il n'en a pas encore
+    /// enregistré ou si le fichier est invalide
+    ///
+    /// # Arguments
+    /// * `path` - Fichier des réglages
+    /// * `strings` - Textes de l'interface, pour signaler un fichier invalide
+    pub fn load_or_default(path: &Path, strings: &Strings) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        fs::read_to_string(path)
+            .map_err(|e| format!("Impossible de lire '{}' : {e}", path.display()))
+            .and_then(|content| {
+                Self::parse(&content).map_err(|e| format!("{} : {e}", path.display()))
+            })
+            .unwrap_or_else(|e| {
+                eprintln!("{}", strings.format("error", &[&e]));
+                Self::default()
+            })
+    }
+
+    /// Enregistre les réglages dans un fichier
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let error = |e: std::io::Error| format!("Impossible d'écrire '{}' : {e}", path.display());
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(error)?;
+        }
+        fs::write(path, self.to_string()).map_err(error)
+    }
+
+    /// Enregistre un réglage, en remplaçant celui du même nom
+    pub fn insert(&mut self, name: &str, difficulty: CustomDifficulty) {
+        let name = name.trim();
+        match self.entries.iter_mut().find(|(n, _)| n == name) {
+            Some(entry) => entry.1 = difficulty,
+            None => self.entries.push((name.to_string(), difficulty)),
+        }
+    }
+
+    /// Noms des réglages, dans l'ordre d'enregistrement
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// Paramètres du réglage à la position donnée
+    pub fn get(&self, index: usize) -> Option<CustomDifficulty> {
+        self.entries.get(index).map(|(_, difficulty)| *difficulty)
+    }
+
+    /// Position d'un réglage
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|(n, _)| n == name.trim())
+    }
+}
+
+impl fmt::Display for Presets {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, difficulty) in &self.entries {
+            writeln!(f, "{name} = {}", difficulty.code())?;
+        }
+        Ok(())
+    }
+}
+
+/// Nombre de vagues d'une difficulté enregistrée avant qu'il soit réglable
+fn single_wave() -> u32 {
+    1
+}
+
+/// Chemin du fichier des réglages, dans le dossier de configuration de l'utilisateur
+pub fn default_presets_path() -> PathBuf {
+    user_config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(PRESETS_FILE)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn code_de_partage() {
+        let difficulty = CustomDifficulty {
+            asteroids: 12,
+            speed: 150,
+            enemy_frequency: 4,
+            waves: 3,
+            ..Default::default()
+        };
+        assert_eq!(difficulty.code(), "12-150-10-500-4-2-3");
+        assert_eq!(
+            CustomDifficulty::from_code("12-150-10-500-4-2-3"),
+            Ok(difficulty)
+        );
+        // Les codes sans nombre de vagues n'en ont qu'une
+        assert_eq!(
+            CustomDifficulty::from_code("12-150-10-500-4-2").map(|d| d.waves),
+            Ok(1)
+        );
+        assert!(CustomDifficulty::from_code("12-150-10").is_err());
+        assert!(CustomDifficulty::from_code("0-150-10-500-4-2").is_err());
+        assert!(CustomDifficulty::from_code("6-x-10-500-4-2").is_err());
+    }
+
+    #[test]
+    fn regles_des_vagues() {
+        // Une difficulté prédéfinie se gagne à la première vague
+        let standard = CustomDifficulty::standard(3);
+        assert!(!standard.has_next_wave(1));
+        assert_eq!(standard.wave_asteroids(1), 3);
+
+        let difficulty = CustomDifficulty {
+            asteroids: 4,
+            wave_growth: 3,
+            waves: 3,
+            ..Default::default()
+        };
+        assert_eq!(difficulty.wave_asteroids(1), 4);
+        assert_eq!(difficulty.wave_asteroids(3), 10);
+        assert!(difficulty.has_next_wave(2));
+        assert!(!difficulty.has_next_wave(3));
+        assert_eq!(difficulty.validate(), Ok(()));
+        assert!(CustomDifficulty {
+            waves: 11,
+            ..difficulty
+        }
+        .validate()
+        .is_err());
+    }
+
+    #[test]
+    fn reglages_enregistres() {
+        let mut presets = Presets::parse("# réglages\nRapide = 6-200-10-300-0-2").unwrap();
+        presets.insert("Calme", CustomDifficulty::standard(3));
+        presets.insert("Rapide ", CustomDifficulty::standard(8));
+        assert_eq!(presets.names(), ["Rapide", "Calme"]);
+        assert_eq!(presets.get(0).map(|d| d.asteroids), Some(8));
+        assert_eq!(Presets::parse(&presets.to_string()), Ok(presets));
+        assert!(Presets::parse("Rapide = 6-200").is_err());
+    }
+}
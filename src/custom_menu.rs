@@ -0,0 +1,398 @@
+//! Module représentant l'éditeur de difficulté personnalisée
+//!
+//! Ce module contient la structure `CustomMenu`, ouverte par le bouton
+//! « Personnalisée » du menu principal. Les curseurs règlent les paramètres
+//! de la partie (voir le module `custom`) ; un réglage peut être enregistré
+//! sous un nom, rechargé depuis la liste, ou échangé par son code de partage.
+
+use std::path::Path;
+
+use crate::button::Button;
+use crate::custom::{
+    CustomDifficulty, Presets, ASTEROIDS, ENEMY_FREQUENCY, FIRE_COOLDOWN_MS, SHIELD, SPEED, WAVES,
+    WAVE_GROWTH_RANGE,
+};
+use crate::locale::Strings;
+use crate::ui::{
+    Align, Column, Dropdown, Focus, Label, NavAction, Navigation, Slider, TextInput, Viewport,
+};
+use macroquad::prelude::*;
+
+/// Hauteur réservée au titre au-dessus des widgets
+const TITLE_HEIGHT: f32 = 70.0;
+
+/// Pas du curseur de vitesse, en pourcentage
+const SPEED_STEP: f32 = 10.0;
+
+/// Pas du curseur du délai de tir, en millisecondes
+const FIRE_COOLDOWN_STEP: f32 = 50.0;
+
+/// Nombre maximal de caractères d'un nom de réglage
+const NAME_LEN: usize = 20;
+
+/// Nombre maximal de caractères d'un code de partage
+const CODE_LEN: usize = 24;
+
+/// Éléments de l'écran pouvant recevoir le focus, de haut en bas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Asteroids,
+    Speed,
+    Shield,
+    FireCooldown,
+    EnemyFrequency,
+    WaveGrowth,
+    Waves,
+    Preset,
+    Name,
+    SavePreset,
+    Code,
+    Apply,
+    Play,
+    Back,
+}
+
+/// Ordre de parcours des éléments au clavier et à la manette
+const ITEMS: [Item; 14] = [
+    Item::Asteroids,
+    Item::Speed,
+    Item::Shield,
+    Item::FireCooldown,
+    Item::EnemyFrequency,
+    Item::WaveGrowth,
+    Item::Waves,
+    Item::Preset,
+    Item::Name,
+    Item::SavePreset,
+    Item::Code,
+    Item::Apply,
+    Item::Play,
+    Item::Back,
+];
+
+/// Représente l'éditeur de difficulté personnalisée
+///
+/// # Champs
+/// * `title` - Titre de l'écran
+/// * `asteroids` / `speed` / `shield` / `fire_cooldown` / `enemy_frequency` /
+///   `wave_growth` / `waves` - Curseurs des paramètres de la partie
+/// * `presets` - Réglages enregistrés par le joueur
+/// * `preset_list` - Liste des réglages enregistrés
+/// * `preset_label` - Nom de la liste, à sa gauche
+/// * `name` - Champ du nom sous lequel enregistrer le réglage
+/// * `btn_save` - Bouton pour enregistrer le réglage
+/// * `code` - Champ du code de partage, mis à jour quand un curseur bouge
+/// * `btn_apply` - Bouton pour appliquer le code saisi
+/// * `btn_play` - Bouton pour lancer la partie
+/// * `btn_back` - Bouton pour revenir au menu
+/// * `message` - Résultat du dernier enregistrement ou code
+/// * `no_preset` - Texte de la liste quand aucun réglage n'est enregistré
+/// * `focus` - Élément ayant le focus du clavier et de la manette
+/// * `nav` - Actions de navigation de la frame en cours
+/// * `viewport` - Taille de la fenêtre pour laquelle les widgets sont placés
+pub struct CustomMenu {
+    title: Label,
+    asteroids: Slider,
+    speed: Slider,
+    shield: Slider,
+    fire_cooldown: Slider,
+    enemy_frequency: Slider,
+    wave_growth: Slider,
+    waves: Slider,
+    presets: Presets,
+    preset_list: Dropdown,
+    preset_label: Label,
+    name: TextInput,
+    btn_save: Button,
+    code: TextInput,
+    btn_apply: Button,
+    btn_play: Button,
+    btn_back: Button,
+    message: Label,
+    no_preset: String,
+    focus: Focus<Item>,
+    nav: Navigation,
+    viewport: Viewport,
+}
+
+impl CustomMenu {
+    /// Crée l'éditeur affichant les paramètres donnés
+    ///
+    /// # Arguments
+    /// * `presets` - Réglages enregistrés par le joueur
+    /// * `difficulty` - Paramètres affichés à l'ouverture
+    /// * `strings` - Textes de l'interface dans la langue du joueur
+    pub fn new(presets: Presets, difficulty: CustomDifficulty, strings: &Strings) -> Self {
+        let rect = Rect::default();
+        let text = |key| strings.get(key);
+        let slider = |key, range: &std::ops::RangeInclusive<u32>, step, value: u32| {
+            Slider::new(
+                rect,
+                text(key),
+                *range.start() as f32,
+                *range.end() as f32,
+                step,
+                value as f32,
+            )
+        };
+
+        let mut menu = Self {
+            title: Label::new(rect, text("custom.title")).with_size(50),
+            asteroids: slider("custom.asteroids", &ASTEROIDS, 1., difficulty.asteroids),
+            speed: slider("custom.speed", &SPEED, SPEED_STEP, difficulty.speed),
+            shield: slider("custom.shield", &SHIELD, 1., difficulty.shield),
+            fire_cooldown: slider(
+                "custom.fire_cooldown",
+                &FIRE_COOLDOWN_MS,
+                FIRE_COOLDOWN_STEP,
+                difficulty.fire_cooldown,
+            ),
+            enemy_frequency: slider(
+                "custom.enemy_frequency",
+                &ENEMY_FREQUENCY,
+                1.,
+                difficulty.enemy_frequency,
+            ),
+            wave_growth: slider(
+                "custom.wave_growth",
+                &WAVE_GROWTH_RANGE,
+                1.,
+                difficulty.wave_growth,
+            ),
+            waves: slider("custom.waves", &WAVES, 1., difficulty.waves),
+            preset_list: Dropdown::new(rect, &[], 0),
+            presets,
+            preset_label: Label::new(rect, text("custom.preset")).with_align(Align::Left),
+            name: TextInput::new(rect, text("custom.name"), NAME_LEN)
+                .with_filter(|c| !c.is_control() && c != '=' && c != '#'),
+            btn_save: Button::from_rect(rect, YELLOW, text("custom.save")),
+            code: TextInput::new(rect, text("custom.code"), CODE_LEN)
+                .with_filter(|c| c.is_ascii_digit() || c == '-'),
+            btn_apply: Button::from_rect(rect, BLUE, text("custom.apply")),
+            btn_play: Button::from_rect(rect, GREEN, text("custom.play")),
+            btn_back: Button::from_rect(rect, GRAY, text("custom.back")),
+            message: Label::new(rect, ""),
+            no_preset: text("custom.no_preset").to_string(),
+            focus: Focus::new(),
+            nav: Navigation::default(),
+            viewport: Viewport::default(),
+        };
+        menu.refresh_presets(None);
+        menu.code.set_text(&difficulty.code());
+        menu.viewport.resized();
+        menu.layout();
+        menu
+    }
+
+    /// Place les widgets en colonne au centre de la fenêtre
+    fn layout(&mut self) {
+        let screen = self.viewport.rect();
+        let column = Column::new(screen.w / 2.0, 0.0, 440.0, 32.0, 10.0);
+        // Le titre et les widgets (onze lignes et le message) sont centrés ensemble
+        let column = Column {
+            top: ((screen.h - TITLE_HEIGHT - column.height(12)) / 2.0).max(0.0) + TITLE_HEIGHT,
+            ..column
+        };
+
+        self.title
+            .set_rect(Rect::new(0., column.top - TITLE_HEIGHT, screen.w, 50.));
+        for (i, slider) in self.sliders().into_iter().enumerate() {
+            slider.set_rect(column.row(i));
+        }
+        self.preset_label.set_rect(column.cell(7, 0, 2));
+        self.preset_list.set_rect(column.cell(7, 1, 2));
+        self.name.set_rect(column.cell(8, 0, 2));
+        self.btn_save.set_rect(column.cell(8, 1, 2));
+        self.code.set_rect(column.cell(9, 0, 2));
+        self.btn_apply.set_rect(column.cell(9, 1, 2));
+        self.btn_play.set_rect(column.cell(10, 0, 2));
+        self.btn_back.set_rect(column.cell(10, 1, 2));
+        self.message
+            .set_rect(Rect::new(0., column.row(11).y, screen.w, column.row_height));
+    }
+
+    /// Curseurs des paramètres, de haut en bas
+    fn sliders(&mut self) -> [&mut Slider; 7] {
+        [
+            &mut self.asteroids,
+            &mut self.speed,
+            &mut self.shield,
+            &mut self.fire_cooldown,
+            &mut self.enemy_frequency,
+            &mut self.wave_growth,
+            &mut self.waves,
+        ]
+    }
+
+    /// Recrée la liste des réglages enregistrés
+    ///
+    /// # Arguments
+    /// * `selected` - Position du réglage à sélectionner
+    fn refresh_presets(&mut self, selected: Option<usize>) {
+        let mut names = self.presets.names();
+        if names.is_empty() {
+            names.push(&self.no_preset);
+        }
+        let mut list = Dropdown::new(Rect::default(), &names, selected.unwrap_or(0));
+        list.set_focused(self.focus.is(Item::Preset));
+        self.preset_list = list;
+        self.layout();
+    }
+
+    /// Paramètres choisis à l'écran
+    pub fn difficulty(&self) -> CustomDifficulty {
+        CustomDifficulty {
+            asteroids: self.asteroids.value() as u32,
+            speed: self.speed.value() as u32,
+            shield: self.shield.value() as u32,
+            fire_cooldown: self.fire_cooldown.value() as u32,
+            enemy_frequency: self.enemy_frequency.value() as u32,
+            wave_growth: self.wave_growth.value() as u32,
+            waves: self.waves.value() as u32,
+        }
+    }
+
+    /// Affiche les paramètres donnés dans les curseurs et le code de partage
+    fn set_difficulty(&mut self, difficulty: CustomDifficulty) {
+        self.asteroids.set_value(difficulty.asteroids as f32);
+        self.speed.set_value(difficulty.speed as f32);
+        self.shield.set_value(difficulty.shield as f32);
+        self.fire_cooldown
+            .set_value(difficulty.fire_cooldown as f32);
+        self.enemy_frequency
+            .set_value(difficulty.enemy_frequency as f32);
+        self.wave_growth.set_value(difficulty.wave_growth as f32);
+        self.waves.set_value(difficulty.waves as f32);
+        self.code.set_text(&difficulty.code());
+    }
+
+    /// Replace les widgets si la fenêtre a changé de taille et déplace le focus
+    /// avec les flèches haut et bas, à appeler à chaque frame avant `draw` et
+    /// les autres méthodes
+    ///
+    /// # Arguments
+    /// * `nav` - Actions de navigation de la frame
+    pub fn update(&mut self, nav: &Navigation) {
+        if self.viewport.resized() {
+            self.layout();
+        }
+        self.nav = nav.clone();
+        let step = nav.step(NavAction::Up, NavAction::Down);
+        if step == 0 || self.preset_list.is_open() {
+            return;
+        }
+        self.focus.navigate(&ITEMS, step);
+
+        let focus = self.focus;
+        self.asteroids.set_focused(focus.is(Item::Asteroids));
+        self.speed.set_focused(focus.is(Item::Speed));
+        self.shield.set_focused(focus.is(Item::Shield));
+        self.fire_cooldown.set_focused(focus.is(Item::FireCooldown));
+        self.enemy_frequency
+            .set_focused(focus.is(Item::EnemyFrequency));
+        self.wave_growth.set_focused(focus.is(Item::WaveGrowth));
+        self.waves.set_focused(focus.is(Item::Waves));
+        self.preset_list.set_focused(focus.is(Item::Preset));
+        self.name.set_focused(focus.is(Item::Name));
+        self.btn_save.set_focused(focus.is(Item::SavePreset));
+        self.code.set_focused(focus.is(Item::Code));
+        self.btn_apply.set_focused(focus.is(Item::Apply));
+        self.btn_play.set_focused(focus.is(Item::Play));
+        self.btn_back.set_focused(focus.is(Item::Back));
+    }
+
+    /// Dessine l'écran et gère les interactions
+    ///
+    /// # Arguments
+    /// * `font` - Police de caractères à utiliser pour le texte (`None` pour la police par défaut)
+    /// * `strings` - Textes de l'interface, pour le message d'un code invalide
+    pub fn draw(&mut self, font: Option<&Font>, strings: &Strings) {
+        clear_background(BLACK);
+        self.title.draw(font);
+        for slider in self.sliders() {
+            slider.draw(font);
+        }
+        self.preset_label.draw(font);
+        self.name.draw(font);
+        self.btn_save.draw(font);
+        self.code.draw(font);
+        self.btn_apply.draw(font);
+        self.btn_play.draw(font);
+        self.btn_back.draw(font);
+        self.message.draw(font);
+        self.preset_list.draw(font);
+
+        // Un clic dans la liste ouverte ne doit pas atteindre les widgets du dessous
+        let nav = self.nav.clone();
+        let list_open = self.preset_list.is_open();
+        if self.preset_list.update(&nav) {
+            if let Some(difficulty) = self.presets.get(self.preset_list.selected()) {
+                self.set_difficulty(difficulty);
+            }
+        }
+        if list_open || self.preset_list.is_open() {
+            return;
+        }
+
+        let mut changed = false;
+        for slider in self.sliders() {
+            changed |= slider.update(&nav);
+        }
+        if changed {
+            let code = self.difficulty().code();
+            self.code.set_text(&code);
+        }
+        self.name.update();
+        self.code.update();
+        if self.btn_apply.is_activated(&nav) {
+            match CustomDifficulty::from_code(self.code.text()) {
+                Ok(difficulty) => {
+                    self.set_difficulty(difficulty);
+                    self.message.set_text("", WHITE);
+                }
+                Err(e) => {
+                    let text = strings.format("custom.invalid_code", &[&e]);
+                    self.message.set_text(&text, RED);
+                }
+            }
+        }
+    }
+
+    /// Enregistre le réglage affiché sous le nom saisi
+    ///
+    /// # Arguments
+    /// * `path` - Fichier des réglages enregistrés
+    /// * `strings` - Textes de l'interface, pour le message affiché
+    pub fn save_preset(&mut self, path: &Path, strings: &Strings) {
+        let name = self.name.text().trim().to_string();
+        if name.is_empty() {
+            self.message
+                .set_text(strings.get("custom.name_missing"), RED);
+            return;
+        }
+        self.presets.insert(&name, self.difficulty());
+        match self.presets.save(path) {
+            Ok(()) => {
+                self.refresh_presets(self.presets.position(&name));
+                let text = strings.format("custom.saved", &[&name]);
+                self.message.set_text(&text, GREEN);
+            }
+            Err(e) => self.message.set_text(&e, RED),
+        }
+    }
+
+    /// Vérifie si le bouton d'enregistrement du réglage a été cliqué ou activé
+    pub fn save_clicked(&self) -> bool {
+        !self.preset_list.is_open() && self.btn_save.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton jouer a été cliqué ou activé
+    pub fn play_clicked(&self) -> bool {
+        !self.preset_list.is_open() && self.btn_play.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton retour a été cliqué ou activé
+    pub fn back_clicked(&self) -> bool {
+        !self.preset_list.is_open() && self.btn_back.is_activated(&self.nav)
+    }
+}
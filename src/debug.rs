@@ -0,0 +1,158 @@
+//! Module gérant l'affichage de débogage.
+//!
+//! L'overlay dessine par-dessus le jeu les cercles de collision, les vecteurs
+//! vitesse, l'orientation des vaisseaux et quelques compteurs (un bloc par
+//! joueur), ce qui permet de vérifier que les images sont bien alignées sur
+//! les "hitbox".
+
+use macroquad::prelude::*;
+
+use crate::locale::Strings;
+use crate::render::player_color;
+use crate::world::World;
+
+/// Touche affichant ou masquant l'overlay
+pub const DEBUG_KEY: KeyCode = KeyCode::F3;
+
+/// Facteur d'agrandissement des vecteurs vitesse (pixels par frame -> pixels affichés)
+const SPEED_SCALE: f32 = 20.0;
+
+/// Overlay de débogage
+///
+/// # Champs
+/// * `visible` - Indique si l'overlay est affiché
+#[derive(Debug, Default)]
+pub struct DebugOverlay {
+    visible: bool,
+}
+
+impl DebugOverlay {
+    /// Crée l'overlay, visible ou non au démarrage
+    pub fn new(visible: bool) -> Self {
+        Self { visible }
+    }
+
+    /// Affiche ou masque l'overlay lorsque la touche F3 est pressée
+    pub fn update(&mut self) {
+        if is_key_pressed(DEBUG_KEY) {
+            self.visible = !self.visible;
+        }
+    }
+
+    /// Dessine l'overlay par-dessus la partie
+    ///
+    /// # Arguments
+    /// * `world` - Partie en cours
+    /// * `font` - Police de caractères (`None` pour la police par défaut)
+    /// * `strings` - Textes de l'interface
+    pub fn draw(&self, world: &World, font: Option<&Font>, strings: &Strings) {
+        if !self.visible {
+            return;
+        }
+
+        // Astéroïdes : cercle de collision et vitesse
+        for asteroid in &world.asteroids {
+            let pos = asteroid.get_position();
+            draw_circle_lines(pos.x, pos.y, asteroid.get_width(), 1., YELLOW);
+            draw_vector(pos, asteroid.get_speed() * SPEED_SCALE, ORANGE);
+        }
+
+        // Missiles : cercle de collision
+        for bullet in &world.bullets {
+            let pos = bullet.get_position();
+            draw_circle_lines(pos.x, pos.y, bullet.get_size(), 1., YELLOW);
+        }
+
+        // Vaisseaux en jeu : cercle de collision, vitesse et orientation
+        for player in world.players.iter().filter(|player| player.is_alive()) {
+            let spaceship = &player.spaceship;
+            let pos = spaceship.get_position();
+            let hitbox_color = if spaceship.get_invicibility() > 0.0 {
+                SKYBLUE
+            } else {
+                GREEN
+            };
+            draw_circle_lines(pos.x, pos.y, spaceship.get_height(), 1., hitbox_color);
+            draw_vector(pos, spaceship.get_speed() * SPEED_SCALE, ORANGE);
+            let orientation = vec2(
+                spaceship.get_orientation().sin(),
+                -spaceship.get_orientation().cos(),
+            );
+            draw_vector(pos, orientation * spaceship.get_height() * 1.5, MAGENTA);
+        }
+
+        // Informations textuelles : la partie, puis un bloc par joueur
+        let mut lines = vec![
+            (strings.format("debug.fps", &[&get_fps()]), GREEN),
+            (strings.format("debug.frame", &[&world.frame]), GREEN),
+            (
+                strings.format(
+                    "debug.objects",
+                    &[&world.asteroids.len(), &world.bullets.len()],
+                ),
+                GREEN,
+            ),
+        ];
+        for i in 0..world.players.len() {
+            lines.push((strings.format("debug.player", &[&(i + 1)]), player_color(i)));
+            lines.extend(
+                player_lines(world, i, strings)
+                    .into_iter()
+                    .map(|line| (line, GREEN)),
+            );
+        }
+        for (i, (line, color)) in lines.iter().enumerate() {
+            draw_text_ex(
+                line,
+                20.,
+                70. + i as f32 * 20.,
+                TextParams {
+                    font_size: 18,
+                    font,
+                    color: *color,
+                    ..Default::default()
+                },
+            );
+        }
+    }
+}
+
+/// Lignes d'informations sur le vaisseau d'un joueur
+fn player_lines(world: &World, i: usize, strings: &Strings) -> Vec<String> {
+    let player = &world.players[i];
+    if !player.is_alive() {
+        return vec![strings.get("debug.destroyed").to_string()];
+    }
+    let spaceship = &player.spaceship;
+    let pos = spaceship.get_position();
+    let can_fire = if world.can_fire(i) {
+        "debug.yes"
+    } else {
+        "debug.no"
+    };
+    vec![
+        strings.format(
+            "debug.position",
+            &[&format!("{:.0}", pos.x), &format!("{:.0}", pos.y)],
+        ),
+        strings.format(
+            "debug.motion",
+            &[
+                &format!("{:.2}", spaceship.get_speed().length()),
+                &format!("{:.2}", spaceship.get_orientation()),
+            ],
+        ),
+        strings.format(
+            "debug.invincibility",
+            &[&format!("{:.2}", spaceship.get_invicibility().max(0.0))],
+        ),
+        strings.format("debug.can_fire", &[&strings.get(can_fire)]),
+    ]
+}
+
+/// Dessine un vecteur sous forme de segment terminé par un point
+fn draw_vector(origin: Vec2, vector: Vec2, color: Color) {
+    let end = origin + vector;
+    draw_line(origin.x, origin.y, end.x, end.y, 1., color);
+    draw_circle(end.x, end.y, 2., color);
+}
@@ -0,0 +1,161 @@
+//! Module gérant les effets visuels déclenchés quand un vaisseau est touché.
+//!
+//! Un choc fait trembler l'arène et la recouvre brièvement de rouge, pour que
+//! le joueur comprenne pourquoi son bouclier a baissé. Ces effets ne touchent
+//! que l'affichage : la simulation de la partie n'en dépend pas.
+//!
+//! Les chocs sont repérés en comparant les boucliers d'une image à l'autre,
+//! ce qui marche aussi pour une partie reçue du réseau (partie en ligne,
+//! rollback ou spectateur) dont la simulation n'est pas locale.
+
+use macroquad::prelude::*;
+
+use crate::hud::Hud;
+use crate::render::{self, Renderer};
+use crate::spaceship::Spaceship;
+use crate::world::World;
+
+/// Durée du tremblement de l'arène (en secondes)
+const SHAKE_DURATION: f32 = 0.3;
+
+/// Amplitude maximale du tremblement, pour le choc le plus fort (en pixels)
+const SHAKE_AMPLITUDE: f32 = 8.0;
+
+/// Part minimale de l'amplitude, pour qu'un choc léger se voie aussi
+const MIN_INTENSITY: f32 = 0.3;
+
+/// Durée du voile rouge (en secondes)
+const FLASH_DURATION: f32 = 0.2;
+
+/// Opacité du voile rouge au moment du choc
+const FLASH_ALPHA: f32 = 0.35;
+
+/// Effets d'un choc en cours
+///
+/// # Champs
+/// * `shake` - Temps de tremblement restant
+/// * `intensity` - Force du dernier choc, de `MIN_INTENSITY` à 1
+/// * `flash` - Temps restant du voile rouge
+/// * `shields` - Boucliers des joueurs lors du dernier appel à `observe`
+#[derive(Debug, Default)]
+pub struct HitEffects {
+    shake: f32,
+    intensity: f32,
+    flash: f32,
+    shields: Vec<u8>,
+}
+
+impl HitEffects {
+    /// Déclenche les effets d'un choc
+    ///
+    /// # Arguments
+    /// * `spin` - Rotation donnée au vaisseau par le choc, qui mesure sa force
+    pub fn trigger(&mut self, spin: f32) {
+        let intensity = (spin.abs() / Spaceship::MAX_SPIN).clamp(MIN_INTENSITY, 1.0);
+        self.intensity = self.intensity.max(intensity);
+        self.shake = SHAKE_DURATION;
+        self.flash = FLASH_DURATION;
+    }
+
+    /// Déclenche les effets si le bouclier d'un joueur a baissé depuis le dernier appel
+    pub fn observe(&mut self, world: &World) {
+        let shields: Vec<u8> = world
+            .players
+            .iter()
+            .map(|player| player.spaceship.get_shield())
+            .collect();
+        let previous = std::mem::replace(&mut self.shields, shields);
+        for (player, shield) in world.players.iter().zip(previous) {
+            if player.spaceship.get_shield() < shield {
+                self.trigger(player.spaceship.get_spin());
+            }
+        }
+    }
+
+    /// Fait avancer les effets dans le temps
+    pub fn update(&mut self, dt: f32) {
+        self.shake = (self.shake - dt).max(0.0);
+        self.flash = (self.flash - dt).max(0.0);
+        if self.shake == 0.0 {
+            self.intensity = 0.0;
+        }
+    }
+
+    /// Décalage de l'arène dû au tremblement, qui s'atténue jusqu'à sa fin
+    pub fn shake_offset(&self) -> Vec2 {
+        if self.shake <= 0.0 {
+            return Vec2::ZERO;
+        }
+        let amplitude = SHAKE_AMPLITUDE * self.intensity * self.shake / SHAKE_DURATION;
+        // Deux fréquences différentes pour que le tremblement ne suive pas une droite
+        vec2((self.shake * 90.).sin(), (self.shake * 70.).cos()) * amplitude
+    }
+
+    /// Caméra décalant l'arène selon le tremblement, en coordonnées de la fenêtre
+    pub fn camera(&self) -> Camera2D {
+        let (width, height) = (screen_width(), screen_height());
+        Camera2D {
+            target: vec2(width, height) / 2. - self.shake_offset(),
+            zoom: vec2(2. / width, 2. / height),
+            ..Default::default()
+        }
+    }
+
+    /// Dessine une partie : l'arène tremble après un choc, le HUD reste en place
+    /// et le voile rouge recouvre le tout
+    pub fn draw(&self, renderer: &mut impl Renderer, world: &World, hud: &Hud) {
+        set_camera(&self.camera());
+        render::draw_scene(renderer, world);
+        set_default_camera();
+        hud.draw(renderer, world);
+        self.draw_flash();
+    }
+
+    /// Recouvre la fenêtre du voile rouge, s'il est en cours
+    pub fn draw_flash(&self) {
+        if self.flash <= 0.0 {
+            return;
+        }
+        let alpha = FLASH_ALPHA * self.flash / FLASH_DURATION;
+        draw_rectangle(
+            0.,
+            0.,
+            screen_width(),
+            screen_height(),
+            Color { a: alpha, ..RED },
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn effets_qui_s_estompent() {
+        let mut effects = HitEffects::default();
+        assert_eq!(effects.shake_offset(), Vec2::ZERO);
+
+        effects.trigger(Spaceship::MAX_SPIN);
+        effects.update(0.01);
+        let strong = effects.shake_offset().length();
+        assert!(strong > 0. && strong <= SHAKE_AMPLITUDE);
+
+        effects.update(SHAKE_DURATION);
+        assert_eq!(effects.shake_offset(), Vec2::ZERO);
+        assert_eq!(effects.flash, 0.0);
+    }
+
+    #[test]
+    fn choc_repere_par_le_bouclier() {
+        let mut world = World::new(1, crate::Difficulty::Easy, crate::world::DEFAULT_ARENA);
+        let mut effects = HitEffects::default();
+        effects.observe(&world);
+        effects.observe(&world);
+        assert_eq!(effects.flash, 0.0);
+
+        world.players[0].spaceship.hit();
+        effects.observe(&world);
+        assert_eq!(effects.flash, FLASH_DURATION);
+    }
+}
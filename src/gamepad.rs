@@ -0,0 +1,159 @@
+//! Module lisant les manettes de jeu.
+//!
+//! Macroquad 0.4 ne gère pas encore les manettes. Sous Linux, la première
+//! manette branchée est lue sur l'interface joystick du noyau
+//! (`/dev/input/js0`...) par un thread ; ailleurs, aucune manette n'est
+//! détectée et le jeu se joue au clavier et à la souris.
+
+use std::fs::File;
+use std::io::Read;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+/// Nombre de manettes cherchées (`/dev/input/js0` à `js3`)
+const MAX_DEVICES: usize = 4;
+
+/// Type d'un événement de bouton
+const EVENT_BUTTON: u8 = 0x01;
+
+/// Type d'un événement d'axe (stick ou croix directionnelle)
+const EVENT_AXIS: u8 = 0x02;
+
+/// Drapeau des événements décrivant l'état initial de la manette
+const EVENT_INIT: u8 = 0x80;
+
+/// Position d'un axe à partir de laquelle une direction est pressée
+const AXIS_THRESHOLD: i16 = 16_000;
+
+/// Boutons de la manette utilisés par le jeu
+///
+/// # Variantes
+/// * `Up` / `Down` / `Left` / `Right` - Croix directionnelle ou stick gauche
+/// * `A` - Bouton de validation (bas des quatre boutons)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PadButton {
+    Up,
+    Down,
+    Left,
+    Right,
+    A,
+}
+
+/// Manette de jeu, éventuellement absente
+///
+/// # Champs
+/// * `events` - Événements lus par le thread de la manette, `None` sans manette
+/// * `held` - Boutons enfoncés
+/// * `pressed` - Boutons enfoncés depuis la frame précédente
+pub struct Gamepad {
+    events: Option<Receiver<[u8; 8]>>,
+    held: Vec<PadButton>,
+    pressed: Vec<PadButton>,
+}
+
+impl Gamepad {
+    /// Ouvre la première manette trouvée, ou une manette absente s'il n'y en a pas
+    pub fn open() -> Self {
+        let file = (0..MAX_DEVICES).find_map(|i| File::open(format!("/dev/input/js{i}")).ok());
+        let events = file.map(|mut file| {
+            let (sender, events) = mpsc::channel();
+            // La lecture est bloquante : elle se fait à côté de la boucle du jeu
+            thread::spawn(move || {
+                let mut event = [0; 8];
+                while file.read_exact(&mut event).is_ok() && sender.send(event).is_ok() {}
+            });
+            events
+        });
+        Self {
+            events,
+            held: Vec::new(),
+            pressed: Vec::new(),
+        }
+    }
+
+    /// Lit les événements reçus depuis la frame précédente
+    pub fn update(&mut self) {
+        self.pressed.clear();
+        let events: Vec<[u8; 8]> = match &self.events {
+            Some(events) => events.try_iter().collect(),
+            None => return,
+        };
+        for event in events {
+            self.apply(event);
+        }
+    }
+
+    /// Applique un événement du noyau (`struct js_event` : date, valeur, type, numéro)
+    fn apply(&mut self, event: [u8; 8]) {
+        let value = i16::from_le_bytes([event[4], event[5]]);
+        let kind = event[6];
+        let number = event[7];
+        // L'état initial ne compte pas comme un appui
+        let initial = kind & EVENT_INIT != 0;
+        match (kind & !EVENT_INIT, number) {
+            (EVENT_BUTTON, 0) => self.set(PadButton::A, value != 0, initial),
+            // Stick gauche (0, 1) ou croix directionnelle (6, 7)
+            (EVENT_AXIS, 0 | 6) => {
+                self.set(PadButton::Left, value < -AXIS_THRESHOLD, initial);
+                self.set(PadButton::Right, value > AXIS_THRESHOLD, initial);
+            }
+            (EVENT_AXIS, 1 | 7) => {
+                self.set(PadButton::Up, value < -AXIS_THRESHOLD, initial);
+                self.set(PadButton::Down, value > AXIS_THRESHOLD, initial);
+            }
+            _ => {}
+        }
+    }
+
+    /// Enfonce ou relâche un bouton
+    fn set(&mut self, button: PadButton, down: bool, initial: bool) {
+        let held = self.held.contains(&button);
+        if down && !held {
+            self.held.push(button);
+            if !initial {
+                self.pressed.push(button);
+            }
+        } else if !down && held {
+            self.held.retain(|&b| b != button);
+        }
+    }
+
+    /// Vérifie si un bouton a été enfoncé depuis la frame précédente
+    pub fn is_pressed(&self, button: PadButton) -> bool {
+        self.pressed.contains(&button)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn event(value: i16, kind: u8, number: u8) -> [u8; 8] {
+        let [low, high] = value.to_le_bytes();
+        [0, 0, 0, 0, low, high, kind, number]
+    }
+
+    #[test]
+    fn evenements_de_la_manette() {
+        let mut gamepad = Gamepad {
+            events: None,
+            held: Vec::new(),
+            pressed: Vec::new(),
+        };
+        // L'état initial d'un bouton déjà enfoncé n'est pas un appui
+        gamepad.apply(event(1, EVENT_BUTTON | EVENT_INIT, 0));
+        assert!(!gamepad.is_pressed(PadButton::A));
+        gamepad.apply(event(0, EVENT_BUTTON, 0));
+        gamepad.apply(event(1, EVENT_BUTTON, 0));
+        assert!(gamepad.is_pressed(PadButton::A));
+
+        // Un stick maintenu ne compte qu'une fois
+        gamepad.apply(event(i16::MAX, EVENT_AXIS, 7));
+        gamepad.apply(event(30_000, EVENT_AXIS, 7));
+        assert_eq!(gamepad.pressed, vec![PadButton::A, PadButton::Down]);
+        gamepad.apply(event(0, EVENT_AXIS, 7));
+        gamepad.apply(event(i16::MIN, EVENT_AXIS, 0));
+        assert!(gamepad.is_pressed(PadButton::Left));
+        assert_eq!(gamepad.held, vec![PadButton::A, PadButton::Left]);
+    }
+}
@@ -0,0 +1,289 @@
+//! Module exposant le jeu comme environnement d'apprentissage par renforcement.
+//!
+//! Avec `--gym`, le jeu lit une commande JSON par ligne sur l'entrée standard
+//! et répond par une ligne JSON sur la sortie standard, à la manière de Gym :
+//!
+//! ```text
+//! > {"cmd": "reset", "seed": 42, "difficulty": "medium"}
+//! < {"observation": {...}, "reward": 0.0, "done": false, "info": {...}}
+//! > {"cmd": "step", "action": {"left": true, "fire": true}}
+//! < {"observation": {...}, "reward": 20.0, "done": false, "info": {...}}
+//! > {"cmd": "close"}
+//! ```
+//!
+//! L'action peut aussi être donnée sous forme encodée (`"action": "L...F."`).
+//! La récompense est le score gagné pendant la frame, moins `SHIELD_PENALTY`
+//! par point de bouclier perdu. Une commande invalide reçoit `{"error": "..."}`.
+
+use std::io::{BufRead, Write};
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::cli::Options;
+use crate::input::Input;
+use crate::world::{wrapped_delta, World};
+use crate::Difficulty;
+
+/// Nombre d'astéroïdes décrits dans une observation (les plus proches)
+pub const OBSERVED_ASTEROIDS: usize = 8;
+
+/// Récompense retirée pour chaque point de bouclier perdu
+pub const SHIELD_PENALTY: f32 = 100.0;
+
+/// Commande reçue sur l'entrée standard
+#[derive(Debug, Deserialize)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+enum Request {
+    Reset {
+        seed: Option<u64>,
+        difficulty: Option<String>,
+    },
+    Step {
+        action: Action,
+    },
+    Close,
+}
+
+/// Action d'un agent : commandes détaillées ou encodées (`LRUDFH`)
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Action {
+    Input(Input),
+    Encoded(String),
+}
+
+/// État du vaisseau vu par l'agent
+#[derive(Debug, Serialize)]
+struct ShipObservation {
+    position: [f32; 2],
+    speed: [f32; 2],
+    orientation: f32,
+    shield: u8,
+    invincibility: f32,
+    can_fire: bool,
+}
+
+/// Astéroïde vu par l'agent, relativement au vaisseau
+#[derive(Debug, Serialize)]
+struct AsteroidObservation {
+    delta: [f32; 2],
+    speed: [f32; 2],
+    width: f32,
+}
+
+/// Observation renvoyée après chaque commande
+#[derive(Debug, Serialize)]
+struct Observation {
+    arena: [f32; 2],
+    ship: ShipObservation,
+    asteroids: Vec<AsteroidObservation>,
+}
+
+/// Informations complémentaires, non destinées à l'apprentissage
+#[derive(Debug, Serialize)]
+struct Info {
+    frame: u64,
+    score: u32,
+    wave: u32,
+    state: &'static str,
+}
+
+/// Réponse à `reset` et `step`
+#[derive(Debug, Serialize)]
+struct Response {
+    observation: Observation,
+    reward: f32,
+    done: bool,
+    info: Info,
+}
+
+/// Environnement : une partie et ses paramètres
+///
+/// # Champs
+/// * `world` - Partie en cours, `None` avant le premier `reset`
+/// * `difficulty` - Difficulté par défaut des parties
+/// * `size` - Dimensions de l'arène
+/// * `max_frames` - Nombre de frames après lequel la partie est arrêtée
+/// * `seed` - Graine utilisée par un `reset` sans graine
+pub struct Env {
+    world: Option<World>,
+    difficulty: Difficulty,
+    size: Vec2,
+    max_frames: u64,
+    seed: u64,
+}
+
+impl Env {
+    /// Crée un environnement selon les options de lancement
+    pub fn new(options: &Options, seed: u64) -> Self {
+        Self {
+            world: None,
+            difficulty: options.difficulty.unwrap_or(Difficulty::Easy),
+            size: vec2(options.width as f32, options.height as f32),
+            max_frames: options.frames,
+            seed,
+        }
+    }
+
+    /// Traite une ligne de commande
+    ///
+    /// # Retourne
+    /// La ligne JSON à répondre, ou `None` pour `close`
+    pub fn handle(&mut self, line: &str) -> Option<String> {
+        let response = match serde_json::from_str::<Request>(line) {
+            Ok(Request::Close) => return None,
+            Ok(request) => self.apply(request),
+            Err(e) => Err(format!("commande invalide ({e})")),
+        };
+        Some(match response {
+            Ok(response) => serde_json::to_string(&response).unwrap_or_default(),
+            Err(e) => serde_json::json!({ "error": e }).to_string(),
+        })
+    }
+
+    fn apply(&mut self, request: Request) -> Result<Response, String> {
+        match request {
+            Request::Reset { seed, difficulty } => {
+                let difficulty = match difficulty {
+                    Some(name) => name.parse()?,
+                    None => self.difficulty,
+                };
+                let seed = seed.unwrap_or_else(|| {
+                    self.seed = self.seed.wrapping_add(1);
+                    self.seed
+                });
+                let world = World::new(seed, difficulty, self.size);
+                Ok(self.respond(world, 0.))
+            }
+            Request::Step { action } => {
+                let input = match action {
+                    Action::Input(input) => input,
+                    Action::Encoded(text) => Input::decode(&text)?,
+                };
+                let mut world = self
+                    .world
+                    .take()
+                    .ok_or("'reset' doit être appelé avant 'step'")?;
+                let (score, shield) = (world.score(), world.spaceship().get_shield());
+                world.step(&input);
+                let lost = shield.saturating_sub(world.spaceship().get_shield());
+                let reward =
+                    world.score().saturating_sub(score) as f32 - lost as f32 * SHIELD_PENALTY;
+                Ok(self.respond(world, reward))
+            }
+            Request::Close => unreachable!(),
+        }
+    }
+
+    /// Construit la réponse puis conserve la partie
+    fn respond(&mut self, world: World, reward: f32) -> Response {
+        let response = Response {
+            observation: observe(&world),
+            reward,
+            done: world.is_over() || world.frame >= self.max_frames,
+            info: Info {
+                frame: world.frame,
+                score: world.score(),
+                wave: world.wave,
+                state: world.state.name(),
+            },
+        };
+        self.world = Some(world);
+        response
+    }
+}
+
+/// Décrit la partie pour l'agent : le vaisseau et les astéroïdes les plus proches
+fn observe(world: &World) -> Observation {
+    let spaceship = world.spaceship();
+    let mut asteroids: Vec<AsteroidObservation> = world
+        .asteroids
+        .iter()
+        .map(|asteroid| {
+            let delta = wrapped_delta(
+                spaceship.get_position(),
+                asteroid.get_position(),
+                world.size,
+            );
+            AsteroidObservation {
+                delta: delta.into(),
+                speed: asteroid.get_speed().into(),
+                width: asteroid.get_width(),
+            }
+        })
+        .collect();
+    asteroids.sort_by(|a, b| {
+        let distance = |o: &AsteroidObservation| Vec2::from(o.delta).length();
+        distance(a).total_cmp(&distance(b))
+    });
+    asteroids.truncate(OBSERVED_ASTEROIDS);
+
+    Observation {
+        arena: world.size.into(),
+        ship: ShipObservation {
+            position: spaceship.get_position().into(),
+            speed: spaceship.get_speed().into(),
+            orientation: spaceship.get_orientation(),
+            shield: spaceship.get_shield(),
+            invincibility: spaceship.get_invicibility().max(0.),
+            can_fire: world.can_fire(0),
+        },
+        asteroids,
+    }
+}
+
+/// Boucle de l'environnement sur l'entrée et la sortie standard
+pub fn run(options: &Options, seed: u64) -> Result<(), String> {
+    let mut env = Env::new(options, seed);
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout().lock();
+    for line in stdin.lock().lines() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some(response) = env.handle(&line) else {
+            break;
+        };
+        writeln!(stdout, "{response}")
+            .and_then(|()| stdout.flush())
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::Value;
+
+    fn request(env: &mut Env, line: &str) -> Value {
+        serde_json::from_str(&env.handle(line).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn protocole_json() {
+        let mut env = Env::new(&Options::default(), 0);
+        assert!(request(&mut env, r#"{"cmd": "step", "action": "....."}"#)["error"].is_string());
+
+        let reset = request(
+            &mut env,
+            r#"{"cmd": "reset", "seed": 4, "difficulty": "hard"}"#,
+        );
+        assert_eq!(reset["done"], false);
+        assert_eq!(reset["observation"]["ship"]["shield"], 10);
+        assert_eq!(
+            reset["observation"]["asteroids"].as_array().unwrap().len(),
+            OBSERVED_ASTEROIDS
+        );
+
+        let step = request(&mut env, r#"{"cmd": "step", "action": {"up": true}}"#);
+        assert_eq!(step["info"]["frame"], 1);
+        let step = request(&mut env, r#"{"cmd": "step", "action": "L...F"}"#);
+        assert_eq!(step["info"]["frame"], 2);
+
+        assert!(request(&mut env, "pas du json")["error"].is_string());
+        assert_eq!(env.handle(r#"{"cmd": "close"}"#), None);
+    }
+}
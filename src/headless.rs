@@ -0,0 +1,252 @@
+//! Module gérant l'exécution du jeu sans fenêtre.
+//!
+//! Le mode headless simule un nombre donné de frames puis affiche un résumé
+//! de la partie, ce qui permet de tester le jeu depuis un script.
+
+use std::fmt;
+
+use macroquad::prelude::*;
+
+use crate::api::RemoteBot;
+use crate::autopilot::Autopilot;
+use crate::cli::Options;
+use crate::input::{Controller, Input};
+use crate::locale::Strings;
+use crate::png_renderer::PngRenderer;
+use crate::render::draw_world;
+use crate::replay::Replay;
+use crate::text_renderer::{TextRenderer, DEFAULT_COLUMNS, DEFAULT_ROWS};
+use crate::world::{GameState, World};
+use crate::Difficulty;
+
+/// Résumé d'une partie simulée
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub frames: u64,
+    pub state: GameState,
+    pub score: u32,
+    pub wave: u32,
+    pub shield: u8,
+    pub asteroids: usize,
+    pub bullets: usize,
+}
+
+impl Summary {
+    /// Construit le résumé de l'état actuel d'une partie
+    pub fn new(seed: u64, world: &World) -> Self {
+        Self {
+            seed,
+            difficulty: world.difficulty,
+            frames: world.frame,
+            state: world.state,
+            score: world.score(),
+            wave: world.wave,
+            shield: world.spaceship().get_shield(),
+            asteroids: world.asteroids.len(),
+            bullets: world.bullets.len(),
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "seed: {}", self.seed)?;
+        writeln!(f, "difficulty: {}", self.difficulty.name())?;
+        writeln!(f, "frames: {}", self.frames)?;
+        writeln!(f, "state: {}", self.state.name())?;
+        writeln!(f, "score: {}", self.score)?;
+        writeln!(f, "wave: {}", self.wave)?;
+        writeln!(f, "shield: {}", self.shield)?;
+        writeln!(f, "asteroids: {}", self.asteroids)?;
+        write!(f, "bullets: {}", self.bullets)
+    }
+}
+
+/// Simule une partie sans fenêtre
+///
+/// Les commandes proviennent du replay s'il y en a un, sinon d'un bot avec
+/// `--api` (la simulation attend alors sa commande à chaque frame) ou du pilote
+/// automatique avec `--autopilot` ; à défaut le vaisseau n'est pas piloté. La simulation s'arrête après `options.frames` frames
+/// ou à la fin de la partie. Avec `--snapshot`, la dernière frame est
+/// enregistrée dans une image PNG.
+///
+/// # Retourne
+/// Le résumé de la partie et, avec `--ascii`, la dernière frame en mode texte
+pub fn run(options: &Options, seed: u64) -> Result<(Summary, Option<String>), String> {
+    let replay = match &options.replay {
+        Some(path) => Some(Replay::load(path)?),
+        None => None,
+    };
+
+    let (seed, mut world) = match &replay {
+        Some(replay) => (
+            replay.seed,
+            World::new(replay.seed, replay.difficulty, replay.size),
+        ),
+        None => (
+            seed,
+            World::new(
+                seed,
+                options.difficulty.unwrap_or(Difficulty::Easy),
+                vec2(options.width as f32, options.height as f32),
+            ),
+        ),
+    };
+
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
+    let mut bot = match options.api {
+        Some(port) if replay.is_none() => {
+            let bot = RemoteBot::bind(port, true)?;
+            eprintln!("{}", strings.format("game.api", &[&bot.port()]));
+            Some(bot)
+        }
+        _ => None,
+    };
+    let mut autopilot = options.autopilot.then(Autopilot::new);
+    while world.frame < options.frames && !world.is_over() {
+        let input = match (&replay, &mut bot, &mut autopilot) {
+            (Some(replay), _, _) => replay.input(world.frame),
+            // La simulation s'arrête si le bot se déconnecte
+            (None, Some(bot), _) => match bot.wait(&world, 0) {
+                Ok(input) => input,
+                Err(_) => break,
+            },
+            (None, None, Some(autopilot)) => autopilot.input(&world, 0),
+            (None, None, None) => Input::default(),
+        };
+        world.step(&input);
+    }
+    if let Some(bot) = &mut bot {
+        bot.finish(&world, 0);
+    }
+
+    if let Some(path) = &options.snapshot {
+        snapshot(&world, path, &strings)?;
+    }
+
+    let scene = options.ascii.then(|| {
+        let mut renderer = TextRenderer::new(DEFAULT_COLUMNS, DEFAULT_ROWS, world.size);
+        draw_world(&mut renderer, &world, &strings);
+        renderer.to_string()
+    });
+    Ok((Summary::new(seed, &world), scene))
+}
+
+/// Résultats du pilote automatique sur plusieurs parties d'une même difficulté
+///
+/// # Champs
+/// * `difficulty` - Difficulté testée
+/// * `games` - Nombre de parties simulées
+/// * `won` / `lost` - Parties gagnées et perdues (les autres ont atteint la limite de frames)
+/// * `score` - Score moyen
+/// * `frames` - Durée moyenne d'une partie en frames
+#[derive(Debug, Clone, PartialEq)]
+pub struct Balance {
+    pub difficulty: Difficulty,
+    pub games: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub score: u32,
+    pub frames: u64,
+}
+
+impl fmt::Display for Balance {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:<10} {:>6} {:>6} {:>6} {:>8} {:>8}",
+            self.difficulty.name(),
+            self.games,
+            self.won,
+            self.lost,
+            self.score,
+            self.frames
+        )
+    }
+}
+
+/// En-tête du tableau affiché par `--balance`
+pub const BALANCE_HEADER: &str = "difficulty  games    won   lost    score   frames";
+
+/// Teste l'équilibrage des difficultés avec le pilote automatique
+///
+/// Chaque difficulté est jouée `games` fois, avec les graines `seed`,
+/// `seed + 1`... pour que les résultats soient reproductibles.
+pub fn balance(options: &Options, games: u32, seed: u64) -> Vec<Balance> {
+    let size = vec2(options.width as f32, options.height as f32);
+    [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard]
+        .into_iter()
+        .map(|difficulty| {
+            let mut result = Balance {
+                difficulty,
+                games,
+                won: 0,
+                lost: 0,
+                score: 0,
+                frames: 0,
+            };
+            let (mut score, mut frames) = (0, 0);
+            for game in 0..games {
+                let mut world = World::new(seed.wrapping_add(game as u64), difficulty, size);
+                let mut autopilot = Autopilot::new();
+                while world.frame < options.frames && !world.is_over() {
+                    let input = autopilot.input(&world, 0);
+                    world.step(&input);
+                }
+                match world.state {
+                    GameState::Won => result.won += 1,
+                    GameState::Lost => result.lost += 1,
+                    GameState::Running => {}
+                }
+                score += world.score() as u64;
+                frames += world.frame;
+            }
+            let games = games.max(1) as u64;
+            result.score = (score / games) as u32;
+            result.frames = frames / games;
+            result
+        })
+        .collect()
+}
+
+/// Enregistre l'état d'une partie dans une image PNG, sans contexte graphique
+pub fn snapshot(world: &World, path: &str, strings: &Strings) -> Result<(), String> {
+    let mut renderer = PngRenderer::new(world.size);
+    draw_world(&mut renderer, world, strings);
+    renderer.save(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn simulation_headless() {
+        let options = Options {
+            frames: 120,
+            ..Default::default()
+        };
+        let (summary, scene) = run(&options, 5).unwrap();
+        assert_eq!(summary.seed, 5);
+        assert!(summary.frames <= 120);
+        assert_eq!(scene, None);
+        assert_eq!(summary, run(&options, 5).unwrap().0);
+    }
+
+    #[test]
+    fn equilibrage() {
+        let options = Options {
+            frames: 600,
+            ..Default::default()
+        };
+        let results = balance(&options, 2, 3);
+        assert_eq!(results.len(), 3);
+        for result in &results {
+            assert_eq!(result.games, 2);
+            assert!(result.won + result.lost <= 2);
+        }
+        assert_eq!(results, balance(&options, 2, 3));
+    }
+}
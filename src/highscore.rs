@@ -0,0 +1,61 @@
+//! Module gérant le meilleur score, affiché par le HUD.
+//!
+//! Le meilleur score des parties classiques, dans une difficulté prédéfinie,
+//! est un simple nombre, écrit dans un fichier du dossier de données de
+//! l'utilisateur.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::paths::user_data_dir;
+
+/// Nom du fichier du meilleur score
+const HIGHSCORE_FILE: &str = "highscore";
+
+/// Chemin du meilleur score, dans le dossier de données de l'utilisateur
+pub fn default_highscore_path() -> PathBuf {
+    user_data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(HIGHSCORE_FILE)
+}
+
+/// Lit le meilleur score, 0 si aucun n'a encore été enregistré ou si le fichier est invalide
+pub fn load(path: &Path) -> u32 {
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| content.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Enregistre le score s'il bat le meilleur score
+///
+/// # Retourne
+/// Le meilleur score après la partie
+pub fn record(path: &Path, score: u32) -> Result<u32, String> {
+    let best = load(path);
+    if score <= best {
+        return Ok(best);
+    }
+    let error = |e: std::io::Error| format!("Impossible d'écrire '{}' : {e}", path.display());
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(error)?;
+    }
+    fs::write(path, score.to_string()).map_err(error)?;
+    Ok(score)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn meilleur_score_conserve() {
+        let path = std::env::temp_dir().join(format!("asteroid-highscore-{}", std::process::id()));
+        let _ = fs::remove_file(&path);
+        assert_eq!(load(&path), 0);
+        assert_eq!(record(&path, 120), Ok(120));
+        assert_eq!(record(&path, 80), Ok(120));
+        assert_eq!(load(&path), 120);
+        fs::remove_file(&path).unwrap();
+    }
+}
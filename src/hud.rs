@@ -0,0 +1,274 @@
+//! Module dessinant l'interface affichée pendant la partie (HUD).
+//!
+//! Chaque élément est placé par rapport à un coin de l'arène (`Anchor`), ce qui
+//! le garde en place quand la fenêtre change de taille :
+//!
+//! * en haut à gauche, le score et le meilleur score ;
+//! * en haut à droite, la vague (s'il y en a plusieurs) et les astéroïdes restants ;
+//! * en bas, un bloc par joueur (à droite pour le premier, à gauche pour le
+//!   second) : jauge de bouclier, recharge du tir et de l'hyperespace, durée
+//!   des effets en cours et vaisseaux restants.
+//!
+//! Le dessin passe par le trait `Renderer`, comme le reste de la partie.
+
+use macroquad::prelude::*;
+
+use crate::locale::Strings;
+use crate::render::{player_color, Renderer};
+use crate::world::{GameMode, World};
+
+/// Marge entre les éléments et le bord de l'arène
+const MARGIN: f32 = 20.0;
+
+/// Hauteur réservée en haut de l'arène aux boutons 'quitter' et 'rejouer'
+const BUTTONS_HEIGHT: f32 = 30.0;
+
+/// Hauteur d'une ligne du HUD
+const LINE_HEIGHT: f32 = 25.0;
+
+/// Taille de police des textes du HUD
+const TEXT_SIZE: u16 = 20;
+
+/// Largeur réservée au libellé à gauche d'une jauge
+const LABEL_WIDTH: f32 = 120.0;
+
+/// Taille d'une jauge
+const GAUGE_SIZE: Vec2 = vec2(120.0, 12.0);
+
+/// Part du bouclier au-dessus de laquelle la jauge est verte, puis orange
+const SHIELD_THRESHOLDS: [f32; 2] = [0.6, 0.3];
+
+/// Couleur d'une jauge de recharge, pendant la recharge puis une fois prête
+const COOLDOWN_COLORS: [Color; 2] = [GRAY, SKYBLUE];
+
+/// Coin de l'arène auquel un élément est accroché
+///
+/// Les coins du haut laissent la place aux boutons de la partie.
+///
+/// # Variantes
+/// * `TopLeft` / `TopRight` - Coins du haut, les lignes descendent
+/// * `BottomLeft` / `BottomRight` - Coins du bas, les lignes montent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Coin supérieur gauche d'un élément accroché à ce coin
+    ///
+    /// # Arguments
+    /// * `arena` - Dimensions de l'arène
+    /// * `size` - Taille de l'élément
+    /// * `line` - Ligne de l'élément, en partant du coin
+    pub fn place(&self, arena: Vec2, size: Vec2, line: usize) -> Vec2 {
+        let offset = line as f32 * LINE_HEIGHT;
+        let x = match self {
+            Anchor::TopLeft | Anchor::BottomLeft => MARGIN,
+            Anchor::TopRight | Anchor::BottomRight => arena.x - MARGIN - size.x,
+        };
+        let y = match self {
+            Anchor::TopLeft | Anchor::TopRight => MARGIN + BUTTONS_HEIGHT + offset,
+            Anchor::BottomLeft | Anchor::BottomRight => arena.y - MARGIN - size.y - offset,
+        };
+        vec2(x, y)
+    }
+}
+
+/// Coin du bloc d'un joueur : à droite pour le premier, à gauche pour le second
+pub fn player_anchor(player: usize) -> Anchor {
+    match player {
+        0 => Anchor::BottomRight,
+        _ => Anchor::BottomLeft,
+    }
+}
+
+/// Couleur de la jauge de bouclier : verte, orange puis rouge quand il baisse
+pub fn shield_color(shield: u8, max: u8) -> Color {
+    let ratio = shield as f32 / max.max(1) as f32;
+    if ratio > SHIELD_THRESHOLDS[0] {
+        GREEN
+    } else if ratio > SHIELD_THRESHOLDS[1] {
+        ORANGE
+    } else {
+        RED
+    }
+}
+
+/// Interface affichée pendant la partie
+///
+/// # Champs
+/// * `strings` - Textes de l'interface dans la langue du joueur
+/// * `high_score` - Meilleur score, affiché s'il est connu
+pub struct Hud<'a> {
+    strings: &'a Strings,
+    high_score: Option<u32>,
+}
+
+impl<'a> Hud<'a> {
+    /// Crée le HUD, sans meilleur score
+    pub fn new(strings: &'a Strings) -> Self {
+        Self {
+            strings,
+            high_score: None,
+        }
+    }
+
+    /// Affiche aussi le meilleur score
+    pub fn with_high_score(mut self, high_score: u32) -> Self {
+        self.high_score = Some(high_score);
+        self
+    }
+
+    /// Dessine le HUD d'une partie ; le mode test n'en a pas
+    pub fn draw(&self, renderer: &mut impl Renderer, world: &World) {
+        if world.sandbox {
+            return;
+        }
+        let strings = self.strings;
+
+        // En duel, le score est affiché par la manche et les astéroïdes sont des obstacles
+        if world.mode == GameMode::Classic {
+            let mut lines = vec![strings.format("hud.score", &[&world.score()])];
+            if let Some(high_score) = self.high_score {
+                let best = high_score.max(world.score());
+                lines.push(strings.format("hud.high_score", &[&best]));
+            }
+            self.draw_lines(renderer, world.size, Anchor::TopLeft, &lines, WHITE);
+
+            // La vague n'est affichée que si la difficulté en compte plusieurs
+            let waves = world.difficulty.params().waves;
+            let mut lines = vec![strings.format("hud.asteroids", &[&world.asteroids.len()])];
+            if waves > 1 {
+                lines.insert(0, strings.format("hud.wave", &[&world.wave, &waves]));
+            }
+            self.draw_lines(renderer, world.size, Anchor::TopRight, &lines, WHITE);
+        }
+
+        for i in 0..world.players.len() {
+            self.draw_player(renderer, world, i);
+        }
+    }
+
+    /// Dessine des lignes de texte accrochées à un coin
+    fn draw_lines(
+        &self,
+        renderer: &mut impl Renderer,
+        arena: Vec2,
+        anchor: Anchor,
+        lines: &[String],
+        color: Color,
+    ) {
+        for (line, text) in lines.iter().enumerate() {
+            let size = vec2(renderer.measure_text(text, TEXT_SIZE), TEXT_SIZE as f32);
+            draw_line_text(renderer, text, anchor.place(arena, size, line), color);
+        }
+    }
+
+    /// Dessine le bloc d'un joueur, de bas en haut : bouclier, recharges, effets
+    /// en cours, vaisseaux restants et, à plusieurs, son nom et son score
+    fn draw_player(&self, renderer: &mut impl Renderer, world: &World, i: usize) {
+        let strings = self.strings;
+        let player = &world.players[i];
+        let anchor = player_anchor(i);
+        let row = vec2(LABEL_WIDTH + GAUGE_SIZE.x, TEXT_SIZE as f32);
+        let mut line = 0;
+
+        if player.is_alive() {
+            let spaceship = &player.spaceship;
+            let max = world.difficulty.params().shield as u8;
+            let shield = spaceship.get_shield();
+            let gauges = [
+                (
+                    strings.get("hud.shield"),
+                    shield as f32 / max.max(1) as f32,
+                    shield_color(shield, max),
+                ),
+                cooldown_gauge(strings.get("hud.fire"), world.fire_charge(i)),
+                cooldown_gauge(strings.get("hud.hyperspace"), world.jump_charge(i)),
+            ];
+            for (label, ratio, color) in gauges {
+                let pos = anchor.place(world.size, row, line);
+                draw_line_text(renderer, label, pos, WHITE);
+                let gauge = Rect::new(
+                    pos.x + LABEL_WIDTH,
+                    pos.y + (row.y - GAUGE_SIZE.y) / 2.,
+                    GAUGE_SIZE.x,
+                    GAUGE_SIZE.y,
+                );
+                renderer.draw_gauge(gauge, ratio, color);
+                line += 1;
+            }
+
+            // Effets en cours : seule l'invincibilité après un choc est limitée dans le temps
+            let invincibility = spaceship.get_invicibility();
+            if invincibility > 0.0 {
+                let text = strings.format("hud.invincible", &[&format!("{invincibility:.1}")]);
+                let pos = anchor.place(world.size, row, line);
+                draw_line_text(renderer, &text, pos, SKYBLUE);
+                line += 1;
+            }
+        } else {
+            // Un joueur sans vaisseau restant ne réapparaîtra pas
+            let key = if player.lives > 0 {
+                "hud.respawn"
+            } else {
+                "hud.no_lives"
+            };
+            let pos = anchor.place(world.size, row, line);
+            draw_line_text(renderer, strings.get(key), pos, RED);
+            line += 1;
+        }
+
+        // En duel, une manche se joue avec un seul vaisseau
+        if world.mode == GameMode::Classic {
+            let text = strings.format("hud.lives", &[&player.lives]);
+            let pos = anchor.place(world.size, row, line);
+            draw_line_text(renderer, &text, pos, WHITE);
+            line += 1;
+        }
+
+        if world.players.len() > 1 {
+            let text = strings.format("hud.player_score", &[&(i + 1), &player.score]);
+            let pos = anchor.place(world.size, row, line);
+            draw_line_text(renderer, &text, pos, player_color(i));
+        }
+    }
+}
+
+/// Jauge de recharge : libellé, part écoulée et couleur selon qu'elle est prête
+fn cooldown_gauge(label: &str, charge: f32) -> (&str, f32, Color) {
+    let color = COOLDOWN_COLORS[(charge >= 1.0) as usize];
+    (label, charge, color)
+}
+
+/// Dessine une ligne de texte dans la ligne du HUD commençant à la position donnée
+fn draw_line_text(renderer: &mut impl Renderer, text: &str, top_left: Vec2, color: Color) {
+    let baseline = top_left + vec2(0., TEXT_SIZE as f32);
+    renderer.draw_text(text, baseline, TEXT_SIZE, color);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn placement_aux_coins() {
+        let arena = vec2(800., 600.);
+        let size = vec2(100., 20.);
+        assert_eq!(Anchor::TopLeft.place(arena, size, 0), vec2(20., 50.));
+        assert_eq!(Anchor::TopRight.place(arena, size, 1), vec2(680., 75.));
+        assert_eq!(Anchor::BottomRight.place(arena, size, 0), vec2(680., 560.));
+        assert_eq!(Anchor::BottomLeft.place(arena, size, 2), vec2(20., 510.));
+    }
+
+    #[test]
+    fn couleur_du_bouclier() {
+        assert_eq!(shield_color(10, 10), GREEN);
+        assert_eq!(shield_color(5, 10), ORANGE);
+        assert_eq!(shield_color(3, 10), RED);
+        assert_eq!(shield_color(0, 0), RED);
+    }
+}
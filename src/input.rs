@@ -0,0 +1,186 @@
+//! Module représentant les commandes envoyées au vaisseau.
+//!
+//! Ce module contient la structure `Input` qui décrit les touches pressées
+//! pendant une frame, indépendamment de leur provenance (clavier, replay...),
+//! et le trait `Controller` implémenté par tout ce qui pilote le vaisseau.
+
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::world::World;
+
+/// Source de commandes du vaisseau (clavier, pilote automatique...)
+pub trait Controller {
+    /// Commandes à appliquer pour la prochaine frame de la partie
+    ///
+    /// # Arguments
+    /// * `world` - Partie en cours
+    /// * `player` - Numéro du joueur piloté
+    fn input(&mut self, world: &World, player: usize) -> Input;
+
+    /// Vérifie si les commandes de la prochaine frame sont disponibles
+    ///
+    /// La partie est figée tant qu'un pilote n'est pas prêt, par exemple un
+    /// bot en lockstep qui n'a pas encore répondu.
+    fn is_ready(&mut self, _world: &World, _player: usize) -> bool {
+        true
+    }
+}
+
+/// Touches associées à chaque commande
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBindings {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub up: KeyCode,
+    pub down: KeyCode,
+    pub fire: KeyCode,
+    pub hyperspace: KeyCode,
+}
+
+/// Touches du premier joueur : flèches, espace et majuscule droite
+pub const ARROW_KEYS: KeyBindings = KeyBindings {
+    left: KeyCode::Left,
+    right: KeyCode::Right,
+    up: KeyCode::Up,
+    down: KeyCode::Down,
+    fire: KeyCode::Space,
+    hyperspace: KeyCode::RightShift,
+};
+
+/// Touches du second joueur : WASD, F et G
+pub const WASD_KEYS: KeyBindings = KeyBindings {
+    left: KeyCode::A,
+    right: KeyCode::D,
+    up: KeyCode::W,
+    down: KeyCode::S,
+    fire: KeyCode::F,
+    hyperspace: KeyCode::G,
+};
+
+/// Commandes lues au clavier
+pub struct Keyboard {
+    keys: KeyBindings,
+}
+
+impl Keyboard {
+    /// Crée un contrôleur clavier avec les touches données
+    pub fn new(keys: KeyBindings) -> Self {
+        Self { keys }
+    }
+}
+
+impl Controller for Keyboard {
+    fn input(&mut self, _world: &World, _player: usize) -> Input {
+        Input::from_keys(&self.keys)
+    }
+}
+
+/// Commandes du vaisseau pour une frame
+///
+/// # Champs
+/// * `left` - Tourner à gauche
+/// * `right` - Tourner à droite
+/// * `up` - Accélérer
+/// * `down` - Freiner / reculer
+/// * `fire` - Tirer un missile
+/// * `hyperspace` - Sauter en hyperespace
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Input {
+    pub left: bool,
+    pub right: bool,
+    pub up: bool,
+    pub down: bool,
+    pub fire: bool,
+    pub hyperspace: bool,
+}
+
+impl Input {
+    /// Lit l'état actuel du clavier avec les touches données
+    pub fn from_keys(keys: &KeyBindings) -> Self {
+        Self {
+            left: is_key_down(keys.left),
+            right: is_key_down(keys.right),
+            up: is_key_down(keys.up),
+            down: is_key_down(keys.down),
+            fire: is_key_down(keys.fire),
+            hyperspace: is_key_down(keys.hyperspace),
+        }
+    }
+
+    /// Vérifie si aucune touche de déplacement n'est pressée
+    pub fn is_idle(&self) -> bool {
+        !self.left && !self.right && !self.up && !self.down
+    }
+
+    /// Encode les commandes sous forme de 6 caractères (`LRUDFH`, `.` si relâché)
+    ///
+    /// # Exemple
+    /// ```
+    /// let input = Input { up: true, fire: true, ..Default::default() };
+    /// assert_eq!(input.encode(), "..U.F.");
+    /// ```
+    pub fn encode(&self) -> String {
+        [
+            (self.left, 'L'),
+            (self.right, 'R'),
+            (self.up, 'U'),
+            (self.down, 'D'),
+            (self.fire, 'F'),
+            (self.hyperspace, 'H'),
+        ]
+        .iter()
+        .map(|&(pressed, c)| if pressed { c } else { '.' })
+        .collect()
+    }
+
+    /// Décode des commandes encodées par `encode`
+    ///
+    /// Les chaînes de 5 caractères (`LRUDF`), écrites avant l'ajout de
+    /// l'hyperespace, sont toujours acceptées.
+    ///
+    /// # Retourne
+    /// Les commandes ou une erreur si la chaîne est mal formée
+    pub fn decode(text: &str) -> Result<Self, String> {
+        let chars: Vec<char> = text.chars().collect();
+        if chars.len() != 5 && chars.len() != 6 {
+            return Err(format!("Commandes invalides : '{text}'"));
+        }
+        let mut flags = [false; 6];
+        for (i, (&c, expected)) in chars.iter().zip("LRUDFH".chars()).enumerate() {
+            flags[i] = match c {
+                '.' => false,
+                c if c == expected => true,
+                _ => return Err(format!("Commandes invalides : '{text}'")),
+            };
+        }
+        Ok(Self {
+            left: flags[0],
+            right: flags[1],
+            up: flags[2],
+            down: flags[3],
+            fire: flags[4],
+            hyperspace: flags[5],
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn encodage_commandes() {
+        let input = Input {
+            left: true,
+            fire: true,
+            ..Default::default()
+        };
+        assert_eq!(input.encode(), "L...F.");
+        assert_eq!(Input::decode("L...F.").unwrap(), input);
+        assert_eq!(Input::decode("L...F").unwrap(), input);
+        assert!(Input::decode("LX..F").is_err());
+        assert!(Input::decode("L...FX").is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! Module gérant la traduction des textes de l'interface.
+//!
+//! Chaque langue a une table de textes `lang/<code>.lang`, une suite de lignes
+//! `clé = texte` où `{0}`, `{1}`... sont remplacés par des valeurs :
+//!
+//! ```text
+//! menu.easy = Facile
+//! hud.score = Score : {0}
+//! ```
+//!
+//! Les tables sont lues dans le dossier de données, comme les autres
+//! ressources ; une copie est intégrée au jeu pour qu'il reste utilisable sans
+//! elles, et complète les clés absentes d'une table modifiée.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+use crate::assets::{resolve, search_dirs};
+use crate::config::split_entry;
+use crate::settings::Language;
+
+/// Tables intégrées au jeu
+const FRENCH: &str = include_str!("../lang/fr.lang");
+const ENGLISH: &str = include_str!("../lang/en.lang");
+
+/// Textes de l'interface dans une langue
+///
+/// # Champs
+/// * `table` - Texte de chaque clé
+#[derive(Debug, Clone, PartialEq)]
+pub struct Strings {
+    table: HashMap<String, String>,
+}
+
+impl Default for Strings {
+    /// Textes intégrés en français
+    fn default() -> Self {
+        Self::builtin(Language::French)
+    }
+}
+
+impl Strings {
+    /// Analyse une table de textes
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut table = HashMap::new();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, text) =
+                split_entry(line).ok_or_else(|| format!("ligne {} : '=' attendu", number + 1))?;
+            table.insert(key.to_string(), text.to_string());
+        }
+        Ok(Self { table })
+    }
+
+    /// Textes intégrés au jeu
+    pub fn builtin(language: Language) -> Self {
+        let content = match language {
+            Language::French => FRENCH,
+            Language::English => ENGLISH,
+        };
+        // Les tables intégrées sont vérifiées par les tests
+        Self::parse(content).unwrap_or_else(|_| Self {
+            table: HashMap::new(),
+        })
+    }
+
+    /// Charge les textes d'une langue depuis le dossier de données
+    ///
+    /// Une table absente est remplacée par la table intégrée ; une table
+    /// invalide ou incomplète est signalée et complétée par celle-ci.
+    ///
+    /// # Arguments
+    /// * `language` - Langue de l'interface
+    /// * `data_dir` - Dossier de données configuré (ligne de commande ou configuration)
+    pub fn load(language: Language, data_dir: Option<&str>) -> Self {
+        let mut strings = Self::builtin(language);
+        let relative = format!("lang/{}.lang", language.name());
+        let Some(path) = resolve(&search_dirs(data_dir), &relative) else {
+            return strings;
+        };
+        let loaded = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| Self::parse(&content));
+        match loaded {
+            Ok(loaded) => {
+                let missing = loaded.missing_keys(&strings);
+                if !missing.is_empty() {
+                    eprintln!(
+                        "{}",
+                        strings.format(
+                            "error.missing_keys",
+                            &[&path.display(), &missing.join(", ")]
+                        )
+                    );
+                }
+                strings.table.extend(loaded.table);
+            }
+            Err(e) => eprintln!(
+                "{}",
+                strings.format("error", &[&format!("{} : {e}", path.display())])
+            ),
+        }
+        strings
+    }
+
+    /// Clés d'une table de référence absentes de cette table, triées
+    pub fn missing_keys<'a>(&self, reference: &'a Strings) -> Vec<&'a str> {
+        let mut missing: Vec<&str> = reference
+            .table
+            .keys()
+            .filter(|key| !self.table.contains_key(*key))
+            .map(String::as_str)
+            .collect();
+        missing.sort_unstable();
+        missing
+    }
+
+    /// Texte d'une clé, ou la clé elle-même si elle est absente
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.table.get(key).map_or(key, String::as_str)
+    }
+
+    /// Texte d'une clé dont `{0}`, `{1}`... sont remplacés par les valeurs données
+    pub fn format(&self, key: &str, values: &[&dyn fmt::Display]) -> String {
+        values
+            .iter()
+            .enumerate()
+            .fold(self.get(key).to_string(), |text, (i, value)| {
+                text.replace(&format!("{{{i}}}"), &value.to_string())
+            })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn toutes_les_cles_traduites() {
+        let french = Strings::builtin(Language::French);
+        let english = Strings::builtin(Language::English);
+        assert!(Strings::parse(FRENCH).is_ok() && Strings::parse(ENGLISH).is_ok());
+        assert!(!french.table.is_empty());
+        assert_eq!(english.missing_keys(&french), Vec::<&str>::new());
+        assert_eq!(french.missing_keys(&english), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn textes_avec_valeurs() {
+        let strings = Strings::parse("hud.score = Score : {0}\nduel = J1 {0} - {1} J2").unwrap();
+        assert_eq!(strings.format("hud.score", &[&12]), "Score : 12");
+        assert_eq!(strings.format("duel", &[&2, &1]), "J1 2 - 1 J2");
+        assert_eq!(strings.get("inconnue"), "inconnue");
+        assert!(Strings::parse("sans egal").is_err());
+    }
+}
@@ -5,20 +5,74 @@
 //! * Les interactions utilisateur
 //! * La logique de collision
 //! * La gestion des états du jeu (victoire/défaite)
-
-use asteroid::Asteroid;
-use bullet::Bullet;
+//! * Les options de lancement (ligne de commande, mode headless)
+
+use std::str::FromStr;
+
+use api::RemoteBot;
+use assets::Assets;
+use autopilot::Autopilot;
+use button::{Button, ButtonStyle};
+use cli::{Options, USAGE};
+use custom::CustomDifficulty;
+use custom_menu::CustomMenu;
+use debug::DebugOverlay;
+use effects::HitEffects;
+use gamepad::Gamepad;
+use hud::Hud;
+use input::{Controller, Input, Keyboard};
+use locale::Strings;
 use macroquad::prelude::*;
-use menu::Menu;
-use spaceship::Spaceship;
-use stellarobject::StellarObject;
-
+use menu::{Menu, PlayMode};
+use pause::{PauseMenu, PAUSE_KEY};
+use render::MacroquadRenderer;
+use replay::Replay;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
+use settings_menu::SettingsMenu;
+use ui::{Focus, NavAction, Navigation};
+use versus::Match;
+use world::{GameMode, GameState, World};
+
+mod api;
+mod assets;
 mod asteroid;
+mod autopilot;
 mod bullet;
 mod button;
+mod cli;
+mod config;
+mod custom;
+mod custom_menu;
+mod debug;
+mod effects;
+mod gamepad;
+mod gym;
+mod headless;
+mod highscore;
+mod hud;
+mod input;
+mod locale;
 mod menu;
+mod net;
+mod paths;
+mod pause;
+mod png_renderer;
+mod render;
+mod replay;
+mod rng;
+mod rollback;
+mod save;
+mod settings;
+mod settings_menu;
 mod spaceship;
+mod spectate;
 mod stellarobject;
+mod text_renderer;
+mod tournament;
+mod ui;
+mod versus;
+mod world;
 
 /// Niveaux de difficulté du jeu
 ///
@@ -26,669 +80,848 @@ mod stellarobject;
 /// * `Easy` - Mode facile avec 3 astéroïdes
 /// * `Medium` - Mode moyen avec 6 astéroïdes
 /// * `Hard` - Mode difficile avec 10 astéroïdes
-#[derive(Debug, Clone, Copy)]
+/// * `Custom` - Difficulté réglée par le joueur dans l'éditeur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Difficulty {
     Easy,
     Medium,
     Hard,
+    Custom(CustomDifficulty),
 }
 
-/// Fonction principale de rendu graphique
-///
-/// # Arguments
-/// * `background` - Texture de l'arrière-plan
-/// * `asteroids` - Vecteur contenant tous les astéroïdes
-/// * `spaceship` - Instance du vaisseau spatial
-/// * `spaceship_skin` - Image du vaisseau
-/// * `parametres` - Parametres liés à l'affichage du vaisseau
-/// * `asteroid_skin` - Images des astéroides
-/// * `parametres2` - Parametres liés à l'affichage des asteroides
-/// * `bullets` - Vecteur contenant tous les projectiles
-/// * `shield` - Points de bouclier actuels
-/// * `font` - Police de caractères pour le texte
-fn draw(
-    background: &Texture2D,
-    spaceship: &Spaceship,
-    spaceship_skin: &Texture2D,
-    parametres: &DrawTextureParams,
-    asteroids: &Vec<Asteroid>,
-    asteroid_skin: &Texture2D,
-    parametres2: &DrawTextureParams,
-    bullets: &Vec<Bullet>,
-    shield: &u8,
-    font: &Font,
-) {
-    // affichage du fond d'écran
-    draw_background(background);
-
-    // affichage de l'image du vaisseau
-    draw_spaceship_skin(
-        spaceship_skin,
-        spaceship.get_position().x - 35.,
-        spaceship.get_position().y - 30.,
-        parametres,
-    );
-
-    // On affiche l'image de tous les asteroides en fonction de leur tailles
-    for a in asteroids {
-        // Selon la taille du l'asteroide (ou de la "hitbox"), on modifie la taille et la position de l'image de l'asteroide
-        // de manière à ce que ces paramètres soient identique à ceux de la "hitbox"
-        if a.get_width() == 25. {
-            let new_parametres2 = DrawTextureParams {
-                dest_size: Some(vec2(50.0, 50.0)), // changement de la taille du vaisseau
-                ..Default::default()
-            };
-            draw_asteroid_skin(
-                asteroid_skin,
-                a.get_position().x - 25., // changement de la position de l'image par rapport à sa "hitbox"
-                a.get_position().y - 25.,
-                &new_parametres2,
-            );
+impl Difficulty {
+    /// Nom de la difficulté utilisé dans les résumés
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom(_) => "custom",
+        }
+    }
 
-            // si la taille de la "hitbox" de l'asteroide est 15, on modifie la taille de l'image de l'asteroide
-        } else if a.get_width() == 15. {
-            let new_parametres2 = DrawTextureParams {
-                dest_size: Some(vec2(30.0, 30.0)), // changement de la taille du vaisseau
-                ..Default::default()
-            };
-            draw_asteroid_skin(
-                asteroid_skin,
-                a.get_position().x - 15., // changement de la position de l'image par rapport à sa "hitbox"
-                a.get_position().y - 15.,
-                &new_parametres2,
-            );
-        } else {
-            // si l'asteroide est de taille initial (50), on garde la taille définit dans la fonction main
-            draw_asteroid_skin(
-                asteroid_skin,
-                a.get_position().x - 50.,
-                a.get_position().y - 50.,
-                parametres2,
-            );
+    /// Nom de la difficulté, ou code de partage d'une difficulté
+    /// personnalisée, utilisé en ligne de commande et dans les fichiers
+    pub fn code(&self) -> String {
+        match self {
+            Difficulty::Custom(custom) => custom.code(),
+            _ => self.name().to_string(),
         }
     }
 
-    for b in bullets {
-        draw_bullet(b);
+    /// Paramètres de la partie pour cette difficulté
+    pub fn params(&self) -> CustomDifficulty {
+        match self {
+            Difficulty::Easy => CustomDifficulty::standard(3),
+            Difficulty::Medium => CustomDifficulty::standard(6),
+            Difficulty::Hard => CustomDifficulty::standard(10),
+            Difficulty::Custom(custom) => *custom,
+        }
     }
-    draw_shield(shield, font);
 }
 
-/// Fonction de rendu graphique pour le test 1
-/// Test 1 : Affichage d'un vaisseau et d'un missile
-///
-/// # Arguments
-/// * `background` - Texture de l'arrière-plan
-/// * `spaceship` - Instance du vaisseau spatial
-/// * `spaceship_skin` - Texture du vaisseau
-/// * `parametres` - Paramètres d'affichage du vaisseau
-/// * `bullets` - Vecteur contenant tous les projectiles    
-fn draw_test_1(
-    background: &Texture2D,
-    spaceship: &Spaceship,
-    spaceship_skin: &Texture2D,
-    parametres: &DrawTextureParams,
-    bullets: &Vec<Bullet>,
-) {
-    draw_background(background);
-
-    draw_spaceship_skin(
-        spaceship_skin,
-        spaceship.get_position().x - 35.,
-        spaceship.get_position().y - 30.,
-        parametres,
-    );
-
-    for b in bullets {
-        draw_bullet(b);
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" | "facile" => Ok(Difficulty::Easy),
+            "medium" | "moyen" => Ok(Difficulty::Medium),
+            "hard" | "difficile" => Ok(Difficulty::Hard),
+            // Un code de partage commence par le nombre d'astéroïdes
+            code if code.starts_with(|c: char| c.is_ascii_digit()) => {
+                CustomDifficulty::from_code(code).map(Difficulty::Custom)
+            }
+            _ => Err(format!("difficulté inconnue '{s}'")),
+        }
     }
 }
 
-/// Dessine l'arrière-plan du jeu
-///
-/// # Arguments
-/// * `background` - Texture à utiliser pour l'arrière-plan
-fn draw_background(background: &Texture2D) {
-    draw_texture(background, 0., 0., WHITE);
+/// Touche enregistrant l'état de la partie dans une image PNG
+const SNAPSHOT_KEY: KeyCode = KeyCode::F12;
+
+/// Configuration de la fenêtre selon les options de lancement et les
+/// préférences du joueur
+fn window_conf(options: &Options, settings: &Settings) -> Conf {
+    Conf {
+        window_title: "BasicShapes".to_string(),
+        window_width: options.width as i32,
+        window_height: options.height as i32,
+        fullscreen: options.fullscreen,
+        // La taille de l'arène doit rester fixe pour enregistrer ou rejouer une
+        // partie, et la même pour l'hôte et le client d'une partie en réseau
+        window_resizable: options.replay.is_none()
+            && options.record.is_none()
+            && !options.host
+            && options.join.is_none()
+            && options.peer.is_none(),
+        platform: macroquad::miniquad::conf::Platform {
+            swap_interval: Some(settings.vsync as i32),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
 }
 
-/// Dessine l'image du vaisseau
+/// Point d'entrée du programme
 ///
-/// # Arguments
-/// * `spaceship_skin` - Texture du vaisseau à dessiner
-/// * `pos_x` - Position x du vaisseau
-/// * `pos_y` - Position y du vaisseau
-/// * `params` - Paramètres d'affichage du vaisseau
-fn draw_spaceship_skin(
-    spaceship_skin: &Texture2D,
-    pos_x: f32,
-    pos_y: f32,
-    params: &DrawTextureParams,
-) {
-    draw_texture_ex(spaceship_skin, pos_x, pos_y, WHITE, params.clone());
-}
+/// Analyse la ligne de commande puis lance soit la simulation headless,
+/// soit la fenêtre de jeu.
+fn main() {
+    // Les préférences du joueur sont appliquées avant d'ouvrir la fenêtre
+    let settings_path = settings::default_settings_path();
+    let settings = Settings::load_or_default(&settings_path);
+    let mut options = match Options::parse(std::env::args().skip(1), &settings) {
+        Ok(options) => options,
+        Err(e) => {
+            let strings = Strings::builtin(settings.language);
+            eprintln!("{}\n\n{USAGE}", strings.format("error", &[&e]));
+            std::process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{USAGE}");
+        return;
+    }
+    // Textes des messages de la console
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
 
-/// Dessine l'image de l'asteroide
-///
-/// # Arguments
-/// * `asteroid_skin` - Texture de l'asteroide à dessiner
-/// * `pos_x` - Position x de l'asteroide
-/// * `pos_y` - Position y de l'asteroide
-/// * `params` - Paramètres d'affichage de l'asteroide  
-fn draw_asteroid_skin(
-    asteroid_skin: &Texture2D,
-    pos_x: f32,
-    pos_y: f32,
-    params: &DrawTextureParams,
-) {
-    draw_texture_ex(asteroid_skin, pos_x, pos_y, WHITE, params.clone());
-}
+    let seed = options.seed.unwrap_or_else(::rand::random);
 
-/// Dessine un projectile sous forme de cercle
-///
-/// # Arguments
-/// * `bullet` - Instance du projectile à dessiner
-fn draw_bullet(bullet: &Bullet) {
-    draw_circle(
-        bullet.get_position().x,
-        bullet.get_position().y,
-        bullet.get_size(),
-        RED,
-    );
-}
+    if let Some(games) = options.balance {
+        println!("{}", headless::BALANCE_HEADER);
+        for result in headless::balance(&options, games, seed) {
+            println!("{result}");
+        }
+        return;
+    }
 
-/// Affiche la barre de bouclier du vaisseau
-///
-/// # Arguments
-/// * `shield` - Nombre de points de bouclier restants
-/// * `font` - Police de caractères à utiliser
-fn draw_shield(shield: &u8, font: &Font) {
-    let mut text = String::from("Bouclier : ");
-    for _i in 0..*shield {
-        text.push('|');
+    if let Some(games) = options.tournament {
+        let standings =
+            tournament::run(&options, games, seed).and_then(|standings| match &options.report {
+                Some(path) => tournament::save_report(&standings, path)
+                    .map(|()| println!("{}", strings.format("tournament.report", &[path]))),
+                None => {
+                    println!("{}", tournament::to_csv(&standings));
+                    Ok(())
+                }
+            });
+        if let Err(e) = standings {
+            eprintln!("{}", strings.format("error", &[&e]));
+            std::process::exit(1);
+        }
+        return;
     }
-    // let text_dimensions = measure_text(&text, Some(font), 20, 1.0);
-    draw_text_ex(
-        &text,
-        screen_width() - 180.,
-        screen_height() - 30.,
-        TextParams {
-            font_size: 20,
-            font: Some(font),
-            color: RED,
-            ..Default::default()
-        },
-    );
-}
 
-/// Initialise le vecteur d'astéroïdes au début du jeu
-///
-/// # Arguments
-/// * `difficulty` - Niveau de difficulté choisi qui détermine le nombre d'astéroïdes
-///
-/// # Retourne
-/// Un vecteur contenant les astéroïdes initiaux selon la difficulté choisie
-///
-/// # Exemple
-/// ```
-/// let asteroids = create_asteroids(Difficulty::Easy); // Crée 3 astéroïdes
-/// assert_eq!(asteroids.len(), 3);
-/// ```
-fn create_asteroids(difficulty: Difficulty) -> Vec<Asteroid> {
-    let asteroid_count = match difficulty {
-        Difficulty::Easy => 3,
-        Difficulty::Medium => 6,
-        Difficulty::Hard => 10,
-    };
+    if let Some(frames) = options.loopback {
+        println!("{}", rollback::loopback(&options, frames, seed));
+        return;
+    }
 
-    (0..asteroid_count)
-        .map(|_| asteroid::Asteroid::new())
-        .collect()
-}
+    if options.gym {
+        if let Err(e) = gym::run(&options, seed) {
+            eprintln!("{}", strings.format("error", &[&e]));
+            std::process::exit(1);
+        }
+        return;
+    }
 
-/// Gère la division d'un astéroïde après une collision
-/// Crée deux astéroïdes fils après la division d'un astéroïde parent
-///
-/// # Arguments
-/// * `position` - La position de l'astéroïde à diviser
-/// * `width` - Taille de l'astéroïde à diviser
-///
-/// # Retourne
-/// Deux astéroïdes de tailles inférieurs ou erreur
-///
-/// # Exemple
-/// ```
-/// let (a1, a2) = divide(Vec2::new(0., 0.), 50.0).unwrap();
-/// assert_eq!(a1.get_width(), 25.0);
-/// assert_eq!(a2.get_width(), 25.0);
-/// ```
-fn divide(position: Vec2, width: f32) -> Result<(Asteroid, Asteroid), String> {
-    match width {
-        25.0 => Ok((
-            Asteroid::new2(vec2(position.x + 1., position.y + 1.), 15.0),
-            Asteroid::new2(vec2(position.x - 1., position.y - 1.), 15.0),
-        )),
-        50.0 => Ok((
-            Asteroid::new2(vec2(position.x + 1., position.y + 1.), 25.0),
-            Asteroid::new2(vec2(position.x - 1., position.y - 1.), 25.0),
-        )),
-        _ => Err("Taille non gérée".to_string()),
+    if options.headless {
+        match headless::run(&options, seed) {
+            Ok((summary, scene)) => {
+                println!("{summary}");
+                if let Some(scene) = scene {
+                    println!("{scene}");
+                }
+            }
+            Err(e) => {
+                eprintln!("{}", strings.format("error", &[&e]));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // La connexion est ouverte avant la fenêtre pour signaler une erreur au plus tôt
+    let conditions = net::NetConditions::from_options(&options);
+    if options.host {
+        match net::Host::bind(options.port, conditions) {
+            Ok(host) => macroquad::Window::from_config(
+                window_conf(&options, &settings),
+                net::host_game(options, seed, host),
+            ),
+            Err(e) => {
+                eprintln!("{}", strings.format("error", &[&e]));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if let Some(addr) = &options.watch {
+        match spectate::Spectator::connect(addr) {
+            Ok(spectator) => macroquad::Window::from_config(
+                window_conf(&options, &settings),
+                spectate::watch_game(options, spectator),
+            ),
+            Err(e) => {
+                eprintln!("{}", strings.format("error", &[&e]));
+                std::process::exit(1);
+            }
+        }
+        return;
     }
+    if let Some(peer) = &options.peer {
+        match rollback::open(&options, peer) {
+            Ok((link, peer)) => macroquad::Window::from_config(
+                window_conf(&options, &settings),
+                rollback::peer_game(options, seed, link, peer),
+            ),
+            Err(e) => {
+                eprintln!("{}", strings.format("error", &[&e]));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    if let Some(addr) = &options.join {
+        match net::Client::connect(addr, conditions) {
+            Ok(client) => macroquad::Window::from_config(
+                window_conf(&options, &settings),
+                net::join_game(options, client),
+            ),
+            Err(e) => {
+                eprintln!("{}", strings.format("error", &[&e]));
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Le replay est chargé avant d'ouvrir la fenêtre pour lui donner la bonne taille
+    let replay = match options.replay.as_deref().map(Replay::load).transpose() {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("{}", strings.format("error", &[&e]));
+            std::process::exit(1);
+        }
+    };
+    if let Some(replay) = &replay {
+        options.width = replay.size.x as u32;
+        options.height = replay.size.y as u32;
+    }
+
+    macroquad::Window::from_config(
+        window_conf(&options, &settings),
+        game(options, seed, replay, settings),
+    );
 }
 
-/// Point d'entrée principal du jeu
+/// Boucle principale du jeu
 ///
 /// Cette fonction contient la boucle principale du jeu et gère :
 /// * L'initialisation des ressources
 /// * La boucle du menu
 /// * La boucle de jeu
 /// * Les entrées utilisateur
-/// * La physique du jeu
 /// * Les conditions de victoire/défaite
-#[macroquad::main("BasicShapes")]
-async fn main() {
-    // Chargement de la police d'ecriture
-    let font = load_ttf_font("./font/BebasNeue-Regular.ttf").await.unwrap();
-
-    // Chargement des différents assets
-    set_pc_assets_folder("img");
-    let background_texture = load_texture("bg_3.png").await.expect("Couldn't load file");
-    let spaceship_texture = load_texture("image_vaisseau.png").await.unwrap();
-    let asteroid_texture = load_texture("image_asteroide.png").await.unwrap();
-
-    // Initialisation des différentes composantes du jeu
-    let mut asteroids: Vec<Asteroid> = Vec::new();
-    let mut spaceship = spaceship::Spaceship::new();
-    let mut bullets: Vec<Bullet> = Vec::new();
-    let mut gameover = false;
-    let mut last_shot = get_time();
-
-    // Paramètres d'affichage du vaisseau
-    let mut parametres = DrawTextureParams {
-        dest_size: Some(vec2(70.0, 60.0)), // Redimensionner l'image à 200x200 pixels
-        rotation: spaceship.get_orientation(), // Rotation en radians (ici, 45 degrés)
-        pivot: Some(spaceship.get_position()), // Afficher l'image entière (pas de découpe)
-        ..Default::default()               // Autres paramètres par défaut
-    };
-
-    let mut parametres2 = DrawTextureParams {
-        dest_size: Some(vec2(100.0, 100.0)), // Redimensionner l'image à 200x200 pixels
-        ..Default::default()                 // Autres paramètres par défaut
-    };
+///
+/// # Arguments
+/// * `options` - Options de lancement
+/// * `seed` - Graine de la première partie
+/// * `replay` - Partie à rejouer à la place des entrées clavier
+/// * `settings` - Préférences du joueur, modifiables depuis le menu
+async fn game(mut options: Options, seed: u64, replay: Option<Replay>, mut settings: Settings) {
+    // Chargement de la police d'ecriture et des différents assets
+    let assets = Assets::load(options.data_dir.as_deref());
+    // Textes de l'interface, rechargés quand la langue change
+    let mut strings = Strings::load(options.language, options.data_dir.as_deref());
+    assets.show_errors(&strings).await;
+    let font = assets.font();
+
+    // Graine de chaque nouvelle partie : fixe si elle est imposée, aléatoire sinon
+    let next_seed = || options.seed.unwrap_or_else(::rand::random);
+
+    // Initialisation de la partie
+    let mut world = World::new_sandbox(seed, screen_size());
+    let mut recording: Option<Replay> = None;
+
+    // Meilleur score des parties classiques, affiché par le HUD
+    let highscore_path = highscore::default_highscore_path();
+    let mut high_score = highscore::load(&highscore_path);
+
+    // Tremblement et voile rouge quand un vaisseau est touché
+    let mut effects = HitEffects::default();
 
     // Création des boutons 'quitter' et 'rejouer'
-    let mut btn_quit = button::Button::new(Vec2::new(20., 20.), 70.0, 20.0, BLACK, "Quitter");
-    let mut btn_replay = button::Button::new(
-        Vec2::new(screen_width() - 90., 20.),
+    // Rouges, grisés au survol
+    let style = ButtonStyle {
+        hover: GRAY,
+        ..ButtonStyle::new(RED)
+    };
+    let mut btn_quit = Button::new(
+        Vec2::new(20., 20.),
         70.0,
         20.0,
-        BLACK,
-        "Rejouer",
-    );
-
-    // Création du menu
-    let mut menu = Menu::new();
-    let mut in_menu = true;
-    let mut test_1 = false;
-
-    loop {
-        // Boucle du menu, on attend la difficulté choisie par le joueur
-        if in_menu {
-            // Quitter le jeu à n'importe quel moment
-            if is_key_down(KeyCode::Escape) || menu.quit_clicked() {
-                break;
-            }
-
-            if menu.test_clicked() {
-                test_1 = true;
-                in_menu = false;
+        RED,
+        strings.get("game.quit"),
+    )
+    .with_style(style);
+    // Le bouton 'rejouer' reste ancré au coin supérieur droit de la fenêtre
+    let replay_rect = || Rect::new(screen_width() - 90., 20., 70., 20.);
+    let mut btn_replay =
+        Button::from_rect(replay_rect(), RED, strings.get("game.replay")).with_style(style);
+
+    // Diffusion de la partie aux spectateurs (`--broadcast`)
+    let mut broadcast = match options.broadcast.map(spectate::Broadcast::bind).transpose() {
+        Ok(broadcast) => {
+            if let Some(broadcast) = &broadcast {
+                println!("{}", strings.format("game.broadcast", &[&broadcast.port()]));
             }
+            broadcast
+        }
+        Err(e) => {
+            eprintln!("{}", strings.format("error", &[&e]));
+            None
+        }
+    };
 
-            menu.draw(&font, &background_texture);
-
-            if let Some(difficulty) = menu.get_difficulty() {
-                asteroids = create_asteroids(difficulty);
-                in_menu = false;
-                test_1 = false;
+    // Manette de jeu, pour parcourir les menus (Linux uniquement)
+    let mut gamepad = Gamepad::open();
+    // Bouton ayant le focus en fin de partie : Quitter (0) ou Rejouer (1)
+    let mut end_focus: Focus<usize> = Focus::new();
+
+    // Overlay de débogage (F3)
+    let mut debug = DebugOverlay::new(options.debug);
+
+    // Pilotes des vaisseaux : le premier au clavier ou automatique
+    // (`--autopilot`), le second au clavier en coopération, avec les touches
+    // choisies dans les paramètres (flèches puis WASD par défaut)
+    let [first_keys, second_keys] = settings.controls.bindings();
+    let mut controllers: Vec<Box<dyn Controller>> = vec![
+        if options.autopilot {
+            Box::new(Autopilot::new())
+        } else {
+            Box::new(Keyboard::new(first_keys))
+        },
+        Box::new(Keyboard::new(second_keys)),
+    ];
+
+    // Vaisseau piloté par un bot (`--api`)
+    if let Some(port) = options.api {
+        match RemoteBot::bind(port, options.lockstep) {
+            Ok(bot) => {
+                println!("{}", strings.format("game.api", &[&bot.port()]));
+                controllers[options.api_player - 1] = Box::new(bot);
             }
-
-            next_frame().await;
-            continue;
+            Err(e) => eprintln!("{}", strings.format("error", &[&e])),
         }
+    }
 
-        // Boucle du test du vaisseau
-        if test_1 {
-            let frame_t = get_time();
+    // Type de partie, choisi en ligne de commande puis dans le menu
+    let mut play_mode = play_mode(&options);
+    // Manches gagnées par chaque joueur en duel
+    let mut duel: Option<Match> = None;
+
+    // Écran des paramètres, ouvert depuis le menu
+    let mut settings_menu: Option<SettingsMenu> = None;
+    let settings_path = settings::default_settings_path();
+
+    // Éditeur de difficulté personnalisée, ouvert depuis le menu avec les
+    // derniers paramètres choisis
+    let mut custom_menu: Option<CustomMenu> = None;
+    let presets_path = custom::default_presets_path();
+    let mut custom = match options.difficulty {
+        Some(Difficulty::Custom(custom)) => custom,
+        _ => CustomDifficulty::default(),
+    };
 
-            // Mise à jour des paramètres d'affichage du vaisseau
-            parametres.rotation = spaceship.get_orientation();
-            parametres.pivot = Some(spaceship.get_position());
+    // Création du menu, avec une partie de démonstration jouée en arrière-plan
+    let mut menu = Menu::new(&strings);
+    menu.set_mode(play_mode);
+    let mut in_menu = true;
+    // Erreur affichée par le menu quand une partie n'a pas pu être lancée
+    let mut menu_error: Option<String> = None;
+    let mut demo = World::new(next_seed(), Difficulty::Medium, screen_size());
+    let mut demo_pilot = Autopilot::new();
+
+    // Menu pause (touche P), absent pendant un replay
+    let mut pause: Option<PauseMenu> = None;
+    let save_path = options
+        .save_file
+        .as_ref()
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(save::default_save_path);
+
+    // Une partie est lancée directement si un replay ou une difficulté est donné
+    if let Some(replay) = &replay {
+        world = World::new(replay.seed, replay.difficulty, replay.size);
+        in_menu = false;
+    } else if let Some(difficulty) = options.difficulty {
+        world = new_game(seed, difficulty, play_mode);
+        duel = new_match(play_mode);
+        in_menu = false;
+    }
+    if !in_menu {
+        recording = start_recording(&options, seed, &world, &strings);
+    }
 
-            draw_test_1(
-                &background_texture,
-                &spaceship,
-                &spaceship_texture,
-                &parametres,
-                &bullets,
-            );
-            btn_quit.draw(&font);
-            btn_replay.draw(&font);
+    loop {
+        gamepad.update();
+        let nav = Navigation::read(&gamepad);
 
-            if is_key_down(KeyCode::Escape) {
-                break;
+        // Boucle du menu, on attend la difficulté choisie par le joueur
+        if in_menu {
+            if let Some(screen) = &mut settings_menu {
+                screen.update(&nav);
+                screen.draw(font);
+                if screen.back_clicked() {
+                    settings_menu = None;
+                } else if screen.save_clicked() {
+                    let chosen = screen.settings();
+                    match chosen.save(&settings_path) {
+                        Ok(()) => {
+                            // La taille de l'arène doit rester fixe pendant un enregistrement
+                            if options.record.is_none() {
+                                chosen.apply_window();
+                            }
+                            options.render_mode = chosen.render_mode;
+                            // Les textes de l'interface passent dans la nouvelle langue
+                            if chosen.language != options.language {
+                                options.language = chosen.language;
+                                strings =
+                                    Strings::load(options.language, options.data_dir.as_deref());
+                                btn_quit.set_text(strings.get("game.quit"));
+                                btn_replay.set_text(strings.get("game.replay"));
+                                menu = Menu::new(&strings);
+                                menu.set_mode(play_mode);
+                            }
+                            // Les pilotes au clavier prennent les nouvelles touches
+                            for (i, keys) in chosen.controls.bindings().into_iter().enumerate() {
+                                let automatic = (i == 0 && options.autopilot)
+                                    || (options.api.is_some() && i + 1 == options.api_player);
+                                if !automatic {
+                                    controllers[i] = Box::new(Keyboard::new(keys));
+                                }
+                            }
+                            settings = chosen;
+                            settings_menu = None;
+                        }
+                        Err(e) => screen.set_error(&e),
+                    }
+                }
+                next_frame().await;
+                continue;
             }
 
-            // Gestion du bouton 'quitter'
-            if btn_quit.is_clicked() {
-                break;
-            } else if btn_quit.is_hovered() {
-                btn_quit.set_color(GRAY);
-            } else {
-                btn_quit.set_color(RED);
+            if let Some(screen) = &mut custom_menu {
+                screen.update(&nav);
+                screen.draw(font, &strings);
+                if screen.back_clicked() {
+                    custom_menu = None;
+                } else if screen.save_clicked() {
+                    screen.save_preset(&presets_path, &strings);
+                } else if screen.play_clicked() {
+                    // La partie est lancée par le menu, comme pour les autres difficultés
+                    custom = screen.difficulty();
+                    menu.set_difficulty(Difficulty::Custom(custom));
+                    custom_menu = None;
+                }
+                next_frame().await;
+                continue;
             }
 
-            // Gestion du bouton 'rejouer' qui ramène vers le menu
-            if btn_replay.is_clicked() {
-                spaceship = spaceship::Spaceship::new();
-                asteroids.clear();
-                bullets.clear();
-                in_menu = true;
-                menu = Menu::new();
-                gameover = false;
-            } else if btn_replay.is_hovered() {
-                btn_replay.set_color(GRAY);
-            } else {
-                btn_replay.set_color(RED);
+            menu.update(&nav);
+            // Quitter le jeu à n'importe quel moment
+            if is_key_down(KeyCode::Escape) || menu.quit_clicked() {
+                break;
             }
 
-            // Tourner (orientation)
-            if is_key_down(KeyCode::Right) {
-                spaceship.set_orientation('R');
-            } else if is_key_down(KeyCode::Left) {
-                spaceship.set_orientation('L');
+            if menu.custom_clicked() {
+                let presets = custom::Presets::load_or_default(&presets_path, &strings);
+                custom_menu = Some(CustomMenu::new(presets, custom, &strings));
             }
 
-            // Faire en sorte que le vaisseau ne s'arrête jamais
-            // si aucune touche n'est pressée
-            if !is_key_down(KeyCode::Up)
-                && !is_key_down(KeyCode::Down)
-                && !is_key_down(KeyCode::Left)
-                && !is_key_down(KeyCode::Right)
-            {
-                spaceship.min_speed();
+            if menu.settings_clicked() {
+                settings_menu = Some(SettingsMenu::new(&settings, &strings));
             }
 
-            // Avancer
-            if is_key_down(KeyCode::Up) {
-                let acc = Vec2::new(
-                    spaceship.get_orientation().sin(),
-                    -spaceship.get_orientation().cos(),
-                ) / 30.0;
-                spaceship.set_speed(acc);
-            } else if is_key_down(KeyCode::Down) {
-                let acc = Vec2::new(
-                    -spaceship.get_orientation().sin(),
-                    spaceship.get_orientation().cos(),
-                ) / 40.0;
-                spaceship.set_speed(acc);
+            // Le mode test n'a qu'un vaisseau
+            if menu.test_clicked() {
+                menu_error = missing_bot_ship(&options, 1, &strings);
+                if menu_error.is_none() {
+                    world =
+                        World::new_sandbox(menu.seed().unwrap_or_else(next_seed), screen_size());
+                    effects = HitEffects::default();
+                    duel = None;
+                    in_menu = false;
+                }
             }
 
-            //On deplace la position du vaisseau
-            spaceship.move_object();
-            spaceship.max_speed(); //force a ne pas depasser le vmax
-
-            // Fait réapparaitre le vaisseau à l'opposé de l'ecran lorsque l'on touche une limite d'ecran
-            spaceship.bound_pos();
-
-            // Lancer un missile si la touche espace est pressée et que le temps entre chaque tir est supérieur à 0.5s
-            if is_key_down(KeyCode::Space) && frame_t - last_shot > 0.5 {
-                // Calcul de la direction du missile
-                let rot_vec = Vec2::new(
-                    spaceship.get_orientation().sin(),
-                    -spaceship.get_orientation().cos(),
+            // Démonstration : une nouvelle partie commence dès que la précédente est finie
+            if options.demo {
+                if demo.is_over() {
+                    demo = World::new(next_seed(), Difficulty::Medium, screen_size());
+                }
+                demo.set_size(screen_size());
+                let input = demo_pilot.input(&demo, 0);
+                demo.step(&input);
+                render::draw_world(
+                    &mut MacroquadRenderer::new(&assets, options.render_mode),
+                    &demo,
+                    &strings,
                 );
-                bullets.push(bullet::Bullet::new(
-                    spaceship.get_position() + rot_vec * spaceship.get_height() / 2.,
-                    rot_vec * 4.,
-                ));
-                last_shot = frame_t;
+                menu.draw_over(font);
+            } else {
+                menu.draw(font, assets.background.as_ref());
             }
-
-            // Bouger un missile
-            for bullet in bullets.iter_mut() {
-                bullet.move_object();
+            if let Some(error) = &menu_error {
+                render::draw_centered_text(error, screen_height() - 20., 20, RED, font);
             }
 
-            // Si le missile dépasse les bords de l'écran il disparait
-            bullets.retain(|bullet| !bullet.is_out());
+            // Le vaisseau piloté par le bot doit exister dans la partie choisie
+            if menu.get_difficulty().is_some() {
+                menu_error = missing_bot_ship(&options, menu.mode().ships(), &strings);
+                if menu_error.is_some() {
+                    menu.clear_difficulty();
+                }
+            }
+            if let Some(difficulty) = menu.get_difficulty() {
+                play_mode = menu.mode();
+                let seed = menu.seed().unwrap_or_else(next_seed);
+                world = new_game(seed, difficulty, play_mode);
+                effects = HitEffects::default();
+                duel = new_match(play_mode);
+                recording = start_recording(&options, seed, &world, &strings);
+                in_menu = false;
+            }
 
             next_frame().await;
             continue;
         }
 
-        // Boucle pour le jeu
-        if !in_menu {
-            let frame_t = get_time();
-
-            // Fait diminuer le temps d'invicibilité à chaque frame
-            if spaceship.get_invicibility() > 0.0 {
-                spaceship.set_invicibility(get_frame_time());
-            }
-
-            // Mise à jour des paramètres d'affichage du vaisseau
-            parametres.rotation = spaceship.get_orientation();
-            parametres.pivot = Some(spaceship.get_position());
-
-            // Mise à jour des paramètres d'affichage des asteroides
-            parametres2.dest_size = Some(vec2(100.0, 100.0));
-
-            // Pour afficher les différents objets du jeu
-            draw(
-                &background_texture,
-                &spaceship,
-                &spaceship_texture,
-                &parametres,
-                &asteroids,
-                &asteroid_texture,
-                &parametres2,
-                &bullets,
-                &spaceship.get_shield(),
-                &font,
-            );
-
-            btn_quit.draw(&font);
-            btn_replay.draw(&font);
+        // En dehors d'un replay, l'arène suit la taille de la fenêtre
+        if replay.is_none() {
+            world.set_size(screen_size());
+        }
 
-            // Quitter le jeu à n'importe quel moment
+        // Pour afficher les différents objets du jeu, puis le HUD (absent en mode test)
+        // L'arène tremble après un choc, le HUD reste en place. Les effets
+        // s'estompent à chaque image, même quand la partie est figée
+        effects.update(get_frame_time());
+        let mut hud = Hud::new(&strings);
+        if has_high_score(&world) {
+            hud = hud.with_high_score(high_score);
+        }
+        effects.draw(
+            &mut MacroquadRenderer::new(&assets, options.render_mode),
+            &world,
+            &hud,
+        );
+
+        debug.update();
+        debug.draw(&world, font, &strings);
+
+        // La partie est figée tant que le menu pause est ouvert
+        if let Some(pause_menu) = &mut pause {
+            pause_menu.update(&nav);
+            pause_menu.draw(font);
             if is_key_down(KeyCode::Escape) {
                 break;
             }
-
-            // Gestion du bouton 'quitter'
-            if btn_quit.is_clicked() {
-                break;
-            } else if btn_quit.is_hovered() {
-                btn_quit.set_color(GRAY);
-            } else {
-                btn_quit.set_color(RED);
-            }
-
-            // Gestion du bouton 'rejouer'
-            if btn_replay.is_clicked() {
-                spaceship = spaceship::Spaceship::new();
-                asteroids.clear();
-                bullets.clear();
+            if pause_menu.resume_clicked() {
+                pause = None;
+            } else if pause_menu.save_clicked() {
+                let result = save::save(&world, &save_path)
+                    .map(|()| strings.format("pause.saved", &[&save_path.display()]));
+                if result.is_ok() {
+                    pause_menu.set_load_enabled(true);
+                }
+                pause_menu.set_result(result);
+            } else if pause_menu.load_clicked() {
+                // Le vaisseau du bot doit aussi exister dans la partie chargée
+                let loaded = save::load(&save_path).and_then(|loaded| {
+                    match missing_bot_ship(&options, loaded.players.len(), &strings) {
+                        Some(error) => Err(error),
+                        None => Ok(loaded),
+                    }
+                });
+                match loaded {
+                    Ok(loaded) => {
+                        // L'enregistrement en cours ne correspond plus à la partie chargée
+                        save_recording(&mut recording, &options, &strings);
+                        world = loaded;
+                        effects = HitEffects::default();
+                        pause = None;
+                    }
+                    Err(e) => pause_menu.set_result(Err(e)),
+                }
+            } else if pause_menu.quit_clicked() {
+                save_recording(&mut recording, &options, &strings);
+                pause = None;
                 in_menu = true;
-                menu = Menu::new();
-                gameover = false;
-            } else if btn_replay.is_hovered() {
-                btn_replay.set_color(GRAY);
-            } else {
-                btn_replay.set_color(RED);
-            }
-
-            // On fait bouger tous les asteroides encore présent et remets leur collision à faux
-            for asteroid in &mut asteroids {
-                asteroid.set_collision(false);
-                asteroid.move_object();
-                asteroid.bound_pos();
-            }
-
-            // Tourner (orientation)
-            if is_key_down(KeyCode::Right) {
-                spaceship.set_orientation('R');
-            } else if is_key_down(KeyCode::Left) {
-                spaceship.set_orientation('L');
+                menu = Menu::new(&strings);
+                menu.set_mode(play_mode);
             }
+            next_frame().await;
+            continue;
+        }
+        if replay.is_none() && !world.is_over() && is_key_pressed(PAUSE_KEY) {
+            pause = Some(PauseMenu::new(save_path.exists(), &strings));
+        }
 
-            // Faire en sorte que le vaisseau ne s'arrête jamais
-            // si aucune touche n'est pressée
-            if !is_key_down(KeyCode::Up)
-                && !is_key_down(KeyCode::Down)
-                && !is_key_down(KeyCode::Left)
-                && !is_key_down(KeyCode::Right)
-            {
-                spaceship.min_speed();
+        // Enregistre l'état de la partie dans une image PNG (rapport de bug)
+        if is_key_pressed(SNAPSHOT_KEY) {
+            let path = format!("snapshot-{}.png", world.frame);
+            match headless::snapshot(&world, &path, &strings) {
+                Ok(()) => println!("{}", strings.format("game.snapshot", &[&path])),
+                Err(e) => eprintln!("{}", strings.format("error", &[&e])),
             }
+        }
 
-            // Avancer
-            if is_key_down(KeyCode::Up) {
-                let acc = Vec2::new(
-                    spaceship.get_orientation().sin(),
-                    -spaceship.get_orientation().cos(),
-                ) / 30.0;
-                spaceship.set_speed(acc);
-            } else if is_key_down(KeyCode::Down) {
-                let acc = Vec2::new(
-                    -spaceship.get_orientation().sin(),
-                    spaceship.get_orientation().cos(),
-                ) / 40.0;
-                spaceship.set_speed(acc);
-            }
+        // En fin de partie, les flèches gauche et droite choisissent un bouton
+        if world.is_over() {
+            end_focus.navigate(&[0, 1], nav.step(NavAction::Left, NavAction::Right));
+        } else {
+            end_focus = Focus::new();
+        }
+        btn_quit.set_focused(end_focus.is(0));
+        btn_replay.set_focused(end_focus.is(1));
+        btn_replay.set_rect(replay_rect());
+        btn_quit.draw(font);
+        btn_replay.draw(font);
+
+        // Quitter le jeu à n'importe quel moment
+        if is_key_down(KeyCode::Escape) {
+            break;
+        }
 
-            //On deplace la position du vaisseau
-            spaceship.move_object();
-            spaceship.max_speed(); //force a ne pas depasser le vmax
+        // Gestion du bouton 'quitter'
+        if btn_quit.is_activated(&nav) {
+            break;
+        }
 
-            // Fait réapparaitre le vaisseau à l'opposé de l'ecran lorsque l'on touche une limite d'ecran
-            spaceship.bound_pos();
+        // Gestion du bouton 'rejouer' qui ramène vers le menu
+        if btn_replay.is_activated(&nav) {
+            save_recording(&mut recording, &options, &strings);
+            in_menu = true;
+            menu = Menu::new(&strings);
+            menu.set_mode(play_mode);
+        }
 
-            // Lancer un missile si la touche espace est pressée et que le temps entre chaque tir est supérieur à 0.5s
-            if is_key_down(KeyCode::Space) && frame_t - last_shot > 0.5 {
-                // Calcul de la direction du missile
-                let rot_vec = Vec2::new(
-                    spaceship.get_orientation().sin(),
-                    -spaceship.get_orientation().cos(),
-                );
-                bullets.push(bullet::Bullet::new(
-                    spaceship.get_position() + rot_vec * spaceship.get_height() / 2.,
-                    rot_vec * 4.,
-                ));
-                last_shot = frame_t;
-            }
+        // Un bot en lockstep fige la partie tant qu'il n'a pas répondu
+        let ready = replay.is_some()
+            || world.is_over()
+            || controllers
+                .iter_mut()
+                .take(world.players.len())
+                .enumerate()
+                .all(|(i, controller)| controller.is_ready(&world, i));
+        if !ready {
+            let text = strings.get("game.waiting_bot");
+            render::draw_centered_text(text, 80., 20, GRAY, font);
+            next_frame().await;
+            continue;
+        }
 
-            // Bouger un missile
-            for bullet in bullets.iter_mut() {
-                bullet.move_object();
+        // Les commandes viennent du replay s'il y en a un, sinon des pilotes
+        let inputs: Vec<Input> = match &replay {
+            Some(replay) => vec![replay.input(world.frame)],
+            None => controllers
+                .iter_mut()
+                .take(world.players.len())
+                .enumerate()
+                .map(|(i, controller)| controller.input(&world, i))
+                .collect(),
+        };
+        if !world.is_over() {
+            if let Some(recording) = &mut recording {
+                recording.record(inputs[0]);
             }
-
-            // Si le missile dépasse les bords de l'écran il disparait
-            bullets.retain(|bullet| !bullet.is_out());
-
-            // Collision astéroide / astéroide
-            for i in 0..asteroids.len() {
-                for j in (i + 1)..asteroids.len() {
-                    let (asteroid1, asteroid2) = {
-                        let (left, right) = asteroids.split_at_mut(j);
-                        (&mut left[i], &mut right[0])
-                    };
-                    asteroid1.collided(asteroid2);
-                }
+        }
+        let was_running = !world.is_over();
+        world.step_players(&inputs);
+        // Un bouclier qui baisse déclenche les effets du choc
+        effects.observe(&world);
+        if let Some(broadcast) = &mut broadcast {
+            broadcast.publish(&world);
+            // En bas de l'écran, les coins du haut étant occupés par le HUD
+            let text = strings.format("game.viewers", &[&broadcast.viewers()]);
+            render::draw_centered_text(&text, screen_height() - 20., 20, GRAY, font);
+        }
+        // Un replay rejoue une partie déjà comptée
+        let counts_for_high_score = has_high_score(&world) && replay.is_none();
+        if was_running && world.is_over() && counts_for_high_score {
+            match highscore::record(&highscore_path, world.score()) {
+                Ok(best) => high_score = best,
+                Err(e) => eprintln!("{}", strings.format("error", &[&e])),
             }
-
-            // Collision entre un astéroide et le vaisseau
-            for asteroid in asteroids.iter_mut() {
-                spaceship.collided(asteroid);
+        }
+        if let Some(duel) = &mut duel {
+            if was_running && world.is_over() {
+                duel.record(world.winner());
             }
+            // Score du duel en haut de l'écran
+            render::draw_centered_text(&duel.score_text(&strings), 40., 30, WHITE, font);
+        }
 
-            // Check la vie restante du vaisseau
-            if spaceship.get_shield() == 0 {
-                gameover = true;
-            }
+        // Regarde si la partie est gagné ou perdu
+        if world.is_over() {
+            save_recording(&mut recording, &options, &strings);
+
+            let (text, action, color) = game_over_message(duel.as_ref(), &world, &strings);
+            render::draw_centered_text(
+                &strings.format("game.press_enter", &[&text, &action]),
+                screen_height() / 2.,
+                40,
+                color,
+                font,
+            );
 
-            // Collision missile avec un astéroide
-            let mut new_asteroids: Vec<Asteroid> = Vec::new();
-            for asteroid in asteroids.iter_mut() {
-                for bullet in bullets.iter_mut() {
-                    if bullet.collided(asteroid) && asteroid.get_width() > 15.0 {
-                        // On unwrap car l'erreur ne peut pas se produire
-                        let (a1, a2) =
-                            divide(asteroid.get_position(), asteroid.get_width()).unwrap();
-                        new_asteroids.push(a1);
-                        new_asteroids.push(a2);
-                    }
+            // Rejouer avec la même difficulté (manche suivante ou nouveau duel),
+            // sauf si Entrée active l'un des deux boutons
+            if (is_key_down(KeyCode::Enter) || nav.pressed(NavAction::Activate))
+                && end_focus.current().is_none()
+            {
+                if duel.as_ref().is_some_and(Match::is_over) {
+                    duel = new_match(play_mode);
                 }
+                let seed = next_seed();
+                world = new_game(seed, world.difficulty, play_mode);
+                effects = HitEffects::default();
+                recording = start_recording(&options, seed, &world, &strings);
             }
+        }
+
+        next_frame().await
+    }
 
-            asteroids.append(&mut new_asteroids);
+    save_recording(&mut recording, &options, &strings);
+}
 
-            // Supprime les astéroides et les projectiles qui ont été touchés
-            asteroids.retain(|asteroid| !asteroid.get_touched());
-            bullets.retain(|bullet| !bullet.get_touched());
+/// Dimensions actuelles de la fenêtre
+fn screen_size() -> Vec2 {
+    vec2(screen_width(), screen_height())
+}
 
-            // Regarde si la partie est gagné ou perdu
-            if gameover || asteroids.is_empty() {
-                asteroids.clear();
-                bullets.clear(); // Reset
+/// Message d'erreur si le vaisseau piloté par le bot (`--api-player`)
+/// n'existe pas dans une partie à `ships` vaisseaux
+fn missing_bot_ship(options: &Options, ships: usize, strings: &Strings) -> Option<String> {
+    (options.api.is_some() && options.api_player > ships)
+        .then(|| strings.format("menu.bot_ship_missing", &[&options.api_player]))
+}
 
-                let mut text = "Gagné ! Presser 'Entrée' pour rejouer";
-                let mut font_color = GREEN;
-                if gameover {
-                    text = "Perdu ! Presser 'Entrée' pour rejouer";
-                    font_color = RED;
-                }
-                let text_dimensions = measure_text(text, Some(&font), 40, 1.0);
-                draw_text_ex(
-                    text,
-                    screen_width() / 2. - text_dimensions.width / 2.0,
-                    screen_height() / 2.,
-                    TextParams {
-                        font_size: 40,
-                        font: Some(&font),
-                        color: font_color,
-                        ..Default::default()
-                    },
-                );
+/// Type de partie demandé en ligne de commande
+fn play_mode(options: &Options) -> PlayMode {
+    match options.versus {
+        Some(best_of) => PlayMode::Versus { best_of },
+        None if options.players > 1 => PlayMode::Coop {
+            friendly_fire: options.friendly_fire,
+        },
+        None => PlayMode::Solo,
+    }
+}
 
-                // Rejouer
-                if is_key_down(KeyCode::Enter) {
-                    asteroids = create_asteroids(Difficulty::Easy);
-                    spaceship = spaceship::Spaceship::new();
-                    gameover = false;
-                    continue;
-                }
-            }
+/// Vérifie si une partie a un meilleur score : seules les parties classiques
+/// dans une difficulté prédéfinie en ont, les scores d'une difficulté
+/// personnalisée ne se comparant pas aux autres
+fn has_high_score(world: &World) -> bool {
+    world.mode == GameMode::Classic
+        && !world.sandbox
+        && !matches!(world.difficulty, Difficulty::Custom(_))
+}
 
-            next_frame().await
+/// Crée une nouvelle partie à la taille de la fenêtre
+///
+/// # Arguments
+/// * `seed` - Graine de la partie
+/// * `difficulty` - Difficulté choisie
+/// * `mode` - Type de partie (un joueur, coopération ou manche de duel)
+fn new_game(seed: u64, difficulty: Difficulty, mode: PlayMode) -> World {
+    match mode {
+        PlayMode::Solo => World::new(seed, difficulty, screen_size()),
+        PlayMode::Coop { friendly_fire } => {
+            let mut world = World::with_players(seed, difficulty, screen_size(), 2);
+            world.friendly_fire = friendly_fire;
+            world
         }
+        PlayMode::Versus { .. } => World::new_versus(seed, difficulty, screen_size()),
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::*;
-
-    // j#[test]
-    // // peut pas foncionner car on a besoin de la taillle de l'ecran, il faut etre dans un boucle avec une window
-    // fn creation_asteroide() {
-    //     let asteroids = create_asteroids(Difficulty::Easy);
-    //     assert_eq!(asteroids.len(), 3);
-    // }
-
-    #[test]
-    fn division_asteroide() {
-        let (a1, a2) = divide(vec2(0., 0.), 50.0).unwrap();
-        assert_eq!(a1.get_width(), 25.0);
-        assert_eq!(a2.get_width(), 25.0);
+/// Commence un nouveau duel si le type de partie en est un
+fn new_match(mode: PlayMode) -> Option<Match> {
+    match mode {
+        PlayMode::Versus { best_of } => Some(Match::new(best_of)),
+        _ => None,
+    }
+}
+
+/// Message affiché à la fin d'une partie ou d'une manche
+///
+/// # Retourne
+/// Le message, l'action proposée par la touche Entrée et la couleur du message
+fn game_over_message<'a>(
+    duel: Option<&Match>,
+    world: &World,
+    strings: &'a Strings,
+) -> (String, &'a str, Color) {
+    let (text, action, color) = match duel {
+        Some(duel) => match (duel.winner(), world.winner()) {
+            (Some(winner), _) => (
+                strings.format("game.duel_won", &[&(winner + 1)]),
+                "game.action.replay",
+                GREEN,
+            ),
+            (None, Some(winner)) => (
+                strings.format("game.round_won", &[&(winner + 1)]),
+                "game.action.continue",
+                YELLOW,
+            ),
+            (None, None) => (
+                strings.get("game.draw").to_string(),
+                "game.action.replay_round",
+                YELLOW,
+            ),
+        },
+        None if world.state == GameState::Lost => (
+            strings.get("game.lost").to_string(),
+            "game.action.replay",
+            RED,
+        ),
+        None => (
+            strings.get("game.won").to_string(),
+            "game.action.replay",
+            GREEN,
+        ),
+    };
+    (text, strings.get(action), color)
+}
+
+/// Commence l'enregistrement d'une partie si `--record` est donné
+///
+/// Les replays ne contiennent les commandes que d'un joueur : les parties en
+/// coopération ne sont pas enregistrées.
+fn start_recording(
+    options: &Options,
+    seed: u64,
+    world: &World,
+    strings: &Strings,
+) -> Option<Replay> {
+    options.record.as_ref()?;
+    if world.players.len() > 1 {
+        eprintln!("{}", strings.get("game.coop_not_recorded"));
+        return None;
+    }
+    Some(Replay::new(seed, world.difficulty, world.size))
+}
+
+/// Enregistre la partie en cours dans le fichier donné par `--record`
+///
+/// L'enregistrement est vidé une fois écrit pour ne pas être sauvegardé deux fois.
+fn save_recording(recording: &mut Option<Replay>, options: &Options, strings: &Strings) {
+    if let (Some(replay), Some(path)) = (recording.take(), &options.record) {
+        if let Err(e) = replay.save(path) {
+            eprintln!("{}", strings.format("error", &[&e]));
+        }
     }
 }
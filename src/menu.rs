@@ -1,124 +1,317 @@
 //! Module représentant le menu principal du jeu
 //!
 //! Ce module contient la structure `Menu` qui gère l'affichage et les interactions du menu principal.
+//! Le menu se parcourt à la souris, au clavier ou à la manette.
 
 use crate::button::Button;
+use crate::locale::Strings;
+use crate::render::draw_cover;
+use crate::ui::{
+    Column, Dropdown, Focus, Label, NavAction, Navigation, Slider, TextInput, Toggle, Viewport,
+};
+use crate::versus::BEST_OF_CHOICES;
+use crate::world::MAX_PLAYERS;
 use crate::Difficulty;
 use macroquad::prelude::*;
 
+/// Type de partie choisi dans le menu
+///
+/// # Variantes
+/// * `Solo` - Un joueur
+/// * `Coop` - Deux joueurs contre les astéroïdes, avec ou sans tir allié
+/// * `Versus` - Duel entre deux joueurs, au meilleur de `best_of` manches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    Solo,
+    Coop { friendly_fire: bool },
+    Versus { best_of: u32 },
+}
+
+impl PlayMode {
+    /// Nombre de vaisseaux d'une partie de ce type
+    pub fn ships(self) -> usize {
+        match self {
+            PlayMode::Solo => 1,
+            PlayMode::Coop { .. } | PlayMode::Versus { .. } => MAX_PLAYERS,
+        }
+    }
+}
+
+/// Clés des types de partie proposés par la liste déroulante du menu
+const MODE_CHOICES: [&str; 3] = ["menu.mode.solo", "menu.mode.coop", "menu.mode.versus"];
+
+/// Hauteur réservée au titre au-dessus des widgets
+const TITLE_HEIGHT: f32 = 110.0;
+
+/// Nombre de lignes de widgets
+const ROWS: usize = 9;
+
+/// Éléments du menu pouvant recevoir le focus, de haut en bas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Easy,
+    Medium,
+    Hard,
+    Custom,
+    Mode,
+    Option,
+    Seed,
+    Test,
+    Settings,
+    Quit,
+}
+
 /// Représente le menu principal du jeu
 ///
 /// # Champs
+/// * `title` - Titre du jeu
 /// * `btn_easy` - Bouton pour le mode facile
 /// * `btn_medium` - Bouton pour le mode moyen
 /// * `btn_hard` - Bouton pour le mode difficile
+/// * `btn_custom` - Bouton pour ouvrir l'éditeur de difficulté personnalisée
+/// * `mode_list` - Liste déroulante du type de partie
+/// * `friendly_fire` - Interrupteur du tir allié en coopération
+/// * `best_of` - Curseur du nombre de manches en duel
+/// * `seed` - Champ de la graine de la partie, aléatoire s'il est vide
+/// * `btn_test` - Bouton pour tester le vaisseau
+/// * `btn_settings` - Bouton pour ouvrir l'écran des paramètres
 /// * `btn_quit` - Bouton pour quitter le jeu
 /// * `difficulty` - Difficulté sélectionnée par le joueur
+/// * `focus` - Élément ayant le focus du clavier et de la manette
+/// * `nav` - Actions de navigation de la frame en cours
+/// * `viewport` - Taille de la fenêtre pour laquelle les widgets sont placés
 pub struct Menu {
+    title: Label,
     btn_easy: Button,
     btn_medium: Button,
     btn_hard: Button,
+    btn_custom: Button,
+    mode_list: Dropdown,
+    friendly_fire: Toggle,
+    best_of: Slider,
+    seed: TextInput,
     btn_test: Button,
+    btn_settings: Button,
     btn_quit: Button,
     difficulty: Option<Difficulty>,
+    focus: Focus<Item>,
+    nav: Navigation,
+    viewport: Viewport,
 }
 
 impl Menu {
     /// Crée une nouvelle instance du menu principal
     ///
+    /// # Arguments
+    /// * `strings` - Textes de l'interface dans la langue du joueur
+    ///
     /// # Retourne
-    /// Une nouvelle instance de Menu avec les boutons positionnés
-    pub fn new() -> Self {
-        let screen_center_x = screen_width() / 2.0;
-        let button_width = 200.0;
-        let button_height = 40.0;
-        let spacing = 60.0;
-
-        Self {
-            btn_easy: Button::new(
-                Vec2::new(screen_center_x - button_width / 2.0, 200.0),
-                button_width,
-                button_height,
-                GREEN,
-                "Facile",
-            ),
-            btn_medium: Button::new(
-                Vec2::new(screen_center_x - button_width / 2.0, 200.0 + spacing),
-                button_width,
-                button_height,
-                YELLOW,
-                "Moyen",
-            ),
-            btn_hard: Button::new(
-                Vec2::new(screen_center_x - button_width / 2.0, 200.0 + spacing * 2.0),
-                button_width,
-                button_height,
-                RED,
-                "Difficile",
-            ),
-            btn_test: Button::new(
-                Vec2::new(screen_center_x - button_width / 2.0, 200.0 + spacing * 3.0),
-                button_width,
-                button_height,
-                BLUE,
-                "Test vaisseau",
-            ),
-            btn_quit: Button::new(
-                Vec2::new(screen_center_x - button_width / 2.0, 200.0 + spacing * 4.0),
-                button_width,
-                button_height,
-                GRAY,
-                "Quitter",
+    /// Une nouvelle instance de Menu avec les widgets disposés en colonne
+    pub fn new(strings: &Strings) -> Self {
+        let (first, last) = (
+            BEST_OF_CHOICES[0],
+            BEST_OF_CHOICES[BEST_OF_CHOICES.len() - 1],
+        );
+        let rect = Rect::default();
+        let text = |key| strings.get(key);
+        let modes = MODE_CHOICES.map(text);
+
+        let mut menu = Self {
+            title: Label::new(rect, text("menu.title")).with_size(60),
+            btn_easy: Button::from_rect(rect, GREEN, text("menu.easy")),
+            btn_medium: Button::from_rect(rect, YELLOW, text("menu.medium")),
+            btn_hard: Button::from_rect(rect, RED, text("menu.hard")),
+            btn_custom: Button::from_rect(rect, ORANGE, text("menu.custom")),
+            mode_list: Dropdown::new(rect, &modes, 0),
+            friendly_fire: Toggle::new(rect, text("menu.friendly_fire"), false),
+            best_of: Slider::new(
+                rect,
+                text("menu.best_of"),
+                first as f32,
+                last as f32,
+                (BEST_OF_CHOICES[1] - first) as f32,
+                BEST_OF_CHOICES[1] as f32,
             ),
+            seed: TextInput::new(rect, text("menu.seed"), 20).with_filter(|c| c.is_ascii_digit()),
+            btn_test: Button::from_rect(rect, BLUE, text("menu.test")),
+            btn_settings: Button::from_rect(rect, PURPLE, text("menu.settings")),
+            btn_quit: Button::from_rect(rect, GRAY, text("menu.quit")),
             difficulty: None,
+            focus: Focus::new(),
+            nav: Navigation::default(),
+            viewport: Viewport::default(),
+        };
+        menu.viewport.resized();
+        menu.layout();
+        menu
+    }
+
+    /// Place les widgets en colonne au centre de la fenêtre
+    fn layout(&mut self) {
+        let screen = self.viewport.rect();
+        let column = Column::new(screen.w / 2.0, 0.0, 200.0, 40.0, 15.0);
+        // Le titre et la colonne sont centrés ensemble verticalement
+        let height = TITLE_HEIGHT + column.height(ROWS);
+        let column = Column {
+            top: ((screen.h - height) / 2.0).max(0.0) + TITLE_HEIGHT,
+            ..column
+        };
+        // La ligne du type de partie est plus large : liste et option côte à côte
+        let wide = Column {
+            width: 420.0,
+            ..column
+        };
+
+        self.title
+            .set_rect(Rect::new(0., column.top - TITLE_HEIGHT, screen.w, 50.));
+        self.btn_easy.set_rect(column.row(0));
+        self.btn_medium.set_rect(column.row(1));
+        self.btn_hard.set_rect(column.row(2));
+        self.btn_custom.set_rect(column.row(3));
+        self.mode_list.set_rect(wide.cell(4, 0, 2));
+        self.friendly_fire.set_rect(wide.cell(4, 1, 2));
+        self.best_of.set_rect(wide.cell(4, 1, 2));
+        self.seed.set_rect(column.row(5));
+        self.btn_test.set_rect(column.row(6));
+        self.btn_settings.set_rect(column.row(7));
+        self.btn_quit.set_rect(column.row(8));
+    }
+
+    /// Choisit le type de partie, affiché par les widgets
+    pub fn set_mode(&mut self, mode: PlayMode) {
+        match mode {
+            PlayMode::Solo => self.mode_list.set_selected(0),
+            PlayMode::Coop { friendly_fire } => {
+                self.mode_list.set_selected(1);
+                self.friendly_fire.set_on(friendly_fire);
+            }
+            PlayMode::Versus { best_of } => {
+                self.mode_list.set_selected(2);
+                self.best_of.set_value(best_of as f32);
+            }
         }
     }
 
+    /// Replace les widgets si la fenêtre a changé de taille et déplace le focus
+    /// avec les flèches haut et bas, à appeler à chaque frame avant `draw` et
+    /// les autres méthodes
+    ///
+    /// # Arguments
+    /// * `nav` - Actions de navigation de la frame
+    pub fn update(&mut self, nav: &Navigation) {
+        if self.viewport.resized() {
+            self.layout();
+        }
+        self.nav = nav.clone();
+        let step = nav.step(NavAction::Up, NavAction::Down);
+        if step == 0 || self.mode_list.is_open() {
+            return;
+        }
+        // L'option du type de partie n'existe pas en solo
+        let items: Vec<Item> = [
+            Item::Easy,
+            Item::Medium,
+            Item::Hard,
+            Item::Custom,
+            Item::Mode,
+            Item::Option,
+            Item::Seed,
+            Item::Test,
+            Item::Settings,
+            Item::Quit,
+        ]
+        .into_iter()
+        .filter(|&item| item != Item::Option || self.mode() != PlayMode::Solo)
+        .collect();
+        self.focus.navigate(&items, step);
+
+        let focus = self.focus;
+        self.btn_easy.set_focused(focus.is(Item::Easy));
+        self.btn_medium.set_focused(focus.is(Item::Medium));
+        self.btn_hard.set_focused(focus.is(Item::Hard));
+        self.btn_custom.set_focused(focus.is(Item::Custom));
+        self.mode_list.set_focused(focus.is(Item::Mode));
+        self.friendly_fire.set_focused(focus.is(Item::Option));
+        self.best_of.set_focused(focus.is(Item::Option));
+        self.seed.set_focused(focus.is(Item::Seed));
+        self.btn_test.set_focused(focus.is(Item::Test));
+        self.btn_settings.set_focused(focus.is(Item::Settings));
+        self.btn_quit.set_focused(focus.is(Item::Quit));
+    }
+
     /// Dessine le menu et gère les interactions
     ///
     /// # Arguments
-    /// * `font` - Police de caractères à utiliser pour le texte
-    /// * `background` - Texture d'arrière-plan à afficher
-    pub fn draw(&mut self, font: &Font, background: &Texture2D) {
+    /// * `font` - Police de caractères à utiliser pour le texte (`None` pour la police par défaut)
+    /// * `background` - Texture d'arrière-plan à afficher (`None` pour un fond noir)
+    pub fn draw(&mut self, font: Option<&Font>, background: Option<&Texture2D>) {
         // Dessiner le fond
-        draw_texture(background, 0., 0., WHITE);
-
-        // Dessiner le titre
-        let title = "ASTEROIDS";
-
-        let title_size = measure_text(title, Some(font), 60, 1.0);
-        draw_text_ex(
-            title,
-            screen_width() / 2.0 - title_size.width / 2.0,
-            100.0,
-            TextParams {
-                font_size: 60,
-                font: Some(font),
-                color: WHITE,
-                ..Default::default()
-            },
+        match background {
+            Some(background) => draw_cover(background),
+            None => clear_background(BLACK),
+        }
+        self.draw_items(font);
+    }
+
+    /// Dessine le menu par-dessus une partie de démonstration, assombrie
+    ///
+    /// # Arguments
+    /// * `font` - Police de caractères à utiliser pour le texte (`None` pour la police par défaut)
+    pub fn draw_over(&mut self, font: Option<&Font>) {
+        draw_rectangle(
+            0.,
+            0.,
+            screen_width(),
+            screen_height(),
+            Color::new(0., 0., 0., 0.5),
         );
+        self.draw_items(font);
+    }
+
+    /// Dessine le titre et les widgets puis gère les interactions
+    fn draw_items(&mut self, font: Option<&Font>) {
+        self.title.draw(font);
 
-        // Dessiner les boutons
+        // Dessiner les widgets, la liste déroulante en dernier pour recouvrir les autres
         self.btn_easy.draw(font);
         self.btn_medium.draw(font);
         self.btn_hard.draw(font);
+        self.btn_custom.draw(font);
+        // Les options n'ont de sens qu'à plusieurs
+        match self.mode() {
+            PlayMode::Solo => {}
+            PlayMode::Coop { .. } => self.friendly_fire.draw(font),
+            PlayMode::Versus { .. } => self.best_of.draw(font),
+        }
+        self.seed.draw(font);
         self.btn_test.draw(font);
+        self.btn_settings.draw(font);
         self.btn_quit.draw(font);
+        self.mode_list.draw(font);
 
-        // Gérer les survols
-        if self.btn_quit.is_hovered() {
-            self.btn_quit.set_color(DARKGRAY);
-        } else {
-            self.btn_quit.set_color(GRAY);
+        // Un clic dans la liste ouverte ne doit pas atteindre les widgets du dessous
+        let list_open = self.mode_list.is_open();
+        self.mode_list.update(&self.nav);
+        if list_open || self.mode_list.is_open() {
+            return;
         }
 
-        // Gérer les clics
-        if self.btn_easy.is_clicked() {
+        // Gérer les clics et les activations au clavier
+        match self.mode() {
+            PlayMode::Solo => {}
+            PlayMode::Coop { .. } => {
+                self.friendly_fire.update(&self.nav);
+            }
+            PlayMode::Versus { .. } => {
+                self.best_of.update(&self.nav);
+            }
+        }
+        self.seed.update();
+        if self.btn_easy.is_activated(&self.nav) {
             self.difficulty = Some(Difficulty::Easy);
-        } else if self.btn_medium.is_clicked() {
+        } else if self.btn_medium.is_activated(&self.nav) {
             self.difficulty = Some(Difficulty::Medium);
-        } else if self.btn_hard.is_clicked() {
+        } else if self.btn_hard.is_activated(&self.nav) {
             self.difficulty = Some(Difficulty::Hard);
         }
     }
@@ -132,15 +325,54 @@ impl Menu {
         self.difficulty
     }
 
-    /// Vérifie si le bouton quitter a été cliqué
+    /// Choisit la difficulté de la partie à lancer, réglée dans l'éditeur
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = Some(difficulty);
+    }
+
+    /// Annule la difficulté sélectionnée, la partie n'ayant pas pu être lancée
+    pub fn clear_difficulty(&mut self) {
+        self.difficulty = None;
+    }
+
+    /// Type de partie choisi
+    pub fn mode(&self) -> PlayMode {
+        match self.mode_list.selected() {
+            1 => PlayMode::Coop {
+                friendly_fire: self.friendly_fire.is_on(),
+            },
+            2 => PlayMode::Versus {
+                best_of: self.best_of.value() as u32,
+            },
+            _ => PlayMode::Solo,
+        }
+    }
+
+    /// Graine saisie, `None` pour une graine aléatoire
+    pub fn seed(&self) -> Option<u64> {
+        self.seed.text().parse().ok()
+    }
+
+    /// Vérifie si le bouton quitter a été cliqué ou activé
     ///
     /// # Retourne
     /// `true` si le bouton quitter a été cliqué, `false` sinon
     pub fn quit_clicked(&self) -> bool {
-        self.btn_quit.is_clicked()
+        !self.mode_list.is_open() && self.btn_quit.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton de la difficulté personnalisée a été cliqué ou activé
+    pub fn custom_clicked(&self) -> bool {
+        !self.mode_list.is_open() && self.btn_custom.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton des paramètres a été cliqué ou activé
+    pub fn settings_clicked(&self) -> bool {
+        !self.mode_list.is_open() && self.btn_settings.is_activated(&self.nav)
     }
 
+    /// Vérifie si le bouton de test du vaisseau a été cliqué ou activé
     pub fn test_clicked(&self) -> bool {
-        self.btn_test.is_clicked()
+        !self.mode_list.is_open() && self.btn_test.is_activated(&self.nav)
     }
 }
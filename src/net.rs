@@ -0,0 +1,966 @@
+//! Module gérant les parties en réseau (UDP).
+//!
+//! L'hôte (`--host`) fait tourner la seule simulation qui fait foi. Le
+//! client (`--join ADRESSE`) lui envoie ses commandes et reçoit régulièrement
+//! l'état complet de la partie :
+//! * chaque paquet du client répète ses dernières commandes, pour qu'un paquet
+//!   perdu ne fasse pas perdre de commande ;
+//! * chaque état envoyé par l'hôte indique la dernière commande appliquée ;
+//! * le client prédit son propre vaisseau en rejouant sur le dernier état reçu
+//!   les commandes que l'hôte n'a pas encore appliquées ;
+//! * les autres objets sont affichés avec un léger retard, interpolés entre
+//!   deux états reçus.
+//!
+//! Chaque paquet contient un message JSON. Pour tester sur une seule machine,
+//! `--latency`, `--jitter` et `--packet-loss` retardent et perdent
+//! volontairement une partie des paquets envoyés.
+
+use std::collections::VecDeque;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+use std::time::{Duration, Instant};
+
+use ::rand::rngs::SmallRng;
+use ::rand::{Rng, SeedableRng};
+use macroquad::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::Assets;
+use crate::autopilot::Autopilot;
+use crate::cli::Options;
+use crate::effects::HitEffects;
+use crate::hud::Hud;
+use crate::input::{Controller, Input, Keyboard, ARROW_KEYS};
+use crate::locale::Strings;
+use crate::menu::PlayMode;
+use crate::render::{self, MacroquadRenderer};
+use crate::spaceship::Spaceship;
+use crate::versus::Match;
+use crate::world::{steer_spaceship, wrapped_delta, World};
+use crate::Difficulty;
+
+/// Port utilisé par défaut
+pub const DEFAULT_PORT: u16 = 7777;
+
+/// Nombre de frames entre deux états envoyés par l'hôte (30 par seconde)
+const SNAPSHOT_INTERVAL: u64 = 2;
+
+/// Retard d'affichage des objets interpolés, en frames de l'hôte
+const INTERPOLATION_DELAY: f32 = 6.0;
+
+/// Nombre de commandes répétées dans chaque paquet du client
+const REDUNDANT_INPUTS: usize = 10;
+
+/// Nombre maximal de commandes en attente, chez l'hôte comme chez le client
+const MAX_QUEUED_INPUTS: usize = 120;
+
+/// Déplacement au-delà duquel un objet n'est pas interpolé (réapparition, autre objet)
+const MAX_INTERPOLATED_JUMP: f32 = 40.0;
+
+/// Délai sans nouvelles après lequel la connexion est considérée perdue
+pub const TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Délai entre deux demandes de connexion du client
+pub const JOIN_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Taille maximale d'un paquet UDP
+const MAX_PACKET: usize = 65_507;
+
+/// Conditions réseau simulées sur les paquets envoyés
+///
+/// # Champs
+/// * `latency` - Retard ajouté à chaque paquet
+/// * `jitter` - Retard aléatoire supplémentaire maximal (les paquets peuvent
+///   alors arriver dans le désordre)
+/// * `loss` - Proportion de paquets perdus, entre 0 et 1
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetConditions {
+    pub latency: Duration,
+    pub jitter: Duration,
+    pub loss: f32,
+}
+
+impl NetConditions {
+    /// Conditions données par `--latency`, `--jitter` et `--packet-loss`
+    pub fn from_options(options: &Options) -> Self {
+        Self {
+            latency: Duration::from_millis(options.latency),
+            jitter: Duration::from_millis(options.jitter),
+            loss: options.packet_loss / 100.,
+        }
+    }
+}
+
+/// Messages échangés entre l'hôte et le client
+///
+/// Le type de la partie est générique pour envoyer une partie empruntée
+/// (`Message<&World>`) et recevoir une partie complète (`Message<World>`).
+///
+/// # Variantes
+/// * `Join` - Le client demande à rejoindre la partie
+/// * `Inputs` - Dernières commandes du client, la dernière portant le numéro `last`
+/// * `Leave` - Le client quitte la partie
+/// * `Welcome` - L'hôte accepte le client et lui donne son numéro de joueur
+/// * `Full` - L'hôte refuse le client, la partie est complète
+/// * `Snapshot` - État de la partie à la frame `tick` de l'hôte, après
+///   application de la commande `ack` du client
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum Message<W = World> {
+    Join,
+    Inputs {
+        last: u32,
+        inputs: Vec<String>,
+    },
+    Leave,
+    Welcome {
+        player: usize,
+    },
+    Full,
+    Snapshot {
+        tick: u64,
+        ack: u32,
+        duel: Option<Match>,
+        world: W,
+    },
+}
+
+/// Socket UDP non bloquante, avec des conditions réseau simulées à l'envoi
+///
+/// # Champs
+/// * `socket` - Socket utilisée
+/// * `conditions` - Retard et pertes appliqués aux paquets envoyés
+/// * `rng` - Tirage des pertes et des retards
+/// * `delayed` - Paquets retardés : instant d'envoi, destinataire et contenu
+pub struct Link {
+    socket: UdpSocket,
+    conditions: NetConditions,
+    rng: SmallRng,
+    delayed: Vec<(Instant, SocketAddr, Vec<u8>)>,
+}
+
+impl Link {
+    /// Ouvre une socket sur l'adresse locale donnée
+    pub fn bind(addr: SocketAddr, conditions: NetConditions) -> Result<Self, String> {
+        let socket =
+            UdpSocket::bind(addr).map_err(|e| format!("impossible d'ouvrir {addr} ({e})"))?;
+        socket.set_nonblocking(true).map_err(|e| e.to_string())?;
+        Ok(Self {
+            socket,
+            conditions,
+            rng: SmallRng::from_entropy(),
+            delayed: Vec::new(),
+        })
+    }
+
+    /// Adresse locale de la socket
+    pub fn local_addr(&self) -> Result<SocketAddr, String> {
+        self.socket.local_addr().map_err(|e| e.to_string())
+    }
+
+    /// Envoie un message, sauf s'il est perdu par les conditions simulées
+    pub fn send(&mut self, message: &impl Serialize, to: SocketAddr) {
+        let Ok(bytes) = serde_json::to_vec(message) else {
+            return;
+        };
+        if self.rng.gen::<f32>() < self.conditions.loss {
+            return;
+        }
+        let delay = self.conditions.latency + self.conditions.jitter.mul_f32(self.rng.gen());
+        if delay.is_zero() {
+            // Comme tout paquet UDP, un envoi qui échoue est simplement perdu
+            let _ = self.socket.send_to(&bytes, to);
+        } else {
+            self.delayed.push((Instant::now() + delay, to, bytes));
+        }
+    }
+
+    /// Envoie les paquets retardés dont l'heure est venue
+    ///
+    /// # Arguments
+    /// * `all` - Envoyer aussi les paquets dont l'heure n'est pas venue
+    fn flush(&mut self, all: bool) {
+        let now = Instant::now();
+        let socket = &self.socket;
+        self.delayed.retain(|(at, to, bytes)| {
+            if all || *at <= now {
+                let _ = socket.send_to(bytes, to);
+                false
+            } else {
+                true
+            }
+        });
+    }
+
+    /// Envoie les paquets retardés puis lit tous les messages reçus
+    ///
+    /// Les paquets qui ne contiennent pas un message valide sont ignorés.
+    pub fn receive<M: DeserializeOwned>(&mut self) -> Vec<(SocketAddr, M)> {
+        self.flush(false);
+        let mut messages = Vec::new();
+        let mut buffer = vec![0; MAX_PACKET];
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((len, from)) => {
+                    if let Ok(message) = serde_json::from_slice(&buffer[..len]) {
+                        messages.push((from, message));
+                    }
+                }
+                // Sous Windows, un paquet refusé par le destinataire remonte ici
+                Err(e) if e.kind() == ErrorKind::ConnectionReset => continue,
+                Err(_) => break,
+            }
+        }
+        messages
+    }
+}
+
+/// Client vu par l'hôte
+///
+/// # Champs
+/// * `addr` - Adresse du client
+/// * `queue` - Commandes reçues mais pas encore appliquées, avec leur numéro
+/// * `received` - Numéro de la dernière commande reçue
+/// * `applied` - Numéro de la dernière commande appliquée, renvoyé au client
+/// * `last_input` - Dernière commande appliquée, répétée si la suivante est en retard
+/// * `last_seen` - Instant du dernier paquet reçu
+struct Remote {
+    addr: SocketAddr,
+    queue: VecDeque<(u32, Input)>,
+    received: u32,
+    applied: u32,
+    last_input: Input,
+    last_seen: Instant,
+}
+
+impl Remote {
+    fn new(addr: SocketAddr) -> Self {
+        Self {
+            addr,
+            queue: VecDeque::new(),
+            received: 0,
+            applied: 0,
+            last_input: Input::default(),
+            last_seen: Instant::now(),
+        }
+    }
+
+    /// Ajoute les commandes d'un paquet qui n'ont pas déjà été reçues
+    ///
+    /// Un paquet dont les numéros sortent des entiers 32 bits est ignoré.
+    ///
+    /// # Arguments
+    /// * `last` - Numéro de la dernière commande du paquet
+    /// * `inputs` - Commandes encodées, de la plus ancienne à la plus récente
+    fn receive(&mut self, last: u32, inputs: &[String]) {
+        self.last_seen = Instant::now();
+        let first = last
+            .checked_add(1)
+            .and_then(|next| next.checked_sub(inputs.len() as u32));
+        let Some(first) = first else {
+            return;
+        };
+        for (seq, text) in (first..).zip(inputs) {
+            if seq <= self.received {
+                continue;
+            }
+            if let Ok(input) = Input::decode(text) {
+                self.queue.push_back((seq, input));
+                self.received = seq;
+            }
+        }
+        // Trop de retard accumulé : les commandes les plus anciennes sont abandonnées
+        while self.queue.len() > MAX_QUEUED_INPUTS {
+            self.next();
+        }
+    }
+
+    /// Commande à appliquer pour la prochaine frame
+    fn next(&mut self) -> Input {
+        if let Some((seq, input)) = self.queue.pop_front() {
+            self.applied = seq;
+            self.last_input = input;
+        }
+        self.last_input
+    }
+}
+
+/// Hôte d'une partie en réseau, qui accepte un client
+///
+/// # Champs
+/// * `link` - Socket de l'hôte
+/// * `remote` - Client connecté
+/// * `tick` - Nombre de frames de l'hôte, qui numérote les états envoyés
+pub struct Host {
+    link: Link,
+    remote: Option<Remote>,
+    tick: u64,
+}
+
+impl Host {
+    /// Ouvre la partie sur le port donné (0 pour un port libre quelconque)
+    pub fn bind(port: u16, conditions: NetConditions) -> Result<Self, String> {
+        Ok(Self {
+            link: Link::bind(SocketAddr::from(([0, 0, 0, 0], port)), conditions)?,
+            remote: None,
+            tick: 0,
+        })
+    }
+
+    /// Port sur lequel la partie est ouverte
+    pub fn port(&self) -> u16 {
+        self.link.local_addr().map_or(0, |addr| addr.port())
+    }
+
+    /// Vérifie si un client est connecté
+    pub fn is_connected(&self) -> bool {
+        self.remote.is_some()
+    }
+
+    /// Traite les messages reçus des clients
+    pub fn poll(&mut self) {
+        for (from, message) in self.link.receive::<Message>() {
+            let known = self
+                .remote
+                .as_ref()
+                .is_some_and(|remote| remote.addr == from);
+            match message {
+                // Le client redemande tant qu'il n'a pas reçu de réponse
+                Message::Join if known || self.remote.is_none() => {
+                    self.remote
+                        .get_or_insert_with(|| Remote::new(from))
+                        .last_seen = Instant::now();
+                    self.link
+                        .send(&Message::<World>::Welcome { player: 1 }, from);
+                }
+                Message::Join => self.link.send(&Message::<World>::Full, from),
+                Message::Inputs { last, inputs } if known => {
+                    if let Some(remote) = &mut self.remote {
+                        remote.receive(last, &inputs);
+                    }
+                }
+                Message::Leave if known => self.remote = None,
+                _ => {}
+            }
+        }
+        if self
+            .remote
+            .as_ref()
+            .is_some_and(|remote| remote.last_seen.elapsed() > TIMEOUT)
+        {
+            self.remote = None;
+        }
+    }
+
+    /// Commande du client pour la prochaine frame (aucune touche sans client)
+    pub fn next_input(&mut self) -> Input {
+        self.remote.as_mut().map(Remote::next).unwrap_or_default()
+    }
+
+    /// Compte une frame et envoie l'état de la partie au client si c'est le moment
+    pub fn send_snapshot(&mut self, world: &World, duel: Option<&Match>) {
+        self.tick += 1;
+        if !self.tick.is_multiple_of(SNAPSHOT_INTERVAL) {
+            return;
+        }
+        let Some(remote) = &self.remote else {
+            return;
+        };
+        let message = Message::Snapshot {
+            tick: self.tick,
+            ack: remote.applied,
+            duel: duel.cloned(),
+            world,
+        };
+        let addr = remote.addr;
+        self.link.send(&message, addr);
+    }
+}
+
+/// États reçus de l'hôte, affichés avec un retard pour être interpolés
+///
+/// # Champs
+/// * `snapshots` - États reçus, numérotés par la frame de l'hôte, du plus ancien au plus récent
+/// * `time` - Frame de l'hôte affichée
+#[derive(Default)]
+pub struct Interpolator {
+    snapshots: VecDeque<(u64, World)>,
+    time: f32,
+}
+
+impl Interpolator {
+    /// Ajoute un état reçu
+    ///
+    /// # Retourne
+    /// `false` si l'état est plus ancien que le dernier reçu (arrivé dans le désordre)
+    pub fn push(&mut self, tick: u64, world: World) -> bool {
+        if self.snapshots.back().is_some_and(|(last, _)| tick <= *last) {
+            return false;
+        }
+        self.snapshots.push_back((tick, world));
+        self.discard_old();
+        true
+    }
+
+    /// État le plus récent
+    pub fn latest(&self) -> Option<&World> {
+        self.snapshots.back().map(|(_, world)| world)
+    }
+
+    /// Avance l'affichage d'une frame
+    pub fn advance(&mut self) {
+        let Some((newest, _)) = self.snapshots.back() else {
+            return;
+        };
+        let target = *newest as f32 - INTERPOLATION_DELAY;
+        self.time += 1.;
+        // Recale l'affichage après le premier état ou une longue coupure
+        if (self.time - target).abs() > INTERPOLATION_DELAY {
+            self.time = target;
+        }
+        self.discard_old();
+    }
+
+    /// Oublie les états devenus inutiles, en gardant celui qui précède l'affichage
+    fn discard_old(&mut self) {
+        while self.snapshots.len() > 2 && self.snapshots[1].0 as f32 <= self.time {
+            self.snapshots.pop_front();
+        }
+    }
+
+    /// Partie à afficher, interpolée entre les deux états qui encadrent l'affichage
+    pub fn sample(&self) -> Option<World> {
+        let pair = self
+            .snapshots
+            .iter()
+            .zip(self.snapshots.iter().skip(1))
+            .find(|((a, _), (b, _))| *a as f32 <= self.time && self.time <= *b as f32);
+        match pair {
+            Some(((a, from), (b, to))) => {
+                let t = (self.time - *a as f32) / (*b - *a) as f32;
+                Some(interpolate(from, to, t))
+            }
+            // En avance sur les états reçus (paquets perdus) : le plus récent
+            None if self.snapshots.front()?.0 as f32 <= self.time => self.latest().cloned(),
+            None => self.snapshots.front().map(|(_, world)| world.clone()),
+        }
+    }
+}
+
+/// Partie intermédiaire entre deux états
+///
+/// Seules les positions sont interpolées. Les objets sont associés par leur
+/// rang : si leur nombre a changé, ceux du nouvel état sont affichés tels quels.
+fn interpolate(from: &World, to: &World, t: f32) -> World {
+    let mut world = to.clone();
+    let size = to.size;
+    for (player, old) in world.players.iter_mut().zip(&from.players) {
+        let position = &mut player.spaceship.position;
+        *position = lerp_position(old.spaceship.position, *position, t, size);
+    }
+    if from.asteroids.len() == to.asteroids.len() {
+        for (asteroid, old) in world.asteroids.iter_mut().zip(&from.asteroids) {
+            asteroid.position = lerp_position(old.position, asteroid.position, t, size);
+        }
+    }
+    if from.bullets.len() == to.bullets.len() {
+        for (bullet, old) in world.bullets.iter_mut().zip(&from.bullets) {
+            bullet.position = lerp_position(old.position, bullet.position, t, size);
+        }
+    }
+    world
+}
+
+/// Position intermédiaire d'un objet, par le plus court chemin dans l'arène torique
+fn lerp_position(from: Vec2, to: Vec2, t: f32, arena: Vec2) -> Vec2 {
+    let delta = wrapped_delta(from, to, arena);
+    if delta.length() > MAX_INTERPOLATED_JUMP {
+        return to;
+    }
+    let position = to - delta * (1. - t);
+    vec2(
+        position.x.rem_euclid(arena.x),
+        position.y.rem_euclid(arena.y),
+    )
+}
+
+/// Client d'une partie en réseau
+///
+/// # Champs
+/// * `link` - Socket du client
+/// * `host` - Adresse de l'hôte
+/// * `player` - Numéro de joueur donné par l'hôte, `None` avant sa réponse
+/// * `seq` - Numéro de la dernière commande envoyée
+/// * `pending` - Commandes envoyées que l'hôte n'a pas encore appliquées
+/// * `snapshots` - États reçus de l'hôte
+/// * `predicted` - Vaisseau du joueur prédit à partir de ses commandes
+/// * `duel` - Score du duel reçu avec le dernier état
+/// * `last_seen` - Instant du dernier message de l'hôte
+/// * `last_join` - Instant de la dernière demande de connexion
+pub struct Client {
+    link: Link,
+    host: SocketAddr,
+    player: Option<usize>,
+    seq: u32,
+    pending: VecDeque<(u32, Input)>,
+    snapshots: Interpolator,
+    predicted: Option<Spaceship>,
+    duel: Option<Match>,
+    last_seen: Instant,
+    last_join: Option<Instant>,
+}
+
+impl Client {
+    /// Prépare la connexion à l'hôte (`adresse` ou `adresse:port`)
+    pub fn connect(addr: &str, conditions: NetConditions) -> Result<Self, String> {
+        // Sans port précisé, celui par défaut de l'hôte
+        let addrs = if addr.contains(':') {
+            addr.to_socket_addrs()
+        } else {
+            (addr, DEFAULT_PORT).to_socket_addrs()
+        };
+        let host = addrs
+            .map_err(|e| format!("adresse invalide '{addr}' ({e})"))?
+            .next()
+            .ok_or_else(|| format!("adresse introuvable '{addr}'"))?;
+        let local = if host.is_ipv4() {
+            SocketAddr::from(([0, 0, 0, 0], 0))
+        } else {
+            SocketAddr::from(([0u16; 8], 0))
+        };
+        Ok(Self {
+            link: Link::bind(local, conditions)?,
+            host,
+            player: None,
+            seq: 0,
+            pending: VecDeque::new(),
+            snapshots: Interpolator::default(),
+            predicted: None,
+            duel: None,
+            last_seen: Instant::now(),
+            last_join: None,
+        })
+    }
+
+    /// Numéro de joueur donné par l'hôte
+    pub fn player(&self) -> Option<usize> {
+        self.player
+    }
+
+    /// Score du duel en cours, `None` hors duel
+    pub fn duel(&self) -> Option<&Match> {
+        self.duel.as_ref()
+    }
+
+    /// Demande à rejoindre la partie puis traite les messages de l'hôte
+    ///
+    /// # Retourne
+    /// Une erreur si la partie est complète ou si l'hôte ne répond plus
+    pub fn poll(&mut self) -> Result<(), String> {
+        if self.player.is_none()
+            && self
+                .last_join
+                .is_none_or(|last| last.elapsed() >= JOIN_INTERVAL)
+        {
+            self.link.send(&Message::<World>::Join, self.host);
+            self.last_join = Some(Instant::now());
+        }
+
+        for (from, message) in self.link.receive::<Message>() {
+            if from != self.host {
+                continue;
+            }
+            self.last_seen = Instant::now();
+            match message {
+                Message::Welcome { player } => {
+                    self.player.get_or_insert(player);
+                }
+                Message::Full => return Err("la partie est complète".to_string()),
+                Message::Snapshot {
+                    tick,
+                    ack,
+                    duel,
+                    world,
+                } => {
+                    // Un état arrivé après un plus récent est ignoré
+                    if !self.snapshots.push(tick, world) {
+                        continue;
+                    }
+                    self.duel = duel;
+                    self.reconcile(ack);
+                }
+                _ => {}
+            }
+        }
+
+        if self.last_seen.elapsed() > TIMEOUT {
+            return Err(match self.player {
+                Some(_) => "connexion perdue avec l'hôte".to_string(),
+                None => format!("l'hôte {} ne répond pas", self.host),
+            });
+        }
+        Ok(())
+    }
+
+    /// Recalcule le vaisseau prédit à partir du dernier état reçu
+    ///
+    /// # Arguments
+    /// * `ack` - Dernière commande appliquée par l'hôte dans cet état
+    fn reconcile(&mut self, ack: u32) {
+        self.pending.retain(|(seq, _)| *seq > ack);
+        let (Some(player), Some(world)) = (self.player, self.snapshots.latest()) else {
+            return;
+        };
+        self.predicted = world
+            .players
+            .get(player)
+            .filter(|player| player.is_alive() && !world.is_over())
+            .map(|player| {
+                let mut spaceship = player.spaceship.clone();
+                for (_, input) in &self.pending {
+                    steer_spaceship(&mut spaceship, input, world.size);
+                }
+                spaceship
+            });
+    }
+
+    /// Envoie la commande de la frame et l'applique au vaisseau prédit
+    pub fn send_input(&mut self, input: Input) {
+        if self.player.is_none() {
+            return;
+        }
+        self.seq += 1;
+        self.pending.push_back((self.seq, input));
+        if self.pending.len() > MAX_QUEUED_INPUTS {
+            self.pending.pop_front();
+        }
+        if let (Some(spaceship), Some(world)) = (&mut self.predicted, self.snapshots.latest()) {
+            steer_spaceship(spaceship, &input, world.size);
+        }
+
+        let skip = self.pending.len().saturating_sub(REDUNDANT_INPUTS);
+        let inputs = self
+            .pending
+            .iter()
+            .skip(skip)
+            .map(|(_, input)| input.encode())
+            .collect();
+        let message = Message::<World>::Inputs {
+            last: self.seq,
+            inputs,
+        };
+        self.link.send(&message, self.host);
+    }
+
+    /// Avance l'affichage d'une frame
+    pub fn advance(&mut self) {
+        self.snapshots.advance();
+    }
+
+    /// Partie à afficher : états interpolés et vaisseau du joueur prédit
+    pub fn view(&self) -> Option<World> {
+        let mut world = self.snapshots.sample()?;
+        if let (Some(player), Some(spaceship)) = (self.player, &self.predicted) {
+            if let Some(player) = world.players.get_mut(player) {
+                player.spaceship = spaceship.clone();
+            }
+        }
+        Some(world)
+    }
+
+    /// Prévient l'hôte que le client quitte la partie
+    pub fn leave(&mut self) {
+        self.link.send(&Message::<World>::Leave, self.host);
+        self.link.flush(true);
+    }
+}
+
+/// Pilote du joueur local : clavier (flèches) ou automatique (`--autopilot`)
+pub fn local_controller(options: &Options) -> Box<dyn Controller> {
+    if options.autopilot {
+        Box::new(Autopilot::new())
+    } else {
+        Box::new(Keyboard::new(ARROW_KEYS))
+    }
+}
+
+/// Affiche le score du duel et le message de fin de partie
+///
+/// # Arguments
+/// * `waiting` - Texte affiché à la place de l'action de la touche Entrée
+/// * `strings` - Textes de l'interface dans la langue du joueur
+pub fn draw_status(
+    world: &World,
+    duel: Option<&Match>,
+    font: Option<&Font>,
+    waiting: Option<&str>,
+    strings: &Strings,
+) {
+    if let Some(duel) = duel {
+        render::draw_centered_text(&duel.score_text(strings), 40., 30, WHITE, font);
+    }
+    if world.is_over() {
+        let (text, action, color) = crate::game_over_message(duel, world, strings);
+        let text = match waiting {
+            Some(waiting) => format!("{text} {waiting}"),
+            None => strings.format("net.press_enter", &[&text, &action]),
+        };
+        render::draw_centered_text(&text, screen_height() / 2., 40, color, font);
+    }
+}
+
+/// Boucle de jeu de l'hôte
+///
+/// L'hôte joue le premier vaisseau ; la partie est figée tant qu'aucun
+/// client n'est connecté.
+///
+/// # Arguments
+/// * `options` - Options de lancement (difficulté, coopération ou duel)
+/// * `seed` - Graine de la première partie
+/// * `host` - Partie ouverte par `Host::bind`
+pub async fn host_game(options: Options, seed: u64, mut host: Host) {
+    let assets = Assets::load(options.data_dir.as_deref());
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
+    assets.show_errors(&strings).await;
+    let font = assets.font();
+
+    let next_seed = || options.seed.unwrap_or_else(::rand::random);
+    // Une partie en réseau se joue toujours à deux
+    let mode = match crate::play_mode(&options) {
+        PlayMode::Solo => PlayMode::Coop {
+            friendly_fire: false,
+        },
+        mode => mode,
+    };
+    let difficulty = options.difficulty.unwrap_or(Difficulty::Easy);
+    let mut world = crate::new_game(seed, difficulty, mode);
+    let mut duel = crate::new_match(mode);
+    let mut controller = local_controller(&options);
+    let mut effects = HitEffects::default();
+
+    loop {
+        if is_key_down(KeyCode::Escape) {
+            break;
+        }
+        host.poll();
+
+        // Un choc fait trembler l'arène, comme dans une partie locale
+        effects.update(get_frame_time());
+        effects.observe(&world);
+        effects.draw(
+            &mut MacroquadRenderer::new(&assets, options.render_mode),
+            &world,
+            &Hud::new(&strings),
+        );
+        if !host.is_connected() {
+            let text = strings.format("net.waiting_player", &[&host.port()]);
+            render::draw_centered_text(&text, screen_height() / 2., 30, WHITE, font);
+            next_frame().await;
+            continue;
+        }
+
+        let inputs = [controller.input(&world, 0), host.next_input()];
+        let was_running = !world.is_over();
+        world.step_players(&inputs);
+        if let Some(duel) = &mut duel {
+            if was_running && world.is_over() {
+                duel.record(world.winner());
+            }
+        }
+        host.send_snapshot(&world, duel.as_ref());
+        draw_status(&world, duel.as_ref(), font, None, &strings);
+
+        // Manche suivante ou nouvelle partie, lancée par l'hôte
+        if world.is_over() && is_key_down(KeyCode::Enter) {
+            if duel.as_ref().is_some_and(Match::is_over) {
+                duel = crate::new_match(mode);
+            }
+            world = crate::new_game(next_seed(), difficulty, mode);
+            effects = HitEffects::default();
+        }
+
+        next_frame().await;
+    }
+}
+
+/// Boucle de jeu du client
+///
+/// # Arguments
+/// * `options` - Options de lancement (adresse de l'hôte, pilote)
+/// * `client` - Connexion préparée par `Client::connect`
+pub async fn join_game(options: Options, mut client: Client) {
+    let assets = Assets::load(options.data_dir.as_deref());
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
+    assets.show_errors(&strings).await;
+    let font = assets.font();
+
+    let mut controller = local_controller(&options);
+    let mut resized = false;
+    let mut effects = HitEffects::default();
+
+    loop {
+        if is_key_down(KeyCode::Escape) {
+            client.leave();
+            break;
+        }
+        if let Err(e) = client.poll() {
+            eprintln!("{}", strings.format("error", &[&e]));
+            break;
+        }
+        client.advance();
+
+        let (Some(player), Some(world)) = (client.player(), client.view()) else {
+            clear_background(BLACK);
+            let text = strings.format("net.connecting", &[&options.join.as_deref().unwrap_or("")]);
+            render::draw_centered_text(&text, screen_height() / 2., 30, WHITE, font);
+            next_frame().await;
+            continue;
+        };
+        // La fenêtre prend la taille de l'arène de l'hôte
+        if !resized {
+            request_new_screen_size(world.size.x, world.size.y);
+            resized = true;
+        }
+
+        let input = controller.input(&world, player);
+        client.send_input(input);
+        let world = client.view().unwrap_or(world);
+
+        // Les chocs sont repérés dans les images reçues de l'hôte
+        effects.update(get_frame_time());
+        effects.observe(&world);
+        effects.draw(
+            &mut MacroquadRenderer::new(&assets, options.render_mode),
+            &world,
+            &Hud::new(&strings),
+        );
+        let waiting = strings.get("net.waiting_host");
+        draw_status(&world, client.duel(), font, Some(waiting), &strings);
+
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::world::DEFAULT_ARENA;
+
+    #[test]
+    fn commandes_redondantes() {
+        let mut remote = Remote::new(SocketAddr::from(([127, 0, 0, 1], 1)));
+        let inputs = |codes: &[&str]| {
+            codes
+                .iter()
+                .map(|code| code.to_string())
+                .collect::<Vec<_>>()
+        };
+        remote.receive(2, &inputs(&["L....", ".R..."]));
+        // Le paquet suivant répète la commande 2 : elle n'est pas reçue deux fois
+        remote.receive(3, &inputs(&[".R...", "..U.."]));
+        assert_eq!(remote.queue.len(), 3);
+
+        assert!(remote.next().left);
+        assert_eq!(remote.applied, 1);
+        remote.next();
+        assert!(remote.next().up);
+        // Sans nouvelle commande, la dernière est répétée sans être confirmée
+        assert!(remote.next().up);
+        assert_eq!(remote.applied, 3);
+
+        // Un paquet aux numéros invalides est ignoré
+        remote.receive(u32::MAX, &inputs(&["L...."]));
+        remote.receive(0, &inputs(&["L....", ".R..."]));
+        assert!(remote.queue.is_empty());
+    }
+
+    #[test]
+    fn interpolation() {
+        let mut from = World::new(0, Difficulty::Easy, DEFAULT_ARENA);
+        let mut to = from.clone();
+        from.players[0].spaceship.position = vec2(100., 100.);
+        to.players[0].spaceship.position = vec2(110., 100.);
+        // De part et d'autre du bord droit : le chemin le plus court passe par le bord
+        from.asteroids[0].position = vec2(795., 300.);
+        to.asteroids[0].position = vec2(5., 300.);
+
+        let world = interpolate(&from, &to, 0.5);
+        assert_eq!(world.players[0].spaceship.position, vec2(105., 100.));
+        assert!((world.asteroids[0].position.x - 0.).abs() < 1e-3);
+
+        let mut snapshots = Interpolator::default();
+        assert!(snapshots.push(10, from));
+        assert!(snapshots.push(20, to));
+        assert!(!snapshots.push(15, World::new(0, Difficulty::Easy, DEFAULT_ARENA)));
+        snapshots.advance();
+        assert_eq!(snapshots.time, 20. - INTERPOLATION_DELAY);
+    }
+
+    /// Attend qu'une condition soit remplie, au plus deux secondes
+    fn wait_until(mut condition: impl FnMut() -> bool) {
+        let start = Instant::now();
+        while !condition() {
+            assert!(start.elapsed() < Duration::from_secs(2), "délai dépassé");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn partie_locale() {
+        let conditions = NetConditions {
+            latency: Duration::from_millis(5),
+            jitter: Duration::from_millis(5),
+            loss: 0.,
+        };
+        let mut host = Host::bind(0, conditions).unwrap();
+        let addr = format!("127.0.0.1:{}", host.port());
+        let mut client = Client::connect(&addr, conditions).unwrap();
+        wait_until(|| {
+            client.poll().unwrap();
+            host.poll();
+            client.player().is_some()
+        });
+        assert!(host.is_connected());
+        assert_eq!(client.player(), Some(1));
+
+        // Deux vaisseaux sans astéroïdes : seules les commandes les déplacent
+        let mut world = World::with_players(0, Difficulty::Easy, DEFAULT_ARENA, 2);
+        world.asteroids.clear();
+        world.sandbox = true;
+        let thrust = Input {
+            up: true,
+            left: true,
+            ..Default::default()
+        };
+        for _ in 0..60 {
+            client.poll().unwrap();
+            client.send_input(thrust);
+            // L'hôte attend chaque commande pour ne jamais répéter la précédente
+            // (les paquets retardés partent quand le client lit les siens)
+            let seq = client.seq;
+            wait_until(|| {
+                client.poll().unwrap();
+                host.poll();
+                host.remote
+                    .as_ref()
+                    .is_some_and(|remote| remote.received == seq)
+            });
+            world.step_players(&[Input::default(), host.next_input()]);
+            host.send_snapshot(&world, None);
+        }
+        client.poll().unwrap();
+
+        // Les états reçus sont en retard sur l'hôte, mais la prédiction le rejoint
+        assert!(client.snapshots.latest().is_some());
+        let predicted = client.view().unwrap().players[1].spaceship.position;
+        let expected = world.players[1].spaceship.position;
+        assert!((predicted - expected).length() < 1e-3);
+
+        client.leave();
+        wait_until(|| {
+            host.poll();
+            !host.is_connected()
+        });
+    }
+}
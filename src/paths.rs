@@ -0,0 +1,53 @@
+//! Module donnant les dossiers propres à l'utilisateur.
+//!
+//! Les fichiers écrits par le jeu (sauvegardes...) sont rangés dans le dossier
+//! de données de l'utilisateur plutôt que dans le dossier courant, et ses
+//! préférences dans son dossier de configuration.
+
+use std::env;
+use std::path::PathBuf;
+
+/// Nom du sous-dossier du jeu
+const APP_DIR: &str = "asteroid";
+
+/// Dossier de données de l'utilisateur
+///
+/// * Linux : `$XDG_DATA_HOME/asteroid` ou `~/.local/share/asteroid`
+/// * Windows : `%APPDATA%\asteroid`
+/// * macOS : `~/Library/Application Support/asteroid`
+///
+/// # Retourne
+/// `None` si aucun dossier personnel n'est connu
+pub fn user_data_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    base.map(|dir| dir.join(APP_DIR))
+}
+
+/// Dossier de configuration de l'utilisateur
+///
+/// * Linux : `$XDG_CONFIG_HOME/asteroid` ou `~/.config/asteroid`
+/// * Windows : `%APPDATA%\asteroid`
+/// * macOS : `~/Library/Preferences/asteroid`
+///
+/// # Retourne
+/// `None` si aucun dossier personnel n'est connu
+pub fn user_config_dir() -> Option<PathBuf> {
+    let base = if cfg!(windows) {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Preferences"))
+    } else {
+        env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    base.map(|dir| dir.join(APP_DIR))
+}
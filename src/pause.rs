@@ -0,0 +1,171 @@
+//! Module représentant le menu pause du jeu
+//!
+//! Ce module contient la structure `PauseMenu` affichée par-dessus la partie
+//! lorsque le joueur appuie sur `P`. Il permet de reprendre, de sauvegarder ou
+//! de charger une partie. Comme le menu principal, il se parcourt aussi au
+//! clavier et à la manette.
+
+use crate::button::Button;
+use crate::locale::Strings;
+use crate::ui::{Column, Focus, Label, NavAction, Navigation, Viewport};
+use macroquad::prelude::*;
+
+/// Touche ouvrant et fermant le menu pause
+pub const PAUSE_KEY: KeyCode = KeyCode::P;
+
+/// Boutons du menu pause pouvant recevoir le focus, de haut en bas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    Resume,
+    Save,
+    Load,
+    Quit,
+}
+
+/// Représente le menu pause
+///
+/// # Champs
+/// * `title` - Titre du menu
+/// * `btn_resume` - Bouton pour reprendre la partie
+/// * `btn_save` - Bouton pour sauvegarder la partie
+/// * `btn_load` - Bouton pour charger la dernière sauvegarde
+/// * `btn_quit` - Bouton pour revenir au menu principal
+/// * `message` - Résultat de la dernière sauvegarde ou du dernier chargement
+/// * `focus` - Bouton ayant le focus du clavier et de la manette
+/// * `nav` - Actions de navigation de la frame en cours
+/// * `viewport` - Taille de la fenêtre pour laquelle les boutons sont placés
+pub struct PauseMenu {
+    title: Label,
+    btn_resume: Button,
+    btn_save: Button,
+    btn_load: Button,
+    btn_quit: Button,
+    message: Label,
+    focus: Focus<Item>,
+    nav: Navigation,
+    viewport: Viewport,
+}
+
+impl PauseMenu {
+    /// Crée un nouveau menu pause centré dans la fenêtre
+    ///
+    /// # Arguments
+    /// * `has_save` - Une sauvegarde existe, le bouton charger est actif
+    /// * `strings` - Textes de l'interface dans la langue du joueur
+    pub fn new(has_save: bool, strings: &Strings) -> Self {
+        let rect = Rect::default();
+        let text = |key| strings.get(key);
+        let mut btn_load = Button::from_rect(rect, ORANGE, text("pause.load"));
+        btn_load.set_enabled(has_save);
+        let mut menu = Self {
+            title: Label::new(rect, text("pause.title")).with_size(60),
+            btn_resume: Button::from_rect(rect, GREEN, text("pause.resume")),
+            btn_save: Button::from_rect(rect, YELLOW, text("pause.save")),
+            btn_load,
+            btn_quit: Button::from_rect(rect, GRAY, text("pause.quit")),
+            message: Label::new(rect, ""),
+            focus: Focus::new(),
+            nav: Navigation::default(),
+            viewport: Viewport::default(),
+        };
+        menu.viewport.resized();
+        menu.layout();
+        menu
+    }
+
+    /// Place les boutons en colonne au centre de la fenêtre
+    fn layout(&mut self) {
+        let screen = self.viewport.rect();
+        let column = Column::new(screen.w / 2.0, 0.0, 200.0, 40.0, 20.0);
+        let column = Column {
+            top: (screen.h - column.height(4)) / 2.0,
+            ..column
+        };
+        let full_width = |y: f32, height: f32| Rect::new(0., y, screen.w, height);
+
+        self.title.set_rect(full_width(column.top - 100., 60.));
+        self.btn_resume.set_rect(column.row(0));
+        self.btn_save.set_rect(column.row(1));
+        self.btn_load.set_rect(column.row(2));
+        self.btn_quit.set_rect(column.row(3));
+        self.message
+            .set_rect(full_width(column.row(4).y, column.row_height));
+    }
+
+    /// Replace les boutons si la fenêtre a changé de taille et déplace le focus
+    /// avec les flèches haut et bas, à appeler à chaque frame
+    ///
+    /// # Arguments
+    /// * `nav` - Actions de navigation de la frame
+    pub fn update(&mut self, nav: &Navigation) {
+        if self.viewport.resized() {
+            self.layout();
+        }
+        self.nav = nav.clone();
+        let items = [Item::Resume, Item::Save, Item::Load, Item::Quit];
+        self.focus
+            .navigate(&items, nav.step(NavAction::Up, NavAction::Down));
+        self.btn_resume.set_focused(self.focus.is(Item::Resume));
+        self.btn_save.set_focused(self.focus.is(Item::Save));
+        self.btn_load.set_focused(self.focus.is(Item::Load));
+        self.btn_quit.set_focused(self.focus.is(Item::Quit));
+    }
+
+    /// Dessine le menu par-dessus la partie
+    ///
+    /// # Arguments
+    /// * `font` - Police de caractères à utiliser pour le texte (`None` pour la police par défaut)
+    pub fn draw(&mut self, font: Option<&Font>) {
+        // Assombrir la partie en arrière-plan
+        draw_rectangle(
+            0.,
+            0.,
+            screen_width(),
+            screen_height(),
+            Color::new(0., 0., 0., 0.6),
+        );
+
+        self.title.draw(font);
+        self.btn_resume.draw(font);
+        self.btn_save.draw(font);
+        self.btn_load.draw(font);
+        self.btn_quit.draw(font);
+        self.message.draw(font);
+    }
+
+    /// Active le bouton charger, une fois une sauvegarde écrite
+    pub fn set_load_enabled(&mut self, enabled: bool) {
+        self.btn_load.set_enabled(enabled);
+    }
+
+    /// Affiche le résultat d'une action sous les boutons
+    ///
+    /// # Arguments
+    /// * `result` - Message de réussite ou erreur
+    pub fn set_result(&mut self, result: Result<String, String>) {
+        match result {
+            Ok(message) => self.message.set_text(&message, GREEN),
+            Err(e) => self.message.set_text(&e, RED),
+        }
+    }
+
+    /// Vérifie si le bouton reprendre a été cliqué ou activé, ou la touche pause pressée
+    pub fn resume_clicked(&self) -> bool {
+        self.btn_resume.is_activated(&self.nav) || is_key_pressed(PAUSE_KEY)
+    }
+
+    /// Vérifie si le bouton sauvegarder a été cliqué ou activé
+    pub fn save_clicked(&self) -> bool {
+        self.btn_save.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton charger a été cliqué ou activé
+    pub fn load_clicked(&self) -> bool {
+        self.btn_load.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton menu principal a été cliqué ou activé
+    pub fn quit_clicked(&self) -> bool {
+        self.btn_quit.is_activated(&self.nav)
+    }
+}
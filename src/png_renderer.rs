@@ -0,0 +1,290 @@
+//! Module contenant un moteur de rendu logiciel produisant des images PNG.
+//!
+//! Le dessin est fait pixel par pixel sur le processeur, sans contexte OpenGL,
+//! ce qui permet d'enregistrer l'état d'une partie pour un rapport de bug ou de
+//! comparer une scène à une image de référence dans les tests.
+//!
+//! Les images du jeu ne sont pas utilisées : le vaisseau est dessiné par son
+//! contour, les astéroïdes par un disque inscrit dans le rectangle de leur
+//! image et les textes par le rectangle qu'ils occupent.
+
+use std::fs::File;
+use std::io::BufWriter;
+
+use macroquad::prelude::*;
+
+use crate::asteroid::Asteroid;
+use crate::bullet::Bullet;
+use crate::render::{
+    asteroid_sprite_rect, explosion_debris, player_color, spaceship_outline, Renderer,
+    GAUGE_BACKGROUND,
+};
+use crate::spaceship::Spaceship;
+
+/// Couleur du fond
+const BACKGROUND_COLOR: Color = Color::new(0.02, 0.02, 0.08, 1.0);
+
+/// Couleur des astéroïdes
+const ASTEROID_COLOR: Color = GRAY;
+
+/// Largeur moyenne d'un caractère par rapport à la taille de police
+const CHAR_WIDTH_RATIO: f32 = 0.5;
+
+/// Hauteur des majuscules par rapport à la taille de police
+const CAP_HEIGHT_RATIO: f32 = 0.7;
+
+/// Moteur de rendu dessinant dans une image en mémoire
+///
+/// # Champs
+/// * `width` - Largeur de l'image en pixels
+/// * `height` - Hauteur de l'image en pixels
+/// * `pixels` - Pixels RGBA, ligne par ligne depuis le haut
+pub struct PngRenderer {
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+impl PngRenderer {
+    /// Crée une image de la taille de l'arène (un pixel par unité)
+    pub fn new(arena: Vec2) -> Self {
+        let width = arena.x.max(1.) as u32;
+        let height = arena.y.max(1.) as u32;
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    /// Enregistre l'image dans un fichier PNG
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let file = File::create(path).map_err(|e| format!("Impossible d'écrire '{path}' : {e}"))?;
+        self.write(BufWriter::new(file))
+            .map_err(|e| format!("{path} : {e}"))
+    }
+
+    fn write<W: std::io::Write>(&self, writer: W) -> Result<(), String> {
+        let mut encoder = png::Encoder::new(writer, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer
+            .write_image_data(&self.pixels)
+            .map_err(|e| e.to_string())
+    }
+
+    /// Mélange une couleur dans un pixel selon sa transparence
+    fn blend(&mut self, x: i64, y: i64, color: Color) {
+        if x < 0 || y < 0 || x >= self.width as i64 || y >= self.height as i64 {
+            return;
+        }
+        let i = ((y as u32 * self.width + x as u32) * 4) as usize;
+        let source = [color.r, color.g, color.b];
+        for (channel, value) in source.iter().enumerate() {
+            let old = self.pixels[i + channel] as f32 / 255.;
+            let new = value * color.a + old * (1. - color.a);
+            self.pixels[i + channel] = (new.clamp(0., 1.) * 255.).round() as u8;
+        }
+        self.pixels[i + 3] = 255;
+    }
+
+    /// Remplit les pixels dont le centre vérifie `inside`, dans un rectangle englobant
+    fn fill(&mut self, bounds: Rect, color: Color, inside: impl Fn(Vec2) -> bool) {
+        let (x0, y0) = (bounds.x.floor() as i64, bounds.y.floor() as i64);
+        let (x1, y1) = (bounds.right().ceil() as i64, bounds.bottom().ceil() as i64);
+        for y in y0.max(0)..y1.min(self.height as i64) {
+            for x in x0.max(0)..x1.min(self.width as i64) {
+                if inside(vec2(x as f32 + 0.5, y as f32 + 0.5)) {
+                    self.blend(x, y, color);
+                }
+            }
+        }
+    }
+
+    /// Dessine un disque plein
+    pub fn fill_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        let bounds = Rect::new(
+            center.x - radius,
+            center.y - radius,
+            radius * 2.,
+            radius * 2.,
+        );
+        self.fill(bounds, color, |p| p.distance(center) <= radius);
+    }
+
+    /// Dessine un rectangle plein
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        self.fill(rect, color, |p| rect.contains(p));
+    }
+
+    /// Dessine un polygone plein (règle pair-impair)
+    pub fn fill_polygon(&mut self, points: &[Vec2], color: Color) {
+        let Some(bounds) = bounding_rect(points) else {
+            return;
+        };
+        self.fill(bounds, color, |p| polygon_contains(points, p));
+    }
+}
+
+impl Renderer for PngRenderer {
+    fn draw_background(&mut self) {
+        let bounds = Rect::new(0., 0., self.width as f32, self.height as f32);
+        self.fill_rect(bounds, BACKGROUND_COLOR);
+    }
+
+    fn draw_spaceship(&mut self, spaceship: &Spaceship, player: usize) {
+        self.fill_polygon(&spaceship_outline(spaceship), player_color(player));
+    }
+
+    /// Disque inscrit dans le rectangle de l'image de l'astéroïde
+    fn draw_asteroid(&mut self, asteroid: &Asteroid) {
+        let rect = asteroid_sprite_rect(asteroid);
+        self.fill_circle(rect.center(), rect.w / 2., ASTEROID_COLOR);
+    }
+
+    fn draw_bullet(&mut self, bullet: &Bullet) {
+        self.fill_circle(bullet.get_position(), bullet.get_size(), RED);
+    }
+
+    /// Seuls les débris sont dessinés, de plus en plus transparents
+    fn draw_explosion(&mut self, center: Vec2, progress: f32, player: usize) {
+        let color = Color {
+            a: 1. - progress,
+            ..player_color(player)
+        };
+        for debris in explosion_debris(center, progress) {
+            self.fill_circle(debris, 2., color);
+        }
+    }
+
+    /// Le texte est représenté par le rectangle qu'il occupe au-dessus de sa ligne de base
+    fn draw_text(&mut self, text: &str, pos: Vec2, font_size: u16, color: Color) {
+        self.fill_rect(text_box(text, pos, font_size), color);
+    }
+
+    fn draw_gauge(&mut self, rect: Rect, ratio: f32, color: Color) {
+        self.fill_rect(rect, GAUGE_BACKGROUND);
+        let fill = Rect::new(rect.x, rect.y, rect.w * ratio.clamp(0., 1.), rect.h);
+        self.fill_rect(fill, color);
+    }
+
+    fn measure_text(&self, text: &str, font_size: u16) -> f32 {
+        text_box(text, Vec2::ZERO, font_size).w
+    }
+}
+
+/// Rectangle approximatif occupé par un texte
+///
+/// # Arguments
+/// * `text` - Texte affiché
+/// * `pos` - Début de la ligne de base du texte
+/// * `font_size` - Taille de police
+pub fn text_box(text: &str, pos: Vec2, font_size: u16) -> Rect {
+    let size = font_size as f32;
+    let width = text.chars().count() as f32 * size * CHAR_WIDTH_RATIO;
+    let height = size * CAP_HEIGHT_RATIO;
+    Rect::new(pos.x, pos.y - height, width, height)
+}
+
+/// Rectangle englobant une liste de points
+fn bounding_rect(points: &[Vec2]) -> Option<Rect> {
+    let first = *points.first()?;
+    let (min, max) = points
+        .iter()
+        .fold((first, first), |(min, max), p| (min.min(*p), max.max(*p)));
+    Some(Rect::new(min.x, min.y, max.x - min.x, max.y - min.y))
+}
+
+/// Vérifie si un point est à l'intérieur d'un polygone
+fn polygon_contains(points: &[Vec2], p: Vec2) -> bool {
+    let mut inside = false;
+    let mut j = points.len() - 1;
+    for i in 0..points.len() {
+        let (a, b) = (points[i], points[j]);
+        if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::locale::Strings;
+    use crate::render::draw_world;
+    use crate::world::World;
+    use crate::Difficulty;
+
+    /// Compare une image à sa référence dans `snapshots/`
+    ///
+    /// Avec la variable `UPDATE_SNAPSHOTS=1`, la référence est réécrite.
+    fn assert_snapshot(name: &str, renderer: &PngRenderer) {
+        let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("snapshots")
+            .join(format!("{name}.png"));
+        if std::env::var("UPDATE_SNAPSHOTS").is_ok() {
+            renderer.save(path.to_str().unwrap()).unwrap();
+        }
+        let decoder = png::Decoder::new(File::open(&path).expect("image de référence absente"));
+        let mut reader = decoder.read_info().unwrap();
+        let mut expected = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut expected).unwrap();
+        assert!(
+            expected == renderer.pixels,
+            "{name} ne correspond pas à {}",
+            path.display()
+        );
+    }
+
+    /// Couleur d'un pixel
+    fn pixel(renderer: &PngRenderer, x: u32, y: u32) -> [u8; 4] {
+        let i = ((y * renderer.width + x) * 4) as usize;
+        renderer.pixels[i..i + 4].try_into().unwrap()
+    }
+
+    fn scene() -> World {
+        let mut rng = ::rand::thread_rng();
+        let mut world = World::new(0, Difficulty::Easy, vec2(320., 240.));
+        world.asteroids = vec![
+            Asteroid::new2(&mut rng, vec2(60., 60.), 50.),
+            Asteroid::new2(&mut rng, vec2(250., 70.), 25.),
+            Asteroid::new2(&mut rng, vec2(260., 180.), 15.),
+        ];
+        world.bullets = vec![Bullet::new(vec2(160., 80.), vec2(0., -4.), 0)];
+        world
+    }
+
+    #[test]
+    fn dessin_formes() {
+        let mut renderer = PngRenderer::new(vec2(20., 20.));
+        renderer.fill_circle(vec2(10., 10.), 3., RED);
+        renderer.fill_polygon(&[vec2(0., 0.), vec2(4., 0.), vec2(0., 4.)], WHITE);
+        assert_eq!(pixel(&renderer, 10, 10), [230, 41, 56, 255]);
+        assert_eq!(pixel(&renderer, 15, 15), [0, 0, 0, 0]);
+        assert_eq!(pixel(&renderer, 0, 0), [255, 255, 255, 255]);
+        assert_eq!(pixel(&renderer, 3, 3), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn jauge_remplie() {
+        let mut renderer = PngRenderer::new(vec2(20., 10.));
+        renderer.draw_gauge(Rect::new(0., 0., 20., 10.), 0.5, GREEN);
+
+        // La moitié gauche a la couleur de la jauge, la droite celle du fond
+        assert_eq!(pixel(&renderer, 5, 5), [0, 227, 48, 255]);
+        assert!(pixel(&renderer, 15, 5)[1] < 100);
+    }
+
+    #[test]
+    fn image_de_reference() {
+        let world = scene();
+        let mut renderer = PngRenderer::new(world.size);
+        draw_world(&mut renderer, &world, &Strings::default());
+        assert_snapshot("scene", &renderer);
+    }
+}
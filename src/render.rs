@@ -0,0 +1,463 @@
+//! Module gérant l'affichage de la partie.
+//!
+//! Deux modes de rendu sont disponibles :
+//! * `Sprites` - Les images du dossier `img` (mode par défaut)
+//! * `Vector` - Des contours lumineux façon borne d'arcade de 1979
+//!
+//! Le dessin passe par le trait `Renderer`, ce qui permet d'afficher une partie
+//! avec Macroquad ou avec un autre moteur (voir `text_renderer`) sans que le
+//! reste du jeu ait à connaître les textures.
+
+use std::f32::consts::PI;
+use std::str::FromStr;
+
+use macroquad::prelude::*;
+
+use crate::assets::Assets;
+use crate::asteroid::Asteroid;
+use crate::bullet::Bullet;
+use crate::hud::Hud;
+use crate::locale::Strings;
+use crate::spaceship::Spaceship;
+use crate::world::World;
+
+/// Taille de l'image du vaisseau, centrée sur sa position
+pub const SPACESHIP_SPRITE_SIZE: Vec2 = vec2(70.0, 60.0);
+
+/// Nombre de sommets du contour d'un astéroïde en mode vectoriel
+const ASTEROID_VERTICES: u32 = 11;
+
+/// Couleur des contours en mode vectoriel
+const VECTOR_COLOR: Color = Color::new(0.75, 0.95, 1.0, 1.0);
+
+/// Teinte du vaisseau de chaque joueur (le premier garde ses couleurs d'origine)
+const PLAYER_COLORS: [Color; 2] = [WHITE, Color::new(1.0, 0.75, 0.3, 1.0)];
+
+/// Durée d'une phase du clignotement d'un vaisseau invincible, en frames
+const BLINK_FRAMES: u64 = 6;
+
+/// Nombre de débris de l'explosion d'un vaisseau
+const EXPLOSION_DEBRIS: u32 = 10;
+
+/// Distance parcourue par les débris à la fin de l'explosion
+const EXPLOSION_RADIUS: f32 = 60.0;
+
+/// Couleur du fond d'une jauge du HUD
+pub const GAUGE_BACKGROUND: Color = Color::new(0.2, 0.2, 0.2, 0.8);
+
+/// Mode de rendu de la partie
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    #[default]
+    Sprites,
+    Vector,
+}
+
+impl RenderMode {
+    /// Nom du mode de rendu utilisé en ligne de commande et dans les fichiers
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderMode::Sprites => "sprites",
+            RenderMode::Vector => "vector",
+        }
+    }
+}
+
+impl FromStr for RenderMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "sprites" => Ok(RenderMode::Sprites),
+            "vector" => Ok(RenderMode::Vector),
+            _ => Err(format!("mode de rendu inconnu '{s}'")),
+        }
+    }
+}
+
+/// Interface commune aux moteurs de rendu
+///
+/// Un moteur de rendu sait dessiner chaque objet du jeu ; l'ordre des dessins
+/// et le placement de l'interface sont gérés par `draw_world`.
+///
+/// # Méthodes requises
+/// * `draw_background` - Efface l'image et dessine le fond
+/// * `draw_spaceship` - Dessine le vaisseau d'un joueur
+/// * `draw_asteroid` - Dessine un astéroïde
+/// * `draw_bullet` - Dessine un projectile
+/// * `draw_explosion` - Dessine l'explosion du vaisseau d'un joueur, `progress` allant de 0 à 1
+/// * `draw_text` - Dessine un texte de l'interface, `pos` étant le début de la ligne de base
+/// * `draw_gauge` - Dessine une jauge du HUD remplie selon `ratio` (de 0 à 1)
+/// * `measure_text` - Largeur d'un texte de l'interface, pour l'aligner à droite
+pub trait Renderer {
+    fn draw_background(&mut self);
+    fn draw_spaceship(&mut self, spaceship: &Spaceship, player: usize);
+    fn draw_asteroid(&mut self, asteroid: &Asteroid);
+    fn draw_bullet(&mut self, bullet: &Bullet);
+    fn draw_explosion(&mut self, center: Vec2, progress: f32, player: usize);
+    fn draw_text(&mut self, text: &str, pos: Vec2, font_size: u16, color: Color);
+    fn draw_gauge(&mut self, rect: Rect, ratio: f32, color: Color);
+    fn measure_text(&self, text: &str, font_size: u16) -> f32;
+}
+
+/// Fonction principale de rendu graphique : la scène puis le HUD
+///
+/// # Arguments
+/// * `renderer` - Moteur de rendu à utiliser
+/// * `world` - Partie en cours (vaisseau, astéroïdes, missiles)
+/// * `strings` - Textes de l'interface dans la langue du joueur
+pub fn draw_world(renderer: &mut impl Renderer, world: &World, strings: &Strings) {
+    draw_scene(renderer, world);
+    Hud::new(strings).draw(renderer, world);
+}
+
+/// Dessine le fond et les objets de la partie, sans le HUD
+pub fn draw_scene(renderer: &mut impl Renderer, world: &World) {
+    // affichage du fond d'écran
+    renderer.draw_background();
+
+    // affichage des vaisseaux en jeu, qui clignotent pendant leur invincibilité,
+    // et de l'explosion de ceux qui viennent d'être détruits
+    for (i, player) in world.players.iter().enumerate() {
+        if player.is_alive() {
+            if is_spaceship_visible(&player.spaceship, world.frame) {
+                renderer.draw_spaceship(&player.spaceship, i);
+            }
+        } else if let Some(progress) = player.explosion(world.frame) {
+            renderer.draw_explosion(player.spaceship.get_position(), progress, i);
+        }
+    }
+
+    // On affiche tous les asteroides en fonction de leur tailles
+    for a in &world.asteroids {
+        renderer.draw_asteroid(a);
+    }
+
+    for b in &world.bullets {
+        renderer.draw_bullet(b);
+    }
+}
+
+/// Vérifie si un vaisseau est dessiné à cette frame : un vaisseau invincible
+/// n'est visible qu'une phase de clignotement sur deux
+pub fn is_spaceship_visible(spaceship: &Spaceship, frame: u64) -> bool {
+    spaceship.get_invicibility() <= 0.0 || (frame / BLINK_FRAMES).is_multiple_of(2)
+}
+
+/// Couleur associée à un joueur
+pub fn player_color(player: usize) -> Color {
+    PLAYER_COLORS[player % PLAYER_COLORS.len()]
+}
+
+/// Dessine un message centré horizontalement dans la fenêtre
+///
+/// # Arguments
+/// * `text` - Message à afficher
+/// * `y` - Ligne de base du texte
+/// * `font_size` - Taille de la police
+/// * `color` - Couleur du texte
+/// * `font` - Police d'écriture, celle par défaut si absente
+pub fn draw_centered_text(text: &str, y: f32, font_size: u16, color: Color, font: Option<&Font>) {
+    let size = measure_text(text, font, font_size, 1.0);
+    draw_text_ex(
+        text,
+        screen_width() / 2. - size.width / 2.,
+        y,
+        TextParams {
+            font_size,
+            font,
+            color,
+            ..Default::default()
+        },
+    );
+}
+
+/// Dessine une image de fond couvrant toute la fenêtre
+///
+/// L'image garde ses proportions : elle est agrandie jusqu'à couvrir la
+/// fenêtre puis centrée, ses bords dépassant d'un côté si besoin.
+pub fn draw_cover(texture: &Texture2D) {
+    let (width, height) = (screen_width(), screen_height());
+    let size = texture.size();
+    let scale = (width / size.x).max(height / size.y);
+    let dest = size * scale;
+    draw_texture_ex(
+        texture,
+        (width - dest.x) / 2.,
+        (height - dest.y) / 2.,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(dest),
+            ..Default::default()
+        },
+    );
+}
+
+/// Moteur de rendu utilisant Macroquad (fenêtre OpenGL)
+///
+/// # Champs
+/// * `assets` - Ressources du jeu (fond, images, police)
+/// * `mode` - Mode de rendu
+pub struct MacroquadRenderer<'a> {
+    assets: &'a Assets,
+    mode: RenderMode,
+}
+
+impl<'a> MacroquadRenderer<'a> {
+    /// Crée un moteur de rendu Macroquad
+    pub fn new(assets: &'a Assets, mode: RenderMode) -> Self {
+        Self { assets, mode }
+    }
+}
+
+impl Renderer for MacroquadRenderer<'_> {
+    /// L'image de fond n'est utilisée qu'en mode `Sprites`, fond noir sinon.
+    fn draw_background(&mut self) {
+        match (self.mode, &self.assets.background) {
+            (RenderMode::Sprites, Some(background)) => draw_cover(background),
+            _ => clear_background(BLACK),
+        }
+    }
+
+    /// En mode `Sprites` sans image, un triangle est dessiné à la place.
+    fn draw_spaceship(&mut self, spaceship: &Spaceship, player: usize) {
+        let pos = spaceship.get_position();
+        let color = player_color(player);
+        match (self.mode, &self.assets.spaceship) {
+            (RenderMode::Sprites, Some(skin)) => {
+                let rect = spaceship_sprite_rect(spaceship);
+                draw_texture_ex(
+                    skin,
+                    rect.x,
+                    rect.y,
+                    color,
+                    DrawTextureParams {
+                        dest_size: Some(rect.size()),
+                        rotation: spaceship.get_orientation(),
+                        pivot: Some(pos),
+                        ..Default::default()
+                    },
+                );
+            }
+            (RenderMode::Sprites, None) => {
+                // Triangle pointant dans la direction du vaisseau
+                let points = spaceship_outline(spaceship);
+                draw_triangle(points[0], points[1], points[3], color);
+            }
+            (RenderMode::Vector, _) => {
+                let color = if player == 0 { VECTOR_COLOR } else { color };
+                draw_glowing_polygon(&spaceship_outline(spaceship), color)
+            }
+        }
+    }
+
+    /// En mode `Sprites` sans image, un polygone est dessiné à la place.
+    fn draw_asteroid(&mut self, asteroid: &Asteroid) {
+        match (self.mode, &self.assets.asteroid) {
+            (RenderMode::Sprites, Some(skin)) => {
+                // La taille et la position de l'image sont identiques à celles de la "hitbox"
+                let rect = asteroid_sprite_rect(asteroid);
+                draw_texture_ex(
+                    skin,
+                    rect.x,
+                    rect.y,
+                    WHITE,
+                    DrawTextureParams {
+                        dest_size: Some(rect.size()),
+                        ..Default::default()
+                    },
+                );
+            }
+            (RenderMode::Sprites, None) => {
+                let pos = asteroid.get_position();
+                draw_poly_lines(pos.x, pos.y, 9, asteroid.get_width(), 0., 2., GRAY);
+            }
+            (RenderMode::Vector, _) => {
+                draw_glowing_polygon(&asteroid_outline(asteroid), VECTOR_COLOR)
+            }
+        }
+    }
+
+    fn draw_bullet(&mut self, bullet: &Bullet) {
+        let pos = bullet.get_position();
+        match self.mode {
+            RenderMode::Sprites => draw_circle(pos.x, pos.y, bullet.get_size(), RED),
+            RenderMode::Vector => {
+                draw_circle(
+                    pos.x,
+                    pos.y,
+                    bullet.get_size(),
+                    with_alpha(VECTOR_COLOR, 0.2),
+                );
+                draw_circle(pos.x, pos.y, bullet.get_size() / 2., VECTOR_COLOR);
+            }
+        }
+    }
+
+    /// Un anneau qui s'élargit autour des débris, l'ensemble s'estompant
+    fn draw_explosion(&mut self, center: Vec2, progress: f32, player: usize) {
+        let color = match self.mode {
+            RenderMode::Vector if player == 0 => VECTOR_COLOR,
+            _ => player_color(player),
+        };
+        let color = with_alpha(color, 1. - progress);
+        let radius = EXPLOSION_RADIUS * progress;
+        draw_circle_lines(center.x, center.y, radius, 2., color);
+        for debris in explosion_debris(center, progress) {
+            draw_circle(debris.x, debris.y, 2., color);
+        }
+    }
+
+    fn draw_text(&mut self, text: &str, pos: Vec2, font_size: u16, color: Color) {
+        draw_text_ex(
+            text,
+            pos.x,
+            pos.y,
+            TextParams {
+                font_size,
+                font: self.assets.font(),
+                color,
+                ..Default::default()
+            },
+        );
+    }
+
+    fn draw_gauge(&mut self, rect: Rect, ratio: f32, color: Color) {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, GAUGE_BACKGROUND);
+        let fill = rect.w * ratio.clamp(0., 1.);
+        draw_rectangle(rect.x, rect.y, fill, rect.h, color);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., WHITE);
+    }
+
+    fn measure_text(&self, text: &str, font_size: u16) -> f32 {
+        measure_text(text, self.assets.font(), font_size, 1.0).width
+    }
+}
+
+/// Rectangle occupé par l'image du vaisseau avant rotation
+///
+/// L'image est centrée sur la position du vaisseau.
+pub fn spaceship_sprite_rect(spaceship: &Spaceship) -> Rect {
+    let top_left = spaceship.get_position() - SPACESHIP_SPRITE_SIZE / 2.;
+    Rect::new(
+        top_left.x,
+        top_left.y,
+        SPACESHIP_SPRITE_SIZE.x,
+        SPACESHIP_SPRITE_SIZE.y,
+    )
+}
+
+/// Rectangle occupé par l'image d'un astéroïde
+///
+/// L'image est un carré circonscrit au cercle de collision.
+pub fn asteroid_sprite_rect(asteroid: &Asteroid) -> Rect {
+    let pos = asteroid.get_position();
+    let radius = asteroid.get_width();
+    Rect::new(pos.x - radius, pos.y - radius, radius * 2., radius * 2.)
+}
+
+/// Contour du vaisseau façon arcade : pointe, aile droite, échancrure, aile gauche
+pub fn spaceship_outline(spaceship: &Spaceship) -> [Vec2; 4] {
+    let rotation = Vec2::from_angle(spaceship.get_orientation());
+    let height = spaceship.get_height();
+    [
+        vec2(0., -1.),
+        vec2(0.7, 0.8),
+        vec2(0., 0.45),
+        vec2(-0.7, 0.8),
+    ]
+    .map(|point| spaceship.get_position() + rotation.rotate(point * height))
+}
+
+/// Position des débris d'une explosion, projetés en étoile depuis le vaisseau
+///
+/// Les débris ne vont pas tous aussi loin, pour que l'explosion ne soit pas
+/// un simple cercle.
+pub fn explosion_debris(center: Vec2, progress: f32) -> Vec<Vec2> {
+    (0..EXPLOSION_DEBRIS)
+        .map(|i| {
+            let angle = 2. * PI * i as f32 / EXPLOSION_DEBRIS as f32;
+            let reach = if i % 2 == 0 { 1. } else { 0.7 };
+            center + Vec2::from_angle(angle) * EXPLOSION_RADIUS * reach * progress
+        })
+        .collect()
+}
+
+/// Contour irrégulier d'un astéroïde, toujours le même pour un astéroïde donné
+pub fn asteroid_outline(asteroid: &Asteroid) -> Vec<Vec2> {
+    (0..ASTEROID_VERTICES)
+        .map(|i| {
+            // Rayon entre 75% et 105% de la "hitbox" selon la forme de l'astéroïde
+            let hash = asteroid
+                .get_shape()
+                .wrapping_add(i)
+                .wrapping_mul(2_654_435_761);
+            let jitter = (hash >> 16) % 1000;
+            let radius = asteroid.get_width() * (0.75 + 0.3 * jitter as f32 / 1000.);
+            let angle = 2. * PI * i as f32 / ASTEROID_VERTICES as f32;
+            asteroid.get_position() + Vec2::from_angle(angle) * radius
+        })
+        .collect()
+}
+
+/// Dessine un polygone fermé avec un halo lumineux
+fn draw_glowing_polygon(points: &[Vec2], color: Color) {
+    for (thickness, alpha) in [(6., 0.12), (3., 0.3), (1.5, 1.)] {
+        let color = with_alpha(color, alpha);
+        for (i, a) in points.iter().enumerate() {
+            let b = points[(i + 1) % points.len()];
+            draw_line(a.x, a.y, b.x, b.y, thickness, color);
+        }
+    }
+}
+
+/// Change la transparence d'une couleur
+fn with_alpha(color: Color, alpha: f32) -> Color {
+    Color { a: alpha, ..color }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn image_asteroide_sur_hitbox() {
+        let asteroid = Asteroid::new2(&mut ::rand::thread_rng(), vec2(100., 80.), 25.);
+        let rect = asteroid_sprite_rect(&asteroid);
+        assert_eq!(rect, Rect::new(75., 55., 50., 50.));
+        assert_eq!(rect.center(), asteroid.get_position());
+    }
+
+    #[test]
+    fn clignotement_invincible() {
+        let mut spaceship = Spaceship::new(vec2(0., 0.));
+        assert!(is_spaceship_visible(&spaceship, BLINK_FRAMES));
+        spaceship.init_invicibility();
+        assert!(is_spaceship_visible(&spaceship, 0));
+        assert!(!is_spaceship_visible(&spaceship, BLINK_FRAMES));
+    }
+
+    #[test]
+    fn debris_projetes() {
+        let center = vec2(100., 100.);
+        assert!(explosion_debris(center, 0.).iter().all(|d| *d == center));
+        let distances: Vec<f32> = explosion_debris(center, 1.)
+            .iter()
+            .map(|d| d.distance(center))
+            .collect();
+        assert!(distances
+            .iter()
+            .all(|d| *d > 0. && *d <= EXPLOSION_RADIUS + 0.01));
+    }
+
+    #[test]
+    fn contour_vaisseau_oriente() {
+        let spaceship = Spaceship::new(vec2(0., 0.));
+        let nose = spaceship_outline(&spaceship)[0];
+        assert_eq!(nose, vec2(0., -spaceship.get_height()));
+        assert_eq!(
+            spaceship_sprite_rect(&spaceship).center(),
+            spaceship.get_position()
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Module gérant l'enregistrement et la relecture des parties.
+//!
+//! Comme la simulation est déterministe pour une graine donnée, un replay
+//! contient seulement les paramètres de la partie et les commandes de chaque
+//! frame. Les commandes identiques consécutives sont regroupées :
+//!
+//! ```text
+//! asteroid-replay 1
+//! seed 42
+//! difficulty medium
+//! size 800 600
+//! 120 ..U..
+//! 3 .RU.F
+//! ```
+
+use std::fs;
+
+use macroquad::prelude::*;
+
+use crate::config::parse_value;
+use crate::input::Input;
+use crate::Difficulty;
+
+/// En-tête identifiant un fichier de replay
+const REPLAY_HEADER: &str = "asteroid-replay 1";
+
+/// Nombre maximal de frames d'un replay (dix heures de jeu), pour qu'un
+/// fichier corrompu ne réserve pas une mémoire démesurée
+const MAX_FRAMES: usize = 10 * 60 * 60 * 60;
+
+/// Représente une partie enregistrée
+///
+/// # Champs
+/// * `seed` - Graine de la partie
+/// * `difficulty` - Difficulté de la partie
+/// * `size` - Dimensions de l'arène
+/// * `inputs` - Commandes de chaque frame
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub seed: u64,
+    pub difficulty: Difficulty,
+    pub size: Vec2,
+    pub inputs: Vec<Input>,
+}
+
+impl Replay {
+    /// Crée un replay vide
+    pub fn new(seed: u64, difficulty: Difficulty, size: Vec2) -> Self {
+        Self {
+            seed,
+            difficulty,
+            size,
+            inputs: Vec::new(),
+        }
+    }
+
+    /// Ajoute les commandes d'une frame
+    pub fn record(&mut self, input: Input) {
+        self.inputs.push(input);
+    }
+
+    /// Commandes de la frame donnée, aucune touche après la fin du replay
+    pub fn input(&self, frame: u64) -> Input {
+        self.inputs.get(frame as usize).copied().unwrap_or_default()
+    }
+
+    /// Charge un fichier de replay
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Impossible de lire '{path}' : {e}"))?;
+        Self::parse(&content).map_err(|e| format!("{path} : {e}"))
+    }
+
+    /// Enregistre le replay dans un fichier
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|e| format!("Impossible d'écrire '{path}' : {e}"))
+    }
+
+    /// Convertit le replay au format texte
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "{REPLAY_HEADER}\nseed {}\ndifficulty {}\nsize {} {}\n",
+            self.seed,
+            self.difficulty.code(),
+            self.size.x,
+            self.size.y
+        );
+        let mut i = 0;
+        while i < self.inputs.len() {
+            let count = self.inputs[i..]
+                .iter()
+                .take_while(|&&input| input == self.inputs[i])
+                .count();
+            text.push_str(&format!("{count} {}\n", self.inputs[i].encode()));
+            i += count;
+        }
+        text
+    }
+
+    /// Analyse un replay au format texte
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut lines = content.lines().map(str::trim).filter(|l| !l.is_empty());
+        if lines.next() != Some(REPLAY_HEADER) {
+            return Err("en-tête de replay manquant".to_string());
+        }
+
+        let mut field = |name: &str| -> Result<String, String> {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .map(|value| value.trim().to_string())
+                .ok_or_else(|| format!("champ '{name}' manquant"))
+        };
+        let seed = parse_value("seed", &field("seed")?)?;
+        let difficulty = field("difficulty")?.parse()?;
+        let size = field("size")?;
+        let (width, height) = size
+            .split_once(' ')
+            .ok_or_else(|| format!("taille invalide : '{size}'"))?;
+        let size = vec2(parse_value("size", width)?, parse_value("size", height)?);
+        if !size.is_finite() || size.x <= 0. || size.y <= 0. {
+            return Err(format!("taille invalide : '{width} {height}'"));
+        }
+
+        let mut replay = Replay::new(seed, difficulty, size);
+        for line in lines {
+            let (count, input) = line
+                .split_once(' ')
+                .ok_or_else(|| format!("ligne invalide : '{line}'"))?;
+            let count: usize = parse_value("count", count)?;
+            let input = Input::decode(input)?;
+            if count > MAX_FRAMES - replay.inputs.len() {
+                return Err(format!("replay trop long (plus de {MAX_FRAMES} frames)"));
+            }
+            replay.inputs.extend(std::iter::repeat_n(input, count));
+        }
+        Ok(replay)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn aller_retour_texte() {
+        let mut replay = Replay::new(3, Difficulty::Hard, vec2(640., 480.));
+        let up = Input {
+            up: true,
+            ..Default::default()
+        };
+        replay.record(up);
+        replay.record(up);
+        replay.record(Input::default());
+        let text = replay.to_text();
+        assert!(text.contains("2 ..U.."));
+        assert_eq!(Replay::parse(&text).unwrap(), replay);
+        assert!(Replay::parse("seed 3").is_err());
+
+        let header = "asteroid-replay 1\nseed 3\ndifficulty easy\n";
+        assert!(Replay::parse(&format!("{header}size 0 600")).is_err());
+        assert!(Replay::parse(&format!("{header}size -800 600")).is_err());
+        assert!(Replay::parse(&format!("{header}size NaN 600")).is_err());
+        assert!(Replay::parse(&format!("{header}size 800 600\n1000000000000 .....")).is_err());
+        assert!(Replay::parse(&format!(
+            "{header}size 800 600\n2000000 .....\n2000000 ....."
+        ))
+        .is_err());
+        assert!(Replay::parse(&format!("{header}size 800 600\n2000000 .....")).is_ok());
+    }
+}
@@ -0,0 +1,65 @@
+//! Module contenant le générateur aléatoire d'une partie.
+//!
+//! `GameRng` est un Xoshiro256++, le générateur qu'utilise `SmallRng` sur les
+//! plateformes 64 bits : une graine donne les mêmes parties qu'auparavant.
+//! Contrairement à `SmallRng`, il est le même sur toutes les plateformes et
+//! son état (quatre entiers de 64 bits) est enregistré tel quel dans les
+//! sauvegardes, qui reprennent ainsi exactement la suite des tirages.
+
+use ::rand::SeedableRng;
+pub use rand_xoshiro::Xoshiro256PlusPlus as GameRng;
+
+/// Crée le générateur d'une graine
+///
+/// L'état initial est tiré de la graine par PCG32, comme le fait
+/// `SmallRng::seed_from_u64` (et non `GameRng::seed_from_u64`, qui utilise
+/// SplitMix64), pour que les graines et les replays existants donnent les
+/// mêmes parties.
+pub fn from_seed(mut seed: u64) -> GameRng {
+    const MUL: u64 = 6364136223846793005;
+    const INC: u64 = 11634580027462260723;
+    let mut bytes = [0u8; 32];
+    for chunk in bytes.chunks_mut(4) {
+        seed = seed.wrapping_mul(MUL).wrapping_add(INC);
+        let xorshifted = (((seed >> 18) ^ seed) >> 27) as u32;
+        let value = xorshifted.rotate_right((seed >> 59) as u32);
+        chunk.copy_from_slice(&value.to_le_bytes());
+    }
+    GameRng::from_seed(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::rand::{Rng, RngCore};
+
+    /// Tire des nombres sur 32 et 64 bits, en alternance
+    fn mixed_draws(rng: &mut impl RngCore) -> Vec<u64> {
+        (0..20)
+            .map(|i| match i % 3 {
+                0 => rng.next_u32() as u64,
+                1 => rng.next_u64(),
+                _ => rng.gen_range(0..1000),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reprise_des_tirages() {
+        let mut rng = from_seed(42);
+        mixed_draws(&mut rng);
+        let json = serde_json::to_string(&rng).unwrap();
+        let mut restored: GameRng = serde_json::from_str(&json).unwrap();
+        assert_eq!(mixed_draws(&mut restored), mixed_draws(&mut rng));
+    }
+
+    #[cfg(target_pointer_width = "64")]
+    #[test]
+    fn memes_tirages_que_small_rng() {
+        use ::rand::rngs::SmallRng;
+        use ::rand::SeedableRng;
+        let mut rng = from_seed(7);
+        let mut small = SmallRng::seed_from_u64(7);
+        assert_eq!(mixed_draws(&mut rng), mixed_draws(&mut small));
+    }
+}
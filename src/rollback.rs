@@ -0,0 +1,739 @@
+//! Module gérant les parties en pair à pair avec retour en arrière (rollback).
+//!
+//! La simulation étant déterministe, chaque pair fait tourner la même partie
+//! et n'échange que les commandes de ses joueurs, à la manière de GGPO :
+//! * les commandes locales sont appliquées avec un retard de quelques frames
+//!   (`--input-delay`), ce qui laisse le temps à celles du pair d'arriver ;
+//! * quand une commande du pair manque, la précédente est répétée ;
+//! * quand elle arrive et diffère de celle qui avait été supposée, la partie
+//!   revient à l'état enregistré avant cette frame et les frames suivantes
+//!   sont simulées à nouveau ;
+//! * régulièrement, chaque pair envoie une somme de contrôle de l'état de la
+//!   partie à une frame dont toutes les commandes sont connues : si elles
+//!   diffèrent, les deux parties se sont désynchronisées.
+//!
+//! `Loopback` simule deux pairs dans le même processus, avec un réseau qui
+//! retarde, mélange et perd des paquets (`--loopback N`).
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::time::Instant;
+
+use ::rand::rngs::SmallRng;
+use ::rand::{Rng, SeedableRng};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::Assets;
+use crate::autopilot::Autopilot;
+use crate::cli::Options;
+use crate::effects::HitEffects;
+use crate::hud::Hud;
+use crate::input::{Controller, Input};
+use crate::locale::Strings;
+use crate::net::{self, Link, NetConditions, DEFAULT_PORT, JOIN_INTERVAL, TIMEOUT};
+use crate::render::{self, MacroquadRenderer};
+use crate::world::{World, FRAME_TIME};
+use crate::Difficulty;
+
+/// Retard par défaut des commandes locales, en frames
+pub const DEFAULT_INPUT_DELAY: u32 = 2;
+
+/// Nombre maximal de frames simulées sans connaître les commandes du pair
+pub const MAX_PREDICTION: u64 = 8;
+
+/// Nombre de frames entre deux sommes de contrôle
+const CHECKSUM_INTERVAL: u64 = 30;
+
+/// Nombre de sommes de contrôle répétées dans chaque paquet
+const SENT_CHECKSUMS: usize = 4;
+
+/// Paquet envoyé au pair à chaque frame
+///
+/// # Champs
+/// * `first` - Frame de la première commande du paquet
+/// * `inputs` - Commandes locales encodées, à partir de `first`, que le pair n'a pas confirmées
+/// * `ack` - Nombre de frames dont les commandes du pair ont été reçues
+/// * `checksums` - Dernières sommes de contrôle : frame et somme de l'état avant cette frame
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Packet {
+    pub first: u64,
+    pub inputs: Vec<String>,
+    pub ack: u64,
+    pub checksums: Vec<(u64, u64)>,
+}
+
+/// Compteurs d'une session
+///
+/// # Champs
+/// * `rollbacks` - Nombre de retours en arrière
+/// * `resimulated` - Nombre de frames simulées à nouveau
+/// * `stalls` - Nombre de frames attendues, trop en avance sur le pair
+/// * `checked` - Nombre de sommes de contrôle comparées à celles du pair
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub rollbacks: u64,
+    pub resimulated: u64,
+    pub stalls: u64,
+    pub checked: u64,
+}
+
+/// Partie synchronisée avec un pair
+///
+/// # Champs
+/// * `local` - Numéro du joueur local (0 ou 1), l'autre étant celui du pair
+/// * `frame` - Prochaine frame à simuler
+/// * `world` - Partie, éventuellement prédite, avant la frame `frame`
+/// * `inputs` - Commandes connues de chaque joueur, par frame
+/// * `received` - Nombre de frames dont les commandes de chaque joueur sont connues
+/// * `predictions` - Commandes du pair supposées pour les frames déjà simulées
+/// * `snapshots` - États enregistrés avant chaque frame non confirmée
+/// * `rollback_from` - Première frame à simuler à nouveau
+/// * `checksums` - Sommes de contrôle locales des états confirmés
+/// * `remote_checksums` - Sommes de contrôle reçues du pair, pas encore comparées
+/// * `next_checksum` - Prochaine frame dont l'état sera contrôlé
+/// * `checked_until` - Dernière frame dont la somme de contrôle a été comparée
+/// * `peer_ack` - Nombre de frames dont le pair a reçu les commandes locales
+/// * `desync` - Première frame où les deux parties ont divergé
+/// * `stats` - Compteurs de la session
+pub struct Session {
+    local: usize,
+    frame: u64,
+    world: World,
+    inputs: [BTreeMap<u64, Input>; 2],
+    received: [u64; 2],
+    predictions: BTreeMap<u64, Input>,
+    snapshots: BTreeMap<u64, World>,
+    rollback_from: Option<u64>,
+    checksums: BTreeMap<u64, u64>,
+    remote_checksums: BTreeMap<u64, u64>,
+    next_checksum: u64,
+    checked_until: u64,
+    peer_ack: u64,
+    desync: Option<u64>,
+    pub stats: Stats,
+}
+
+impl Session {
+    /// Crée une session à partir de la partie de départ, identique chez les deux pairs
+    ///
+    /// # Arguments
+    /// * `world` - Partie à deux joueurs
+    /// * `local` - Numéro du joueur local
+    /// * `input_delay` - Retard des commandes locales, le même chez les deux pairs
+    pub fn new(world: World, local: usize, input_delay: u32) -> Self {
+        let delay = input_delay as u64;
+        // Pendant les premières frames, aucune commande ne peut encore arriver
+        let idle: BTreeMap<u64, Input> = (0..delay).map(|f| (f, Input::default())).collect();
+        Self {
+            local,
+            frame: 0,
+            world,
+            inputs: [idle.clone(), idle],
+            received: [delay; 2],
+            predictions: BTreeMap::new(),
+            snapshots: BTreeMap::new(),
+            rollback_from: None,
+            checksums: BTreeMap::new(),
+            remote_checksums: BTreeMap::new(),
+            next_checksum: CHECKSUM_INTERVAL,
+            checked_until: 0,
+            peer_ack: delay,
+            desync: None,
+            stats: Stats::default(),
+        }
+    }
+
+    /// Numéro du joueur local
+    pub fn local(&self) -> usize {
+        self.local
+    }
+
+    /// Numéro du joueur du pair
+    fn remote(&self) -> usize {
+        1 - self.local
+    }
+
+    /// Prochaine frame à simuler
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Partie à afficher
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Première frame où les deux parties ont divergé, `None` si elles sont identiques
+    pub fn desync(&self) -> Option<u64> {
+        self.desync
+    }
+
+    /// Simule une frame avec la commande locale donnée
+    ///
+    /// Les frames à corriger depuis le dernier paquet reçu sont d'abord
+    /// simulées à nouveau.
+    ///
+    /// # Retourne
+    /// `false` si la partie attend le pair, trop en retard : la commande est ignorée
+    pub fn advance(&mut self, input: Input) -> bool {
+        if let Some(from) = self.rollback_from.take() {
+            self.world = self.snapshots[&from].clone();
+            self.stats.rollbacks += 1;
+            self.stats.resimulated += self.frame - from;
+            for frame in from..self.frame {
+                self.simulate(frame);
+            }
+        }
+
+        if self.frame >= self.received[self.remote()] + MAX_PREDICTION {
+            self.stats.stalls += 1;
+            return false;
+        }
+        let local = self.local;
+        self.inputs[local].insert(self.received[local], input);
+        self.received[local] += 1;
+        self.simulate(self.frame);
+        self.frame += 1;
+        self.confirm();
+        true
+    }
+
+    /// Enregistre l'état avant une frame puis la simule
+    fn simulate(&mut self, frame: u64) {
+        self.snapshots.insert(frame, self.world.clone());
+        let remote = self.remote();
+        let mut inputs = [Input::default(); 2];
+        inputs[self.local] = self.inputs[self.local][&frame];
+        inputs[remote] = match self.inputs[remote].get(&frame) {
+            Some(input) => {
+                self.predictions.remove(&frame);
+                *input
+            }
+            None => {
+                // La commande du pair manque : on suppose qu'il garde la précédente
+                let guess = self.inputs[remote]
+                    .get(&(self.received[remote].saturating_sub(1)))
+                    .copied()
+                    .unwrap_or_default();
+                self.predictions.insert(frame, guess);
+                guess
+            }
+        };
+        self.world.step_players(&inputs);
+    }
+
+    /// Calcule les sommes de contrôle des états confirmés et oublie ce qui
+    /// ne servira plus à revenir en arrière
+    fn confirm(&mut self) {
+        // Toutes les frames avant `confirmed` ont été simulées avec les vraies commandes
+        let confirmed = self.received[self.remote()].min(self.frame);
+        while self.next_checksum <= confirmed {
+            let state = match self.snapshots.get(&self.next_checksum) {
+                Some(state) => state,
+                None => &self.world,
+            };
+            self.checksums.insert(self.next_checksum, checksum(state));
+            self.next_checksum += CHECKSUM_INTERVAL;
+        }
+        self.compare_checksums();
+
+        self.snapshots = self.snapshots.split_off(&confirmed);
+        let remote = self.remote();
+        self.inputs[remote] = self.inputs[remote].split_off(&confirmed.saturating_sub(1));
+        let local = self.local;
+        self.inputs[local] = self.inputs[local].split_off(&confirmed.min(self.peer_ack));
+        while self.checksums.len() > SENT_CHECKSUMS * 4 {
+            self.checksums.pop_first();
+        }
+    }
+
+    /// Compare les sommes de contrôle reçues à celles calculées localement
+    fn compare_checksums(&mut self) {
+        let oldest = self.checksums.keys().next().copied().unwrap_or(0);
+        self.remote_checksums.retain(|frame, sum| {
+            match self.checksums.get(frame) {
+                Some(local) => {
+                    self.stats.checked += 1;
+                    self.checked_until = self.checked_until.max(*frame);
+                    if local != sum && self.desync.is_none() {
+                        self.desync = Some(*frame);
+                    }
+                    false
+                }
+                // Pas encore calculée localement, sauf si elle est trop ancienne
+                None => *frame > oldest,
+            }
+        });
+    }
+
+    /// Traite un paquet du pair
+    pub fn receive(&mut self, packet: &Packet) {
+        let remote = self.remote();
+        for (frame, text) in (packet.first..).zip(&packet.inputs) {
+            // Les commandes déjà reçues sont répétées ; après un trou, on attend
+            // le paquet suivant qui le comblera
+            if frame != self.received[remote] {
+                continue;
+            }
+            let Ok(input) = Input::decode(text) else {
+                break;
+            };
+            self.inputs[remote].insert(frame, input);
+            self.received[remote] += 1;
+            if self
+                .predictions
+                .remove(&frame)
+                .is_some_and(|guess| guess != input)
+            {
+                self.rollback_from = Some(self.rollback_from.map_or(frame, |f| f.min(frame)));
+            }
+        }
+        self.peer_ack = self.peer_ack.max(packet.ack);
+        // Les sommes déjà comparées sont répétées par le pair
+        let checked_until = self.checked_until;
+        self.remote_checksums.extend(
+            packet
+                .checksums
+                .iter()
+                .filter(|(frame, _)| *frame > checked_until)
+                .copied(),
+        );
+        self.compare_checksums();
+    }
+
+    /// Paquet à envoyer au pair
+    pub fn packet(&self) -> Packet {
+        let local = self.local;
+        Packet {
+            first: self.peer_ack,
+            inputs: (self.peer_ack..self.received[local])
+                .filter_map(|frame| self.inputs[local].get(&frame))
+                .map(Input::encode)
+                .collect(),
+            ack: self.received[self.remote()],
+            checksums: self
+                .checksums
+                .iter()
+                .rev()
+                .take(SENT_CHECKSUMS)
+                .map(|(frame, sum)| (*frame, *sum))
+                .collect(),
+        }
+    }
+}
+
+/// Somme de contrôle d'une partie (FNV-1a de sa sérialisation)
+///
+/// L'état du générateur aléatoire fait partie de la sérialisation : un pair
+/// ayant tiré un nombre de plus est détecté avant même que la partie change.
+pub fn checksum(world: &World) -> u64 {
+    let bytes = serde_json::to_vec(world).unwrap_or_default();
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Conditions du réseau simulé par `Loopback`, en frames
+///
+/// # Champs
+/// * `latency` - Retard de chaque paquet
+/// * `jitter` - Retard supplémentaire aléatoire maximal
+/// * `loss` - Proportion de paquets perdus, entre 0 et 1
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoopbackConditions {
+    pub latency: u64,
+    pub jitter: u64,
+    pub loss: f32,
+}
+
+impl LoopbackConditions {
+    /// Conditions données en millisecondes par `--latency`, `--jitter` et `--packet-loss`
+    pub fn from_options(options: &Options) -> Self {
+        let frames = |ms: u64| (ms as f32 / 1000. / FRAME_TIME).round() as u64;
+        Self {
+            latency: frames(options.latency),
+            jitter: frames(options.jitter),
+            loss: options.packet_loss / 100.,
+        }
+    }
+}
+
+/// Deux pairs pilotés automatiquement dans le même processus
+///
+/// # Champs
+/// * `peers` - Session de chaque pair
+/// * `pilots` - Pilote automatique de chaque joueur
+/// * `in_flight` - Paquets en route : frame d'arrivée, destinataire et contenu
+/// * `conditions` - Conditions du réseau simulé
+/// * `rng` - Tirage des pertes et des retards
+/// * `tick` - Nombre de frames écoulées
+pub struct Loopback {
+    pub peers: [Session; 2],
+    pilots: [Autopilot; 2],
+    in_flight: Vec<(u64, usize, Packet)>,
+    conditions: LoopbackConditions,
+    rng: SmallRng,
+    tick: u64,
+}
+
+impl Loopback {
+    /// Crée deux pairs sur la même partie
+    ///
+    /// # Arguments
+    /// * `world` - Crée la partie de départ de chaque pair
+    /// * `input_delay` - Retard des commandes locales
+    /// * `conditions` - Conditions du réseau simulé
+    /// * `seed` - Graine du tirage des pertes et des retards
+    pub fn new(
+        world: impl Fn(usize) -> World,
+        input_delay: u32,
+        conditions: LoopbackConditions,
+        seed: u64,
+    ) -> Self {
+        Self {
+            peers: [
+                Session::new(world(0), 0, input_delay),
+                Session::new(world(1), 1, input_delay),
+            ],
+            pilots: [Autopilot::new(); 2],
+            in_flight: Vec::new(),
+            conditions,
+            rng: SmallRng::seed_from_u64(seed),
+            tick: 0,
+        }
+    }
+
+    /// Avance d'une frame : livre les paquets arrivés, puis chaque pair joue
+    /// et envoie son paquet
+    pub fn step(&mut self) {
+        self.tick += 1;
+        let tick = self.tick;
+        let (arrived, in_flight) = std::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition::<Vec<_>, _>(|(at, _, _)| *at <= tick);
+        self.in_flight = in_flight;
+        for (_, to, packet) in arrived {
+            self.peers[to].receive(&packet);
+        }
+
+        for (i, peer) in self.peers.iter_mut().enumerate() {
+            let input = self.pilots[i].input(peer.world(), i);
+            peer.advance(input);
+            if self.rng.gen::<f32>() < self.conditions.loss {
+                continue;
+            }
+            let delay = self.conditions.latency + self.rng.gen_range(0..=self.conditions.jitter);
+            self.in_flight
+                .push((tick + delay.max(1), 1 - i, peer.packet()));
+        }
+    }
+
+    /// Avance de plusieurs frames, en s'arrêtant à la première désynchronisation
+    pub fn run(&mut self, frames: u64) {
+        for _ in 0..frames {
+            self.step();
+            if self.peers.iter().any(|peer| peer.desync().is_some()) {
+                break;
+            }
+        }
+    }
+}
+
+impl fmt::Display for Loopback {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "frames     : {}", self.tick)?;
+        for (i, peer) in self.peers.iter().enumerate() {
+            let stats = peer.stats;
+            writeln!(
+                f,
+                "pair {}     : frame {}, {} retours en arrière, {} frames resimulées, {} attentes, {} contrôles",
+                i + 1,
+                peer.frame(),
+                stats.rollbacks,
+                stats.resimulated,
+                stats.stalls,
+                stats.checked
+            )?;
+        }
+        match self.peers.iter().find_map(Session::desync) {
+            Some(frame) => write!(f, "résultat   : désynchronisation à la frame {frame}"),
+            None => write!(f, "résultat   : synchronisés"),
+        }
+    }
+}
+
+/// Simule deux pairs avec les options de lancement (`--loopback N`)
+pub fn loopback(options: &Options, frames: u64, seed: u64) -> Loopback {
+    let difficulty = options.difficulty.unwrap_or(Difficulty::Easy);
+    let size = vec2(options.width as f32, options.height as f32);
+    let mut harness = Loopback::new(
+        |_| World::new_versus(seed, difficulty, size),
+        options.input_delay,
+        LoopbackConditions::from_options(options),
+        seed,
+    );
+    harness.run(frames);
+    harness
+}
+
+/// Paramètres d'une partie en pair à pair, échangés avant de commencer
+///
+/// Chaque pair tire un nombre au hasard : celui qui a le plus petit joue le
+/// premier vaisseau et impose ses paramètres.
+///
+/// # Champs
+/// * `nonce` - Nombre tiré au hasard
+/// * `seed` - Graine de la partie
+/// * `difficulty` - Difficulté de la partie
+/// * `versus` - Duel, sinon coopération
+/// * `size` - Dimensions de l'arène
+/// * `input_delay` - Retard des commandes locales
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Hello {
+    nonce: u64,
+    seed: u64,
+    difficulty: Difficulty,
+    versus: bool,
+    #[serde(with = "crate::save::vec2")]
+    size: Vec2,
+    input_delay: u32,
+}
+
+impl Hello {
+    /// Partie de départ décrite par ces paramètres
+    fn world(&self) -> World {
+        if self.versus {
+            World::new_versus(self.seed, self.difficulty, self.size)
+        } else {
+            World::with_players(self.seed, self.difficulty, self.size, 2)
+        }
+    }
+}
+
+/// Messages échangés entre les pairs
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum PeerMessage {
+    Hello(Hello),
+    Inputs(Packet),
+}
+
+/// Prépare une partie en pair à pair
+///
+/// # Retourne
+/// La socket locale (`--port`) et l'adresse du pair (`--peer`)
+pub fn open(options: &Options, peer: &str) -> Result<(Link, SocketAddr), String> {
+    let addrs = if peer.contains(':') {
+        peer.to_socket_addrs()
+    } else {
+        (peer, DEFAULT_PORT).to_socket_addrs()
+    };
+    let peer = addrs
+        .map_err(|e| format!("adresse invalide '{peer}' ({e})"))?
+        .next()
+        .ok_or_else(|| format!("adresse introuvable '{peer}'"))?;
+    let local = SocketAddr::from(([0, 0, 0, 0], options.port));
+    Ok((
+        Link::bind(local, NetConditions::from_options(options))?,
+        peer,
+    ))
+}
+
+/// Boucle de jeu d'une partie en pair à pair
+///
+/// # Arguments
+/// * `options` - Options de lancement (paramètres proposés au pair, pilote)
+/// * `seed` - Graine proposée au pair
+/// * `link` - Socket locale
+/// * `peer` - Adresse du pair
+pub async fn peer_game(options: Options, seed: u64, mut link: Link, peer: SocketAddr) {
+    let assets = Assets::load(options.data_dir.as_deref());
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
+    assets.show_errors(&strings).await;
+    let font = assets.font();
+
+    let hello = Hello {
+        nonce: ::rand::random(),
+        seed,
+        difficulty: options.difficulty.unwrap_or(Difficulty::Easy),
+        versus: options.versus.is_some(),
+        size: vec2(options.width as f32, options.height as f32),
+        input_delay: options.input_delay,
+    };
+    let mut controller = net::local_controller(&options);
+    let mut session: Option<Session> = None;
+    let mut effects = HitEffects::default();
+    // Le pair a commencé : il a reçu nos paramètres
+    let mut peer_started = false;
+    let mut last_hello: Option<Instant> = None;
+    let mut last_seen = Instant::now();
+
+    loop {
+        if is_key_down(KeyCode::Escape) {
+            break;
+        }
+
+        for (from, message) in link.receive::<PeerMessage>() {
+            if from != peer {
+                continue;
+            }
+            last_seen = Instant::now();
+            match message {
+                PeerMessage::Hello(theirs) if session.is_none() => {
+                    let (config, local) = if hello.nonce < theirs.nonce {
+                        (hello, 0)
+                    } else {
+                        (theirs, 1)
+                    };
+                    request_new_screen_size(config.size.x, config.size.y);
+                    session = Some(Session::new(config.world(), local, config.input_delay));
+                }
+                PeerMessage::Inputs(packet) => {
+                    peer_started = true;
+                    if let Some(session) = &mut session {
+                        session.receive(&packet);
+                    }
+                }
+                PeerMessage::Hello(_) => {}
+            }
+        }
+        if !peer_started && last_hello.is_none_or(|last| last.elapsed() >= JOIN_INTERVAL) {
+            link.send(&PeerMessage::Hello(hello), peer);
+            last_hello = Some(Instant::now());
+        }
+
+        let Some(session) = &mut session else {
+            clear_background(BLACK);
+            let text = strings.format("net.waiting_peer", &[&peer]);
+            render::draw_centered_text(&text, screen_height() / 2., 30, WHITE, font);
+            next_frame().await;
+            continue;
+        };
+
+        let input = controller.input(session.world(), session.local());
+        session.advance(input);
+        link.send(&PeerMessage::Inputs(session.packet()), peer);
+
+        effects.update(get_frame_time());
+        effects.observe(session.world());
+        effects.draw(
+            &mut MacroquadRenderer::new(&assets, options.render_mode),
+            session.world(),
+            &Hud::new(&strings),
+        );
+        let escape = strings.get("net.escape");
+        net::draw_status(session.world(), None, font, Some(escape), &strings);
+        if let Some(frame) = session.desync() {
+            let text = strings.format("net.desync", &[&frame]);
+            render::draw_centered_text(&text, 40., 30, RED, font);
+        } else if last_seen.elapsed() > TIMEOUT {
+            let text = strings.get("net.peer_lost");
+            render::draw_centered_text(text, 40., 30, RED, font);
+        }
+
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rng::GameRng;
+    use crate::world::DEFAULT_ARENA;
+    use ::rand::RngCore;
+
+    #[test]
+    fn retour_en_arriere() {
+        let world = || World::new_versus(5, Difficulty::Easy, DEFAULT_ARENA);
+        let mut session = Session::new(world(), 0, 0);
+        let left = Input {
+            left: true,
+            ..Default::default()
+        };
+        // Le pair tourne dès la première frame, mais ses commandes arrivent en retard
+        for _ in 0..5 {
+            assert!(session.advance(Input::default()));
+        }
+        session.receive(&Packet {
+            first: 0,
+            inputs: vec![left.encode(); 5],
+            ack: 5,
+            checksums: Vec::new(),
+        });
+        session.advance(Input::default());
+        assert_eq!(session.stats.rollbacks, 1);
+        assert_eq!(session.stats.resimulated, 5);
+
+        // Même résultat que si les commandes étaient arrivées à temps
+        let mut expected = world();
+        for _ in 0..6 {
+            expected.step_players(&[Input::default(), left]);
+        }
+        assert_eq!(checksum(session.world()), checksum(&expected));
+
+        // Sans nouvelles du pair, la partie s'arrête après quelques frames d'avance
+        let mut advanced = 0;
+        while session.advance(Input::default()) {
+            advanced += 1;
+        }
+        assert_eq!(advanced, MAX_PREDICTION - 1);
+    }
+
+    #[test]
+    fn pairs_synchronises_malgre_la_gigue() {
+        let conditions = LoopbackConditions {
+            latency: 3,
+            jitter: 6,
+            loss: 0.1,
+        };
+        let mut harness = Loopback::new(
+            |_| World::new_versus(2, Difficulty::Medium, DEFAULT_ARENA),
+            DEFAULT_INPUT_DELAY,
+            conditions,
+            7,
+        );
+        harness.run(600);
+        for peer in &harness.peers {
+            assert_eq!(peer.desync(), None);
+            assert!(peer.stats.checked > 5);
+            assert!(peer.frame() > 500);
+        }
+        assert!(harness.peers.iter().any(|peer| peer.stats.rollbacks > 0));
+    }
+
+    #[test]
+    fn desynchronisation_detectee() {
+        // Les deux pairs ne partent pas de la même partie
+        let mut harness = Loopback::new(
+            |peer| World::new_versus(peer as u64, Difficulty::Easy, DEFAULT_ARENA),
+            DEFAULT_INPUT_DELAY,
+            LoopbackConditions::default(),
+            0,
+        );
+        harness.run(300);
+        assert_eq!(harness.peers[0].desync(), Some(CHECKSUM_INTERVAL));
+    }
+
+    #[test]
+    fn tirage_en_trop_detecte() {
+        // Le second pair a tiré un nombre aléatoire de plus, sans effet sur la partie
+        let world = |peer: usize| {
+            let world = World::new_versus(3, Difficulty::Easy, DEFAULT_ARENA);
+            let mut value = serde_json::to_value(&world).unwrap();
+            if peer == 1 {
+                let mut rng: GameRng = serde_json::from_value(value["rng"].take()).unwrap();
+                rng.next_u64();
+                value["rng"] = serde_json::to_value(rng).unwrap();
+            }
+            serde_json::from_value(value).unwrap()
+        };
+        assert_ne!(checksum(&world(0)), checksum(&world(1)));
+
+        let mut harness =
+            Loopback::new(world, DEFAULT_INPUT_DELAY, LoopbackConditions::default(), 0);
+        harness.run(300);
+        assert_eq!(harness.peers[0].desync(), Some(CHECKSUM_INTERVAL));
+    }
+}
@@ -0,0 +1,315 @@
+//! Module gérant la sauvegarde d'une partie en cours.
+//!
+//! Une sauvegarde est un fichier JSON versionné :
+//!
+//! ```text
+//! { "format": "asteroid-save", "version": 4, "world": { ..., "rng": { "s": [1, 2, 3, 4] } } }
+//! ```
+//!
+//! Lorsque le format change, `SAVE_VERSION` est incrémentée et une migration
+//! est ajoutée à `MIGRATIONS` : les anciennes sauvegardes sont converties à la
+//! lecture, étape par étape.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ::rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::paths::user_data_dir;
+use crate::rng;
+use crate::world::World;
+
+/// Identifiant du format de sauvegarde
+const SAVE_FORMAT: &str = "asteroid-save";
+
+/// Version actuelle du format de sauvegarde
+pub const SAVE_VERSION: u32 = 4;
+
+/// Nom du fichier de sauvegarde par défaut
+const SAVE_FILE: &str = "save.json";
+
+/// Migrations du format : l'élément `i` convertit la version `i + 1` en `i + 2`
+const MIGRATIONS: &[fn(&mut Value)] = &[migrate_v1_players, migrate_v2_rng, migrate_v3_rng_state];
+
+/// Nombre maximal de tirages rejoués pour convertir une sauvegarde de version 3
+/// (environ une seconde) ; au-delà, le fichier est considéré comme corrompu
+const MAX_MIGRATED_DRAWS: u64 = 1 << 30;
+
+/// Contenu d'un fichier de sauvegarde
+///
+/// # Champs
+/// * `format` - Identifiant du format (`asteroid-save`)
+/// * `version` - Version du format
+/// * `world` - État de la partie, générateur aléatoire compris
+#[derive(Serialize, Deserialize)]
+struct SaveFile<W> {
+    format: String,
+    version: u32,
+    world: W,
+}
+
+/// Chemin de la sauvegarde par défaut, dans le dossier de données de l'utilisateur
+pub fn default_save_path() -> PathBuf {
+    user_data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(SAVE_FILE)
+}
+
+/// Convertit une partie en JSON
+pub fn to_json(world: &World) -> Result<String, String> {
+    let save = SaveFile {
+        format: SAVE_FORMAT.to_string(),
+        version: SAVE_VERSION,
+        world,
+    };
+    serde_json::to_string_pretty(&save).map_err(|e| e.to_string())
+}
+
+/// Lit une partie depuis du JSON, en migrant les anciennes versions
+pub fn from_json(text: &str) -> Result<World, String> {
+    let mut value: Value =
+        serde_json::from_str(text).map_err(|e| format!("fichier corrompu ({e})"))?;
+
+    if value.get("format").and_then(Value::as_str) != Some(SAVE_FORMAT) {
+        return Err("ce fichier n'est pas une sauvegarde".to_string());
+    }
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or("version de sauvegarde absente")? as u32;
+    if version == 0 || version > SAVE_VERSION {
+        return Err(format!(
+            "version de sauvegarde {version} non gérée (version actuelle : {SAVE_VERSION})"
+        ));
+    }
+
+    // Application des migrations depuis la version du fichier
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        migration(&mut value);
+    }
+    value["version"] = SAVE_VERSION.into();
+
+    let save: SaveFile<World> =
+        serde_json::from_value(value).map_err(|e| format!("sauvegarde invalide ({e})"))?;
+    let world = save.world;
+    if world.size.x <= 0. || world.size.y <= 0. {
+        return Err("sauvegarde invalide (arène vide)".to_string());
+    }
+    Ok(world)
+}
+
+/// Version 2 : le vaisseau, le score et le dernier tir passent dans la liste
+/// des joueurs, et chaque missile connaît le joueur qui l'a tiré
+fn migrate_v1_players(save: &mut Value) {
+    let Some(world) = save.get_mut("world").and_then(Value::as_object_mut) else {
+        return;
+    };
+    let player = serde_json::json!({
+        "spaceship": world.remove("spaceship"),
+        "score": world.remove("score"),
+        "last_shot": world.remove("last_shot"),
+        "respawn_at": null,
+    });
+    world.insert("players".to_string(), Value::Array(vec![player]));
+    world.insert("friendly_fire".to_string(), Value::Bool(false));
+    if let Some(bullets) = world.get_mut("bullets").and_then(Value::as_array_mut) {
+        for bullet in bullets.iter_mut().filter_map(Value::as_object_mut) {
+            bullet.insert("owner".to_string(), 0.into());
+        }
+    }
+}
+
+/// Version 3 : l'état du générateur aléatoire est enregistré avec la partie,
+/// à la place d'une graine tirée au moment de la sauvegarde
+fn migrate_v2_rng(save: &mut Value) {
+    let Some(save) = save.as_object_mut() else {
+        return;
+    };
+    let seed = save.remove("rng_seed").unwrap_or(0.into());
+    if let Some(world) = save.get_mut("world").and_then(Value::as_object_mut) {
+        world.insert(
+            "rng".to_string(),
+            serde_json::json!({ "seed": seed, "draws": 0 }),
+        );
+    }
+}
+
+/// Version 4 : l'état complet du générateur remplace sa graine et son nombre
+/// de tirages, qui obligeaient à rejouer tous les tirages à la lecture
+///
+/// Les sauvegardes de version 3 comptaient les tirages de `SmallRng`, qui sur
+/// les plateformes 64 bits avance son état d'un pas par tirage, sur 32 comme
+/// sur 64 bits : rejouer autant de tirages sur 64 bits retrouve son état. Un
+/// nombre de tirages invalide ou trop grand est laissé tel quel, et la lecture
+/// de la partie échoue.
+fn migrate_v3_rng_state(save: &mut Value) {
+    let Some(rng) = save.pointer_mut("/world/rng") else {
+        return;
+    };
+    let seed = rng.get("seed").and_then(Value::as_u64);
+    let draws = rng
+        .get("draws")
+        .and_then(Value::as_u64)
+        .filter(|draws| *draws <= MAX_MIGRATED_DRAWS);
+    let (Some(seed), Some(draws)) = (seed, draws) else {
+        return;
+    };
+    let mut generator = rng::from_seed(seed);
+    for _ in 0..draws {
+        generator.next_u64();
+    }
+    if let Ok(state) = serde_json::to_value(generator) {
+        *rng = state;
+    }
+}
+
+/// Enregistre une partie dans un fichier
+///
+/// Le fichier est d'abord écrit à côté puis renommé, pour ne jamais laisser
+/// une sauvegarde à moitié écrite.
+pub fn save(world: &World, path: &Path) -> Result<(), String> {
+    let error = |e: std::io::Error| format!("Impossible d'écrire '{}' : {e}", path.display());
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(error)?;
+    }
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, to_json(world)?).map_err(error)?;
+    fs::rename(&tmp, path).map_err(error)
+}
+
+/// Charge une partie depuis un fichier
+pub fn load(path: &Path) -> Result<World, String> {
+    let text = fs::read_to_string(path)
+        .map_err(|e| format!("Impossible de lire '{}' : {e}", path.display()))?;
+    from_json(&text).map_err(|e| format!("{} : {e}", path.display()))
+}
+
+/// Sérialisation d'un `Vec2` sous forme de tableau `[x, y]`
+///
+/// À utiliser avec `#[serde(with = "crate::save::vec2")]`.
+pub mod vec2 {
+    use macroquad::prelude::Vec2;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer>(v: &Vec2, serializer: S) -> Result<S::Ok, S::Error> {
+        [v.x, v.y].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec2, D::Error> {
+        let [x, y] = <[f32; 2]>::deserialize(deserializer)?;
+        Ok(Vec2::new(x, y))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::Input;
+    use crate::world::DEFAULT_ARENA;
+    use crate::Difficulty;
+
+    #[test]
+    fn reprise_identique() {
+        let input = Input {
+            left: true,
+            up: true,
+            fire: true,
+            ..Default::default()
+        };
+        let mut world = World::new(9, Difficulty::Medium, DEFAULT_ARENA);
+        for _ in 0..200 {
+            world.step(&input);
+        }
+
+        // Une partie rechargée continue exactement comme l'originale
+        let json = to_json(&world).unwrap();
+        let mut loaded = from_json(&json).unwrap();
+        for _ in 0..600 {
+            world.step(&input);
+            loaded.step(&input);
+        }
+        assert_eq!(loaded.frame, world.frame);
+        assert_eq!(loaded.score(), world.score());
+        assert_eq!(loaded.wave, world.wave);
+        assert_eq!(loaded.asteroids.len(), world.asteroids.len());
+        assert_eq!(
+            loaded.spaceship().get_position(),
+            world.spaceship().get_position()
+        );
+    }
+
+    #[test]
+    fn migration_version_1() {
+        let v1 = r#"{
+            "format": "asteroid-save",
+            "version": 1,
+            "rng_seed": 7,
+            "world": {
+                "spaceship": {
+                    "position": [100.0, 200.0],
+                    "speed": [0.0, -0.7],
+                    "orientation": 0.0,
+                    "shield": 4,
+                    "invincibility_timer": 0.0
+                },
+                "asteroids": [],
+                "bullets": [{"position": [10.0, 10.0], "speed": [0.0, -4.0], "touched": false}],
+                "size": [800.0, 600.0],
+                "difficulty": "Hard",
+                "sandbox": false,
+                "score": 320,
+                "wave": 2,
+                "frame": 900,
+                "state": "Running",
+                "last_shot": 880
+            }
+        }"#;
+        let world = from_json(v1).unwrap();
+        assert_eq!(world.players.len(), 1);
+        assert_eq!(world.score(), 320);
+        assert_eq!(world.spaceship().get_shield(), 4);
+        assert_eq!(world.bullets[0].get_owner(), 0);
+        assert!(!world.can_fire(0));
+    }
+
+    #[test]
+    fn migration_version_3() {
+        let mut save = serde_json::json!({ "world": { "rng": { "seed": 77, "draws": 5 } } });
+        migrate_v3_rng_state(&mut save);
+        let mut expected = rng::from_seed(77);
+        for _ in 0..5 {
+            expected.next_u64();
+        }
+        assert_eq!(
+            save["world"]["rng"],
+            serde_json::to_value(expected).unwrap()
+        );
+
+        // Un nombre de tirages démesuré n'est pas rejoué : la sauvegarde est refusée
+        let mut world =
+            serde_json::to_value(World::new(1, Difficulty::Easy, DEFAULT_ARENA)).unwrap();
+        world["rng"] = serde_json::json!({ "seed": 77, "draws": u64::MAX });
+        let v3 = serde_json::json!({ "format": "asteroid-save", "version": 3, "world": world });
+        assert!(error(&v3.to_string()).contains("invalide"));
+    }
+
+    /// Message d'erreur de la lecture d'une sauvegarde invalide
+    fn error(text: &str) -> String {
+        from_json(text)
+            .err()
+            .expect("la sauvegarde aurait dû être refusée")
+    }
+
+    #[test]
+    fn fichiers_invalides() {
+        assert!(error("{ pas du json").contains("corrompu"));
+        assert!(error(r#"{"format": "autre"}"#).contains("pas une sauvegarde"));
+        assert!(error(r#"{"format": "asteroid-save", "version": 99}"#).contains("99"));
+        assert!(
+            error(r#"{"format": "asteroid-save", "version": 1, "rng_seed": 0}"#)
+                .contains("invalide")
+        );
+    }
+}
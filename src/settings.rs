@@ -0,0 +1,287 @@
+//! Module gérant les préférences du joueur.
+//!
+//! Les préférences sont choisies dans l'écran des paramètres du menu puis
+//! enregistrées dans le dossier de configuration de l'utilisateur, au même
+//! format `clé = valeur` que le fichier de configuration (`--config`) :
+//!
+//! ```text
+//! master_volume = 80
+//! sfx_volume = 100
+//! music_volume = 60
+//! fullscreen = false
+//! width = 1280
+//! height = 720
+//! vsync = true
+//! render_mode = vector
+//! language = fr
+//! controls = arrows
+//! ```
+//!
+//! Elles sont lues au démarrage et servent de base aux options de la fenêtre :
+//! le fichier de configuration et la ligne de commande restent prioritaires.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use macroquad::window::{request_new_screen_size, set_fullscreen};
+
+use crate::config::{parse_value, split_entry};
+use crate::input::{KeyBindings, ARROW_KEYS, WASD_KEYS};
+use crate::locale::Strings;
+use crate::paths::user_config_dir;
+use crate::render::RenderMode;
+use crate::world::DEFAULT_ARENA;
+
+/// Nom du fichier des préférences
+const SETTINGS_FILE: &str = "settings.cfg";
+
+/// Volume maximal, en pourcentage
+pub const MAX_VOLUME: u32 = 100;
+
+/// Résolutions de fenêtre proposées par l'écran des paramètres
+pub const RESOLUTIONS: [(u32, u32); 4] = [(800, 600), (1024, 768), (1280, 720), (1920, 1080)];
+
+/// Langue de l'interface
+///
+/// # Variantes
+/// * `French` - Français
+/// * `English` - Anglais
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    French,
+    English,
+}
+
+impl Language {
+    /// Code de la langue utilisé dans les fichiers
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::French => "fr",
+            Language::English => "en",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "fr" | "français" => Ok(Language::French),
+            "en" | "english" => Ok(Language::English),
+            _ => Err(format!("langue inconnue '{s}'")),
+        }
+    }
+}
+
+/// Touches des deux joueurs au clavier
+///
+/// # Variantes
+/// * `Arrows` - Le premier joueur joue aux flèches, le second en WASD
+/// * `Wasd` - Le premier joueur joue en WASD, le second aux flèches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlScheme {
+    #[default]
+    Arrows,
+    Wasd,
+}
+
+impl ControlScheme {
+    /// Nom du schéma de touches utilisé dans les fichiers
+    pub fn name(&self) -> &'static str {
+        match self {
+            ControlScheme::Arrows => "arrows",
+            ControlScheme::Wasd => "wasd",
+        }
+    }
+
+    /// Touches du premier puis du second joueur
+    pub fn bindings(&self) -> [KeyBindings; 2] {
+        match self {
+            ControlScheme::Arrows => [ARROW_KEYS, WASD_KEYS],
+            ControlScheme::Wasd => [WASD_KEYS, ARROW_KEYS],
+        }
+    }
+}
+
+impl FromStr for ControlScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "arrows" => Ok(ControlScheme::Arrows),
+            "wasd" => Ok(ControlScheme::Wasd),
+            _ => Err(format!("schéma de touches inconnu '{s}'")),
+        }
+    }
+}
+
+/// Préférences du joueur
+///
+/// # Champs
+/// * `master_volume` / `sfx_volume` / `music_volume` - Volumes général, des effets et de la musique, en pourcentage
+/// * `fullscreen` - Jouer en plein écran
+/// * `width` / `height` - Taille de la fenêtre
+/// * `vsync` - Synchronisation verticale, appliquée au prochain lancement
+/// * `render_mode` - Mode de rendu (images ou contours vectoriels)
+/// * `language` - Langue de l'interface
+/// * `controls` - Touches des deux joueurs
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub master_volume: u32,
+    pub sfx_volume: u32,
+    pub music_volume: u32,
+    pub fullscreen: bool,
+    pub width: u32,
+    pub height: u32,
+    pub vsync: bool,
+    pub render_mode: RenderMode,
+    pub language: Language,
+    pub controls: ControlScheme,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: MAX_VOLUME,
+            sfx_volume: MAX_VOLUME,
+            music_volume: MAX_VOLUME,
+            fullscreen: false,
+            width: DEFAULT_ARENA.x as u32,
+            height: DEFAULT_ARENA.y as u32,
+            vsync: true,
+            render_mode: RenderMode::Sprites,
+            language: Language::French,
+            controls: ControlScheme::Arrows,
+        }
+    }
+}
+
+impl Settings {
+    /// Analyse le contenu d'un fichier de préférences
+    ///
+    /// Les préférences absentes gardent leur valeur par défaut.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut settings = Settings::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) =
+                split_entry(line).ok_or_else(|| format!("ligne {} : '=' attendu", number + 1))?;
+            let error = |e: String| format!("ligne {} : {e}", number + 1);
+            let volume = |value: &str| {
+                parse_value::<u32>(key, value)
+                    .map(|volume| volume.min(MAX_VOLUME))
+                    .map_err(error)
+            };
+            match key {
+                "master_volume" => settings.master_volume = volume(value)?,
+                "sfx_volume" => settings.sfx_volume = volume(value)?,
+                "music_volume" => settings.music_volume = volume(value)?,
+                "fullscreen" => settings.fullscreen = parse_value(key, value).map_err(error)?,
+                "width" => settings.width = parse_value(key, value).map_err(error)?,
+                "height" => settings.height = parse_value(key, value).map_err(error)?,
+                "vsync" => settings.vsync = parse_value(key, value).map_err(error)?,
+                "render_mode" => settings.render_mode = value.parse().map_err(error)?,
+                "language" => settings.language = value.parse().map_err(error)?,
+                "controls" => settings.controls = value.parse().map_err(error)?,
+                _ => return Err(error(format!("clé inconnue '{key}'"))),
+            }
+        }
+        if settings.width == 0 || settings.height == 0 {
+            return Err("la taille de la fenêtre doit être positive".to_string());
+        }
+        Ok(settings)
+    }
+
+    /// Charge les préférences d'un fichier
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Impossible de lire '{}' : {e}", path.display()))?;
+        Self::parse(&content).map_err(|e| format!("{} : {e}", path.display()))
+    }
+
+    /// Charge les préférences de l'utilisateur, ou celles par défaut s'il n'en
+    /// a pas encore enregistré ou si le fichier est invalide
+    pub fn load_or_default(path: &Path) -> Self {
+        if !path.exists() {
+            return Self::default();
+        }
+        // La langue étant lue dans ce fichier, l'erreur est signalée en français
+        Self::load(path).unwrap_or_else(|e| {
+            eprintln!("{}", Strings::default().format("error", &[&e]));
+            Self::default()
+        })
+    }
+
+    /// Enregistre les préférences dans un fichier
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let error = |e: std::io::Error| format!("Impossible d'écrire '{}' : {e}", path.display());
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(error)?;
+        }
+        fs::write(path, self.to_string()).map_err(error)
+    }
+
+    /// Applique le plein écran et la taille de la fenêtre pendant le jeu
+    pub fn apply_window(&self) {
+        set_fullscreen(self.fullscreen);
+        if !self.fullscreen {
+            request_new_screen_size(self.width as f32, self.height as f32);
+        }
+    }
+}
+
+impl fmt::Display for Settings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "master_volume = {}", self.master_volume)?;
+        writeln!(f, "sfx_volume = {}", self.sfx_volume)?;
+        writeln!(f, "music_volume = {}", self.music_volume)?;
+        writeln!(f, "fullscreen = {}", self.fullscreen)?;
+        writeln!(f, "width = {}", self.width)?;
+        writeln!(f, "height = {}", self.height)?;
+        writeln!(f, "vsync = {}", self.vsync)?;
+        writeln!(f, "render_mode = {}", self.render_mode.name())?;
+        writeln!(f, "language = {}", self.language.name())?;
+        writeln!(f, "controls = {}", self.controls.name())
+    }
+}
+
+/// Chemin du fichier des préférences, dans le dossier de configuration de l'utilisateur
+pub fn default_settings_path() -> PathBuf {
+    user_config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(SETTINGS_FILE)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lecture_des_preferences() {
+        let settings = Settings {
+            master_volume: 40,
+            fullscreen: true,
+            width: 1280,
+            height: 720,
+            render_mode: RenderMode::Vector,
+            language: Language::English,
+            controls: ControlScheme::Wasd,
+            ..Default::default()
+        };
+        assert_eq!(Settings::parse(&settings.to_string()), Ok(settings));
+
+        let settings = Settings::parse("# volumes\nsfx_volume = 250\nvsync = false").unwrap();
+        assert_eq!(settings.sfx_volume, MAX_VOLUME);
+        assert!(!settings.vsync);
+        assert_eq!(settings.width, 800);
+        assert!(Settings::parse("language = de").is_err());
+        assert!(Settings::parse("width = 0").is_err());
+    }
+}
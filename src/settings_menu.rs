@@ -0,0 +1,344 @@
+//! Module représentant l'écran des paramètres
+//!
+//! Ce module contient la structure `SettingsMenu`, ouverte depuis le menu
+//! principal. Elle modifie une copie des préférences du joueur (voir le
+//! module `settings`), enregistrée seulement quand il valide.
+
+use crate::button::Button;
+use crate::locale::Strings;
+use crate::render::RenderMode;
+use crate::settings::{ControlScheme, Language, Settings, MAX_VOLUME, RESOLUTIONS};
+use crate::ui::{
+    Align, Column, Dropdown, Focus, Label, NavAction, Navigation, Slider, Toggle, Viewport,
+};
+use macroquad::prelude::*;
+
+/// Hauteur réservée au titre au-dessus des widgets
+const TITLE_HEIGHT: f32 = 70.0;
+
+/// Pas des curseurs de volume, en pourcentage
+const VOLUME_STEP: f32 = 5.0;
+
+/// Modes de rendu proposés, dans l'ordre de la liste
+const RENDER_MODES: [RenderMode; 2] = [RenderMode::Sprites, RenderMode::Vector];
+
+/// Langues proposées, dans l'ordre de la liste
+const LANGUAGES: [Language; 2] = [Language::French, Language::English];
+
+/// Schémas de touches proposés, dans l'ordre de la liste
+const CONTROL_SCHEMES: [ControlScheme; 2] = [ControlScheme::Arrows, ControlScheme::Wasd];
+
+/// Position d'une valeur dans les choix d'une liste, le premier choix à défaut
+fn position<T: PartialEq>(choices: &[T], value: T) -> usize {
+    choices.iter().position(|c| *c == value).unwrap_or(0)
+}
+
+/// Éléments de l'écran pouvant recevoir le focus, de haut en bas
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item {
+    MasterVolume,
+    SfxVolume,
+    MusicVolume,
+    Fullscreen,
+    Resolution,
+    Vsync,
+    RenderMode,
+    Language,
+    Controls,
+    Save,
+    Back,
+}
+
+/// Ordre de parcours des éléments au clavier et à la manette
+const ITEMS: [Item; 11] = [
+    Item::MasterVolume,
+    Item::SfxVolume,
+    Item::MusicVolume,
+    Item::Fullscreen,
+    Item::Resolution,
+    Item::Vsync,
+    Item::RenderMode,
+    Item::Language,
+    Item::Controls,
+    Item::Save,
+    Item::Back,
+];
+
+/// Représente l'écran des paramètres
+///
+/// # Champs
+/// * `title` - Titre de l'écran
+/// * `master_volume` / `sfx_volume` / `music_volume` - Curseurs des volumes
+/// * `fullscreen` - Interrupteur du plein écran
+/// * `resolutions` - Tailles de fenêtre proposées par `resolution`
+/// * `resolution` - Liste des tailles de fenêtre
+/// * `vsync` - Interrupteur de la synchronisation verticale
+/// * `render_mode` - Liste des modes de rendu
+/// * `language` - Liste des langues
+/// * `controls` - Liste des schémas de touches
+/// * `labels` - Noms des listes, à leur gauche
+/// * `btn_save` - Bouton pour enregistrer et revenir au menu
+/// * `btn_back` - Bouton pour revenir au menu sans enregistrer
+/// * `message` - Erreur de la dernière tentative d'enregistrement
+/// * `focus` - Élément ayant le focus du clavier et de la manette
+/// * `nav` - Actions de navigation de la frame en cours
+/// * `viewport` - Taille de la fenêtre pour laquelle les widgets sont placés
+pub struct SettingsMenu {
+    title: Label,
+    master_volume: Slider,
+    sfx_volume: Slider,
+    music_volume: Slider,
+    fullscreen: Toggle,
+    resolutions: Vec<(u32, u32)>,
+    resolution: Dropdown,
+    vsync: Toggle,
+    render_mode: Dropdown,
+    language: Dropdown,
+    controls: Dropdown,
+    labels: Vec<Label>,
+    btn_save: Button,
+    btn_back: Button,
+    message: Label,
+    focus: Focus<Item>,
+    nav: Navigation,
+    viewport: Viewport,
+}
+
+impl SettingsMenu {
+    /// Crée l'écran des paramètres affichant les préférences données
+    ///
+    /// # Arguments
+    /// * `settings` - Préférences actuelles du joueur
+    /// * `strings` - Textes de l'interface dans la langue du joueur
+    pub fn new(settings: &Settings, strings: &Strings) -> Self {
+        let rect = Rect::default();
+        let text = |key| strings.get(key);
+        let volume = |label: &str, value: u32| {
+            Slider::new(
+                rect,
+                label,
+                0.,
+                MAX_VOLUME as f32,
+                VOLUME_STEP,
+                value as f32,
+            )
+        };
+
+        // La taille actuelle est ajoutée à la liste si elle n'y est pas
+        let mut resolutions = RESOLUTIONS.to_vec();
+        let size = (settings.width, settings.height);
+        if !resolutions.contains(&size) {
+            resolutions.push(size);
+        }
+        let names: Vec<String> = resolutions
+            .iter()
+            .map(|(width, height)| format!("{width} x {height}"))
+            .collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+        let selected = position(&resolutions, size);
+
+        let label = |key| Label::new(rect, text(key)).with_align(Align::Left);
+        let mut menu = Self {
+            title: Label::new(rect, text("settings.title")).with_size(50),
+            master_volume: volume(text("settings.master_volume"), settings.master_volume),
+            sfx_volume: volume(text("settings.sfx_volume"), settings.sfx_volume),
+            music_volume: volume(text("settings.music_volume"), settings.music_volume),
+            fullscreen: Toggle::new(rect, text("settings.fullscreen"), settings.fullscreen),
+            resolution: Dropdown::new(rect, &names, selected),
+            resolutions,
+            vsync: Toggle::new(rect, text("settings.vsync"), settings.vsync),
+            render_mode: Dropdown::new(
+                rect,
+                &[
+                    text("settings.render.sprites"),
+                    text("settings.render.vector"),
+                ],
+                position(&RENDER_MODES, settings.render_mode),
+            ),
+            language: Dropdown::new(
+                rect,
+                &["Français", "English"],
+                position(&LANGUAGES, settings.language),
+            ),
+            controls: Dropdown::new(
+                rect,
+                &[
+                    text("settings.controls.arrows"),
+                    text("settings.controls.wasd"),
+                ],
+                position(&CONTROL_SCHEMES, settings.controls),
+            ),
+            labels: vec![
+                label("settings.resolution"),
+                label("settings.render_mode"),
+                label("settings.language"),
+                label("settings.controls"),
+            ],
+            btn_save: Button::from_rect(rect, GREEN, text("settings.save")),
+            btn_back: Button::from_rect(rect, GRAY, text("settings.back")),
+            message: Label::new(rect, ""),
+            focus: Focus::new(),
+            nav: Navigation::default(),
+            viewport: Viewport::default(),
+        };
+        menu.viewport.resized();
+        menu.layout();
+        menu
+    }
+
+    /// Place les widgets en colonne au centre de la fenêtre
+    fn layout(&mut self) {
+        let screen = self.viewport.rect();
+        let column = Column::new(screen.w / 2.0, 0.0, 360.0, 32.0, 10.0);
+        // Le titre et les widgets (dix lignes et le message) sont centrés ensemble
+        let column = Column {
+            top: ((screen.h - TITLE_HEIGHT - column.height(11)) / 2.0).max(0.0) + TITLE_HEIGHT,
+            ..column
+        };
+
+        self.title
+            .set_rect(Rect::new(0., column.top - TITLE_HEIGHT, screen.w, 50.));
+        self.master_volume.set_rect(column.row(0));
+        self.sfx_volume.set_rect(column.row(1));
+        self.music_volume.set_rect(column.row(2));
+        self.fullscreen.set_rect(column.row(3));
+        self.resolution.set_rect(column.cell(4, 1, 2));
+        self.vsync.set_rect(column.row(5));
+        self.render_mode.set_rect(column.cell(6, 1, 2));
+        self.language.set_rect(column.cell(7, 1, 2));
+        self.controls.set_rect(column.cell(8, 1, 2));
+        for (label, row) in self.labels.iter_mut().zip([4, 6, 7, 8]) {
+            label.set_rect(column.cell(row, 0, 2));
+        }
+        self.btn_save.set_rect(column.cell(9, 0, 2));
+        self.btn_back.set_rect(column.cell(9, 1, 2));
+        self.message
+            .set_rect(Rect::new(0., column.row(10).y, screen.w, column.row_height));
+    }
+
+    /// Préférences choisies à l'écran
+    pub fn settings(&self) -> Settings {
+        let (width, height) = self.resolutions[self.resolution.selected()];
+        Settings {
+            master_volume: self.master_volume.value() as u32,
+            sfx_volume: self.sfx_volume.value() as u32,
+            music_volume: self.music_volume.value() as u32,
+            fullscreen: self.fullscreen.is_on(),
+            width,
+            height,
+            vsync: self.vsync.is_on(),
+            render_mode: RENDER_MODES[self.render_mode.selected()],
+            language: LANGUAGES[self.language.selected()],
+            controls: CONTROL_SCHEMES[self.controls.selected()],
+        }
+    }
+
+    /// Listes déroulantes de l'écran, de haut en bas
+    fn dropdowns(&mut self) -> [&mut Dropdown; 4] {
+        [
+            &mut self.resolution,
+            &mut self.render_mode,
+            &mut self.language,
+            &mut self.controls,
+        ]
+    }
+
+    /// Vérifie si une des listes est ouverte
+    fn list_open(&self) -> bool {
+        [
+            &self.resolution,
+            &self.render_mode,
+            &self.language,
+            &self.controls,
+        ]
+        .iter()
+        .any(|list| list.is_open())
+    }
+
+    /// Replace les widgets si la fenêtre a changé de taille et déplace le focus
+    /// avec les flèches haut et bas, à appeler à chaque frame avant `draw` et
+    /// les autres méthodes
+    ///
+    /// # Arguments
+    /// * `nav` - Actions de navigation de la frame
+    pub fn update(&mut self, nav: &Navigation) {
+        if self.viewport.resized() {
+            self.layout();
+        }
+        self.nav = nav.clone();
+        let step = nav.step(NavAction::Up, NavAction::Down);
+        if step == 0 || self.list_open() {
+            return;
+        }
+        self.focus.navigate(&ITEMS, step);
+
+        let focus = self.focus;
+        self.master_volume.set_focused(focus.is(Item::MasterVolume));
+        self.sfx_volume.set_focused(focus.is(Item::SfxVolume));
+        self.music_volume.set_focused(focus.is(Item::MusicVolume));
+        self.fullscreen.set_focused(focus.is(Item::Fullscreen));
+        self.resolution.set_focused(focus.is(Item::Resolution));
+        self.vsync.set_focused(focus.is(Item::Vsync));
+        self.render_mode.set_focused(focus.is(Item::RenderMode));
+        self.language.set_focused(focus.is(Item::Language));
+        self.controls.set_focused(focus.is(Item::Controls));
+        self.btn_save.set_focused(focus.is(Item::Save));
+        self.btn_back.set_focused(focus.is(Item::Back));
+    }
+
+    /// Dessine l'écran et gère les interactions
+    ///
+    /// # Arguments
+    /// * `font` - Police de caractères à utiliser pour le texte (`None` pour la police par défaut)
+    pub fn draw(&mut self, font: Option<&Font>) {
+        clear_background(BLACK);
+        self.title.draw(font);
+        self.master_volume.draw(font);
+        self.sfx_volume.draw(font);
+        self.music_volume.draw(font);
+        self.fullscreen.draw(font);
+        self.vsync.draw(font);
+        for label in &self.labels {
+            label.draw(font);
+        }
+        self.btn_save.draw(font);
+        self.btn_back.draw(font);
+        self.message.draw(font);
+        // Les listes du haut recouvrent celles du bas quand elles sont ouvertes
+        for list in self.dropdowns().into_iter().rev() {
+            list.draw(font);
+        }
+
+        // Un clic dans une liste ouverte ne doit pas atteindre les widgets du dessous
+        let nav = self.nav.clone();
+        let list_open = self.list_open();
+        for list in self.dropdowns() {
+            if list.is_open() || !list_open {
+                list.update(&nav);
+            }
+        }
+        if list_open || self.list_open() {
+            return;
+        }
+        self.master_volume.update(&nav);
+        self.sfx_volume.update(&nav);
+        self.music_volume.update(&nav);
+        self.fullscreen.update(&nav);
+        self.vsync.update(&nav);
+    }
+
+    /// Affiche l'erreur d'un enregistrement raté
+    pub fn set_error(&mut self, error: &str) {
+        self.message.set_text(error, RED);
+    }
+
+    /// Vérifie si le bouton enregistrer a été cliqué ou activé
+    pub fn save_clicked(&self) -> bool {
+        !self.list_open() && self.btn_save.is_activated(&self.nav)
+    }
+
+    /// Vérifie si le bouton retour a été cliqué ou activé
+    pub fn back_clicked(&self) -> bool {
+        !self.list_open() && self.btn_back.is_activated(&self.nav)
+    }
+}
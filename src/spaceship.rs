@@ -4,6 +4,7 @@
 //! pour gérer le déplacement, l'orientation et les collisions du vaisseau.
 
 use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
 
 /// Représente le vaisseau spatial contrôlé par le joueur.
 ///
@@ -13,12 +14,18 @@ use macroquad::prelude::*;
 /// * `orientation` - Angle d'orientation en radians
 /// * `shield` - Points de bouclier restants
 /// * `invincibility_timer` - Temps d'invincibilité restant après une collision
+/// * `spin` - Rotation due au dernier choc, en radians par frame
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Spaceship {
+    #[serde(with = "crate::save::vec2")]
     pub position: Vec2,
+    #[serde(with = "crate::save::vec2")]
     speed: Vec2,
     orientation: f32,
     shield: u8,
     invincibility_timer: f32,
+    #[serde(default)]
+    spin: f32,
 }
 
 impl Spaceship {
@@ -26,18 +33,34 @@ impl Spaceship {
     pub const SPACESHIP_HEIGHT: f32 = 25.0;
     pub const SPACESHIP_MAX_SPEED: f32 = 3.;
     pub const SPACESHIP_MIN_SPEED: f32 = 0.5;
-
-    /// Crée une nouvelle instance de Spaceship au centre de l'écran
-    pub fn new() -> Self {
+    pub const SPACESHIP_ROTATION: f32 = 0.03;
+    pub const SPACESHIP_SHIELD: u8 = 10;
+    /// Rotation donnée par un choc, par pixel par frame de vitesse d'impact
+    pub const KNOCK_SPIN: f32 = 0.025;
+    /// Rotation maximale due aux chocs, en radians par frame
+    pub const MAX_SPIN: f32 = 0.15;
+    /// Part de la rotation due aux chocs conservée d'une frame à l'autre
+    pub const SPIN_DAMPING: f32 = 0.93;
+
+    /// Crée une nouvelle instance de Spaceship à la position donnée
+    /// (en général le centre de l'arène)
+    pub fn new(position: Vec2) -> Self {
         Self {
-            position: Vec2::new(screen_width() / 2., screen_height() / 2.),
+            position,
             speed: Vec2::new(0.0, -0.7),
             orientation: 0.0,
-            shield: 10,
+            shield: Self::SPACESHIP_SHIELD,
             invincibility_timer: 0.0,
+            spin: 0.0,
         }
     }
 
+    /// Donne au vaisseau un bouclier différent de celui par défaut
+    pub fn with_shield(mut self, shield: u8) -> Self {
+        self.shield = shield;
+        self
+    }
+
     pub fn get_height(&self) -> f32 {
         Self::SPACESHIP_HEIGHT
     }
@@ -62,6 +85,10 @@ impl Spaceship {
         self.invincibility_timer
     }
 
+    pub fn get_spin(&self) -> f32 {
+        self.spin
+    }
+
     /// Enlève un de vie
     pub fn set_shield(&mut self) {
         self.shield -= 1;
@@ -81,8 +108,8 @@ impl Spaceship {
     /// L (left), R (right)
     pub fn set_orientation(&mut self, dir: char) {
         match dir {
-            'L' => self.orientation -= 0.03,
-            'R' => self.orientation += 0.03,
+            'L' => self.orientation -= Self::SPACESHIP_ROTATION,
+            'R' => self.orientation += Self::SPACESHIP_ROTATION,
             _ => panic!("pas une direction"),
         }
     }
@@ -92,10 +119,10 @@ impl Spaceship {
         self.speed += acc;
     }
 
-    /// Fait apparaitre le vaisseau de l'autre coté de l'écran si il sort
-    pub fn bound_pos(&mut self) -> Vec2 {
-        self.position.x = Self::bound_to(self.position.x, screen_width());
-        self.position.y = Self::bound_to(self.position.y, screen_height());
+    /// Fait apparaitre le vaisseau de l'autre coté de l'arène si il sort
+    pub fn bound_pos(&mut self, arena: Vec2) -> Vec2 {
+        self.position.x = Self::bound_to(self.position.x, arena.x);
+        self.position.y = Self::bound_to(self.position.y, arena.y);
         self.position
     }
 
@@ -138,11 +165,34 @@ impl Spaceship {
             .clamp(-Self::SPACESHIP_MAX_SPEED, Self::SPACESHIP_MAX_SPEED);
     }
 
-    pub fn handle_collision(&mut self, correction: Vec2) {
+    /// Fait tourner le vaisseau sous un choc, d'autant plus que l'impact est fort
+    ///
+    /// # Arguments
+    /// * `offset` - Position de l'objet percutant par rapport au vaisseau
+    /// * `velocity` - Vitesse de l'objet percutant par rapport au vaisseau
+    pub fn knock(&mut self, offset: Vec2, velocity: Vec2) {
+        // Le côté touché donne le sens de rotation
+        let side = offset.perp_dot(velocity).signum();
+        self.spin = (self.spin + side * velocity.length() * Self::KNOCK_SPIN)
+            .clamp(-Self::MAX_SPIN, Self::MAX_SPIN);
+    }
+
+    /// Applique puis amortit la rotation due aux chocs
+    pub fn apply_spin(&mut self) {
+        self.orientation += self.spin;
+        self.spin *= Self::SPIN_DAMPING;
+    }
+
+    /// Enlève un point de bouclier, sauf pendant l'invincibilité qui suit un choc
+    pub fn hit(&mut self) {
         if self.get_invicibility() <= 0.0 {
             self.set_shield();
             self.init_invicibility();
         }
+    }
+
+    pub fn handle_collision(&mut self, correction: Vec2) {
+        self.hit();
         self.position += correction;
         self.bounce();
     }
@@ -0,0 +1,612 @@
+//! Module diffusant une partie en direct à des spectateurs (TCP).
+//!
+//! Avec `--broadcast PORT`, la partie jouée est publiée sur un port TCP local ;
+//! d'autres instances la regardent avec `--watch ADRESSE`, sans pouvoir agir.
+//!
+//! Chaque message est précédé de sa longueur (4 octets) et commence par son type :
+//! * une image clé contient la partie complète en JSON ; elle est envoyée à
+//!   chaque nouveau spectateur, puis régulièrement à tous ;
+//! * une image différentielle contient la partie sans ses astéroïdes ni ses
+//!   missiles (JSON), puis, pour chacune de ces deux listes, soit le
+//!   déplacement de chaque objet depuis l'image précédente (deux entiers de
+//!   16 bits au seizième de pixel), soit la liste complète si des objets sont
+//!   apparus ou ont disparu.
+//!
+//! Tout ce qui suit le type du message est compressé (deflate). Les
+//! déplacements sont calculés par rapport aux positions reconstruites par
+//! les spectateurs, pour que les arrondis ne s'accumulent pas. Un message plus
+//! long que `MAX_PENDING`, une fois reçu ou décompressé, est refusé.
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+
+use macroquad::prelude::*;
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::assets::Assets;
+use crate::asteroid::Asteroid;
+use crate::bullet::Bullet;
+use crate::cli::Options;
+use crate::effects::HitEffects;
+use crate::hud::Hud;
+use crate::locale::Strings;
+use crate::render::{self, MacroquadRenderer};
+use crate::world::{wrapped_delta, World};
+
+/// Port de diffusion par défaut
+pub const DEFAULT_BROADCAST_PORT: u16 = 7778;
+
+/// Nombre d'images entre deux images clés envoyées à tous les spectateurs
+const KEYFRAME_INTERVAL: u64 = 600;
+
+/// Subdivisions d'un pixel dans les déplacements transmis
+const POSITION_SCALE: f32 = 16.0;
+
+/// Déplacement au-delà duquel la liste complète est envoyée (autre objet)
+const MAX_DELTA: f32 = 50.0;
+
+/// Taille maximale des données en attente pour un spectateur trop lent, et
+/// d'un message reçu par un spectateur
+const MAX_PENDING: usize = 4 << 20;
+
+/// Niveau de compression des messages (0 à 10)
+const COMPRESSION_LEVEL: u8 = 6;
+
+/// Type d'un message : image clé
+const KEYFRAME: u8 = 0;
+
+/// Type d'un message : image différentielle
+const DELTA: u8 = 1;
+
+/// Liste d'objets transmise par déplacements
+const MOVED: u8 = 0;
+
+/// Liste d'objets transmise complète
+const REPLACED: u8 = 1;
+
+/// Objet dont seule la position est transmise d'une image à l'autre
+trait Positioned: Clone + Serialize + DeserializeOwned {
+    fn position(&self) -> Vec2;
+    fn position_mut(&mut self) -> &mut Vec2;
+}
+
+impl Positioned for Asteroid {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn position_mut(&mut self) -> &mut Vec2 {
+        &mut self.position
+    }
+}
+
+impl Positioned for Bullet {
+    fn position(&self) -> Vec2 {
+        self.position
+    }
+
+    fn position_mut(&mut self) -> &mut Vec2 {
+        &mut self.position
+    }
+}
+
+/// Ajoute une valeur en JSON, précédée de sa longueur
+fn write_json(out: &mut Vec<u8>, value: &impl Serialize) {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    out.extend((bytes.len() as u32).to_le_bytes());
+    out.extend(bytes);
+}
+
+/// Compresse un message, à l'exception de son type
+fn compress(mut message: Vec<u8>) -> Vec<u8> {
+    let body = compress_to_vec(&message[1..], COMPRESSION_LEVEL);
+    message.truncate(1);
+    message.extend(body);
+    message
+}
+
+/// Lecture d'un message reçu
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if self.bytes.len() < len {
+            return Err("message tronqué".to_string());
+        }
+        let (head, tail) = self.bytes.split_at(len);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn i16(&mut self) -> Result<i16, String> {
+        Ok(i16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn json<T: DeserializeOwned>(&mut self) -> Result<T, String> {
+        let len = self.u32()? as usize;
+        serde_json::from_slice(self.take(len)?).map_err(|e| format!("message invalide ({e})"))
+    }
+}
+
+/// Position après un déplacement transmis, ramenée dans l'arène
+fn apply_delta(position: Vec2, delta: (i16, i16), arena: Vec2) -> Vec2 {
+    let position = position + vec2(delta.0 as f32, delta.1 as f32) / POSITION_SCALE;
+    vec2(
+        position.x.rem_euclid(arena.x),
+        position.y.rem_euclid(arena.y),
+    )
+}
+
+/// Ajoute une liste d'objets et met à jour celle connue des spectateurs
+fn encode_objects<T: Positioned>(out: &mut Vec<u8>, sent: &mut Vec<T>, current: &[T], arena: Vec2) {
+    let deltas: Option<Vec<(i16, i16)>> = (sent.len() == current.len())
+        .then(|| {
+            sent.iter_mut()
+                .zip(current)
+                .map(|(old, new)| {
+                    let delta = wrapped_delta(*old.position_mut(), new.position(), arena);
+                    (delta.length() <= MAX_DELTA).then(|| {
+                        let q = (delta * POSITION_SCALE).round();
+                        (q.x as i16, q.y as i16)
+                    })
+                })
+                .collect()
+        })
+        .flatten();
+    match deltas {
+        Some(deltas) => {
+            out.push(MOVED);
+            out.extend((deltas.len() as u16).to_le_bytes());
+            for (object, delta) in sent.iter_mut().zip(deltas) {
+                out.extend(delta.0.to_le_bytes());
+                out.extend(delta.1.to_le_bytes());
+                let position = object.position_mut();
+                *position = apply_delta(*position, delta, arena);
+            }
+        }
+        None => {
+            out.push(REPLACED);
+            write_json(out, &current);
+            *sent = current.to_vec();
+        }
+    }
+}
+
+/// Lit une liste d'objets à partir de celle de l'image précédente
+fn decode_objects<T: Positioned>(
+    reader: &mut Reader,
+    mut objects: Vec<T>,
+    arena: Vec2,
+) -> Result<Vec<T>, String> {
+    match reader.u8()? {
+        MOVED => {
+            if reader.u16()? as usize != objects.len() {
+                return Err("nombre d'objets incohérent".to_string());
+            }
+            for object in &mut objects {
+                let delta = (reader.i16()?, reader.i16()?);
+                let position = object.position_mut();
+                *position = apply_delta(*position, delta, arena);
+            }
+            Ok(objects)
+        }
+        REPLACED => reader.json(),
+        kind => Err(format!("liste d'objets inconnue ({kind})")),
+    }
+}
+
+/// Encode les images successives d'une partie
+///
+/// # Champs
+/// * `sent` - Partie telle que reconstruite par les spectateurs
+/// * `frames` - Nombre d'images encodées
+#[derive(Default)]
+pub struct Encoder {
+    sent: Option<World>,
+    frames: u64,
+}
+
+impl Encoder {
+    /// Image clé de la partie connue des spectateurs, pour un nouveau spectateur
+    pub fn keyframe(&self) -> Option<Vec<u8>> {
+        let world = self.sent.as_ref()?;
+        let mut out = vec![KEYFRAME];
+        write_json(&mut out, world);
+        Some(compress(out))
+    }
+
+    /// Image suivante : clé au début puis régulièrement, différentielle sinon
+    pub fn encode(&mut self, world: &World) -> Vec<u8> {
+        self.frames += 1;
+        let Some(sent) = &mut self.sent else {
+            self.sent = Some(world.clone());
+            return self.keyframe().unwrap_or_default();
+        };
+        if self.frames.is_multiple_of(KEYFRAME_INTERVAL) {
+            *sent = world.clone();
+            return self.keyframe().unwrap_or_default();
+        }
+
+        // L'en-tête est la partie sans ses astéroïdes ni ses missiles
+        let mut next = world.clone();
+        let asteroids = std::mem::take(&mut next.asteroids);
+        let bullets = std::mem::take(&mut next.bullets);
+        let mut out = vec![DELTA];
+        write_json(&mut out, &next);
+        encode_objects(&mut out, &mut sent.asteroids, &asteroids, world.size);
+        encode_objects(&mut out, &mut sent.bullets, &bullets, world.size);
+        next.asteroids = std::mem::take(&mut sent.asteroids);
+        next.bullets = std::mem::take(&mut sent.bullets);
+        *sent = next;
+        compress(out)
+    }
+}
+
+/// Reconstruit la partie à partir des images reçues
+#[derive(Default)]
+pub struct Decoder {
+    world: Option<World>,
+}
+
+impl Decoder {
+    /// Partie reconstruite, `None` avant la première image clé
+    pub fn world(&self) -> Option<&World> {
+        self.world.as_ref()
+    }
+
+    /// Applique une image reçue
+    pub fn apply(&mut self, message: &[u8]) -> Result<(), String> {
+        let (&kind, body) = message.split_first().ok_or("message vide")?;
+        let body = decompress_to_vec_with_limit(body, MAX_PENDING)
+            .map_err(|e| format!("message invalide ({e})"))?;
+        let mut reader = Reader { bytes: &body };
+        match kind {
+            KEYFRAME => self.world = Some(reader.json()?),
+            DELTA => {
+                let previous = self
+                    .world
+                    .take()
+                    .ok_or("image différentielle reçue avant l'image clé")?;
+                let mut world: World = reader.json()?;
+                world.asteroids = decode_objects(&mut reader, previous.asteroids, world.size)?;
+                world.bullets = decode_objects(&mut reader, previous.bullets, world.size)?;
+                self.world = Some(world);
+            }
+            kind => return Err(format!("message inconnu ({kind})")),
+        }
+        Ok(())
+    }
+}
+
+/// Spectateur connecté, avec les données qui n'ont pas encore pu lui être envoyées
+struct Viewer {
+    stream: TcpStream,
+    pending: Vec<u8>,
+}
+
+impl Viewer {
+    /// Ajoute un message à envoyer, précédé de sa longueur
+    fn queue(&mut self, message: &[u8]) {
+        self.pending.extend((message.len() as u32).to_le_bytes());
+        self.pending.extend(message);
+    }
+
+    /// Envoie ce qui peut l'être sans bloquer
+    ///
+    /// # Retourne
+    /// `false` si le spectateur est parti ou trop lent
+    fn flush(&mut self) -> bool {
+        while !self.pending.is_empty() {
+            match self.stream.write(&self.pending) {
+                Ok(0) => return false,
+                Ok(written) => {
+                    self.pending.drain(..written);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => return false,
+            }
+        }
+        self.pending.len() <= MAX_PENDING
+    }
+}
+
+/// Diffusion d'une partie aux spectateurs
+///
+/// # Champs
+/// * `listener` - Port d'écoute des spectateurs
+/// * `viewers` - Spectateurs connectés
+/// * `encoder` - Images envoyées aux spectateurs
+pub struct Broadcast {
+    listener: TcpListener,
+    viewers: Vec<Viewer>,
+    encoder: Encoder,
+}
+
+impl Broadcast {
+    /// Ouvre la diffusion sur le port donné (0 pour un port libre quelconque)
+    pub fn bind(port: u16) -> Result<Self, String> {
+        let listener = TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port)))
+            .map_err(|e| format!("impossible d'ouvrir le port {port} ({e})"))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        Ok(Self {
+            listener,
+            viewers: Vec::new(),
+            encoder: Encoder::default(),
+        })
+    }
+
+    /// Port de la diffusion
+    pub fn port(&self) -> u16 {
+        self.listener.local_addr().map_or(0, |addr| addr.port())
+    }
+
+    /// Nombre de spectateurs connectés
+    pub fn viewers(&self) -> usize {
+        self.viewers.len()
+    }
+
+    /// Accueille les nouveaux spectateurs et leur envoie l'image de la frame
+    pub fn publish(&mut self, world: &World) {
+        while let Ok((stream, _)) = self.listener.accept() {
+            if stream.set_nonblocking(true).is_err() {
+                continue;
+            }
+            let _ = stream.set_nodelay(true);
+            let mut viewer = Viewer {
+                stream,
+                pending: Vec::new(),
+            };
+            // Le nouveau spectateur part de la partie connue des autres
+            if let Some(keyframe) = self.encoder.keyframe() {
+                viewer.queue(&keyframe);
+            }
+            self.viewers.push(viewer);
+        }
+        if self.viewers.is_empty() {
+            // Sans spectateur, la prochaine image sera une image clé
+            self.encoder = Encoder::default();
+            return;
+        }
+
+        let message = self.encoder.encode(world);
+        self.viewers.retain_mut(|viewer| {
+            viewer.queue(&message);
+            viewer.flush()
+        });
+    }
+}
+
+/// Spectateur d'une partie diffusée
+///
+/// # Champs
+/// * `stream` - Connexion à la partie
+/// * `buffer` - Données reçues pas encore décodées
+/// * `decoder` - Partie reconstruite
+pub struct Spectator {
+    stream: TcpStream,
+    buffer: Vec<u8>,
+    decoder: Decoder,
+}
+
+impl Spectator {
+    /// Se connecte à une partie diffusée (`adresse` ou `adresse:port`)
+    pub fn connect(addr: &str) -> Result<Self, String> {
+        let addrs = if addr.contains(':') {
+            addr.to_socket_addrs()
+        } else {
+            (addr, DEFAULT_BROADCAST_PORT).to_socket_addrs()
+        };
+        let addrs: Vec<SocketAddr> = addrs
+            .map_err(|e| format!("adresse invalide '{addr}' ({e})"))?
+            .collect();
+        let stream = TcpStream::connect(addrs.as_slice())
+            .map_err(|e| format!("impossible de se connecter à '{addr}' ({e})"))?;
+        stream.set_nonblocking(true).map_err(|e| e.to_string())?;
+        Ok(Self {
+            stream,
+            buffer: Vec::new(),
+            decoder: Decoder::default(),
+        })
+    }
+
+    /// Partie reconstruite, `None` avant la première image
+    pub fn world(&self) -> Option<&World> {
+        self.decoder.world()
+    }
+
+    /// Lit et applique les images reçues
+    ///
+    /// # Retourne
+    /// Une erreur si la diffusion s'est arrêtée ou si un message est invalide
+    pub fn poll(&mut self) -> Result<(), String> {
+        let mut chunk = [0; 16 * 1024];
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => return Err("la diffusion est terminée".to_string()),
+                Ok(len) => self.buffer.extend_from_slice(&chunk[..len]),
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        while self.buffer.len() >= 4 {
+            let len = u32::from_le_bytes(self.buffer[..4].try_into().unwrap()) as usize;
+            if len > MAX_PENDING {
+                return Err(format!("message trop long ({len} octets)"));
+            }
+            if self.buffer.len() < 4 + len {
+                break;
+            }
+            let message: Vec<u8> = self.buffer.drain(..4 + len).skip(4).collect();
+            self.decoder.apply(&message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Boucle d'affichage d'un spectateur
+///
+/// # Arguments
+/// * `options` - Options de lancement (adresse, mode de rendu)
+/// * `spectator` - Connexion ouverte par `Spectator::connect`
+pub async fn watch_game(options: Options, mut spectator: Spectator) {
+    let assets = Assets::load(options.data_dir.as_deref());
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
+    assets.show_errors(&strings).await;
+    let font = assets.font();
+
+    let mut error: Option<String> = None;
+    let mut size: Option<Vec2> = None;
+    let mut effects = HitEffects::default();
+
+    loop {
+        if is_key_down(KeyCode::Escape) {
+            break;
+        }
+        if error.is_none() {
+            error = spectator.poll().err();
+        }
+
+        match spectator.world() {
+            Some(world) => {
+                // La fenêtre suit la taille de l'arène diffusée
+                if size != Some(world.size) {
+                    request_new_screen_size(world.size.x, world.size.y);
+                    size = Some(world.size);
+                }
+                effects.update(get_frame_time());
+                effects.observe(world);
+                effects.draw(
+                    &mut MacroquadRenderer::new(&assets, options.render_mode),
+                    world,
+                    &Hud::new(&strings),
+                );
+                let text = strings.get("net.spectator");
+                render::draw_centered_text(text, 40., 30, GRAY, font);
+            }
+            None => clear_background(BLACK),
+        }
+        if let Some(error) = &error {
+            render::draw_centered_text(error, screen_height() / 2., 30, RED, font);
+        } else if spectator.world().is_none() {
+            let text = strings.format("net.connecting", &[&options.watch.as_deref().unwrap_or("")]);
+            render::draw_centered_text(&text, screen_height() / 2., 30, WHITE, font);
+        }
+
+        next_frame().await;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::autopilot::Autopilot;
+    use crate::input::{Controller, Input};
+    use crate::world::DEFAULT_ARENA;
+    use crate::Difficulty;
+
+    #[test]
+    fn images_differentielles() {
+        let mut world = World::new(4, Difficulty::Hard, DEFAULT_ARENA);
+        let mut pilot = Autopilot::new();
+        let mut encoder = Encoder::default();
+        let mut decoder = Decoder::default();
+        let (mut keyframe_size, mut delta_size, mut deltas) = (0, 0, 0);
+
+        for _ in 0..300 {
+            let input = pilot.input(&world, 0);
+            world.step(&input);
+            let message = encoder.encode(&world);
+            match message[0] {
+                KEYFRAME => keyframe_size = message.len(),
+                _ => {
+                    delta_size += message.len();
+                    deltas += 1;
+                }
+            }
+            decoder.apply(&message).unwrap();
+
+            // Les positions reconstruites restent à un demi-seizième de pixel près
+            let decoded = decoder.world().unwrap();
+            assert_eq!(decoded.players[0].score, world.players[0].score);
+            assert_eq!(decoded.asteroids.len(), world.asteroids.len());
+            for (a, b) in decoded.asteroids.iter().zip(&world.asteroids) {
+                let error = wrapped_delta(a.get_position(), b.get_position(), world.size);
+                assert!(error.length() <= 1. / POSITION_SCALE);
+            }
+        }
+        // Une image clé compressée est bien plus petite que le JSON de la
+        // partie, une image différentielle plus petite qu'une image clé
+        assert!(delta_size / deltas < keyframe_size);
+        assert!(keyframe_size < serde_json::to_vec(&world).unwrap().len() / 2);
+    }
+
+    #[test]
+    fn message_trop_long_refuse() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let mut spectator = Spectator::connect(&format!("127.0.0.1:{port}")).unwrap();
+        let (mut stream, _) = listener.accept().unwrap();
+
+        // Seule la longueur est envoyée : le message est refusé sans l'attendre
+        stream.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        let start = Instant::now();
+        let result = loop {
+            assert!(start.elapsed() < Duration::from_secs(2), "délai dépassé");
+            match spectator.poll() {
+                Ok(()) => std::thread::sleep(Duration::from_millis(1)),
+                error => break error,
+            }
+        };
+        assert!(result.unwrap_err().contains("trop long"));
+    }
+
+    #[test]
+    fn spectateur_en_cours_de_partie() {
+        let mut broadcast = Broadcast::bind(0).unwrap();
+        let mut world = World::new(1, Difficulty::Medium, DEFAULT_ARENA);
+        let mut watchers = Vec::new();
+
+        // Un spectateur arrive au début, un autre en cours de partie
+        for frame in 0..120 {
+            if frame == 0 || frame == 60 {
+                watchers
+                    .push(Spectator::connect(&format!("127.0.0.1:{}", broadcast.port())).unwrap());
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            world.step(&Input::default());
+            broadcast.publish(&world);
+        }
+        assert_eq!(broadcast.viewers(), 2);
+
+        for watcher in &mut watchers {
+            let start = Instant::now();
+            while watcher.world().is_none_or(|seen| seen.frame != world.frame) {
+                assert!(start.elapsed() < Duration::from_secs(2), "délai dépassé");
+                watcher.poll().unwrap();
+                std::thread::sleep(Duration::from_millis(1));
+            }
+            let seen = watcher.world().unwrap();
+            assert_eq!(seen.asteroids.len(), world.asteroids.len());
+        }
+    }
+}
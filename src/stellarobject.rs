@@ -41,6 +41,7 @@ impl StellarObject for Asteroid {
 impl StellarObject for Spaceship {
     fn move_object(&mut self) {
         self.position += self.get_speed();
+        self.apply_spin();
     }
 
     fn collided(&mut self, other: &mut Asteroid) -> bool {
@@ -51,6 +52,7 @@ impl StellarObject for Spaceship {
             let overlap = min_distance - distance;
             let correction = delta.normalize() * overlap / 2.0;
 
+            self.knock(delta, other.get_speed() - self.get_speed());
             self.handle_collision(-correction);
             other.handle_collision(correction);
 
@@ -0,0 +1,230 @@
+//! Module contenant un moteur de rendu en mode texte.
+//!
+//! L'arène est découpée en une grille de caractères, ce qui permet d'afficher
+//! une partie dans un terminal ou de comparer une scène à un texte attendu dans
+//! les tests, sans carte graphique.
+//!
+//! | Objet     | Caractère               |
+//! |-----------|-------------------------|
+//! | Vaisseau  | `^`, `>`, `v` ou `<`    |
+//! | Astéroïde | `#`                     |
+//! | Missile   | `*`                     |
+//! | Jauge     | `[===   ]`              |
+
+use std::f32::consts::{FRAC_PI_4, PI};
+use std::fmt;
+
+use macroquad::prelude::*;
+
+use crate::asteroid::Asteroid;
+use crate::bullet::Bullet;
+use crate::render::{explosion_debris, Renderer};
+use crate::spaceship::Spaceship;
+
+/// Taille par défaut de la grille (celle d'un terminal classique)
+pub const DEFAULT_COLUMNS: usize = 80;
+pub const DEFAULT_ROWS: usize = 30;
+
+/// Moteur de rendu dessinant dans une grille de caractères
+///
+/// # Champs
+/// * `columns` - Nombre de colonnes de la grille
+/// * `rows` - Nombre de lignes de la grille
+/// * `cell` - Taille d'une case en pixels de l'arène
+/// * `cells` - Contenu de la grille, ligne par ligne
+pub struct TextRenderer {
+    columns: usize,
+    rows: usize,
+    cell: Vec2,
+    cells: Vec<char>,
+}
+
+impl TextRenderer {
+    /// Crée une grille vide couvrant toute l'arène
+    ///
+    /// # Arguments
+    /// * `columns` - Nombre de colonnes de la grille
+    /// * `rows` - Nombre de lignes de la grille
+    /// * `arena` - Dimensions de l'arène représentée
+    pub fn new(columns: usize, rows: usize, arena: Vec2) -> Self {
+        Self {
+            columns,
+            rows,
+            cell: arena / vec2(columns as f32, rows as f32),
+            cells: vec![' '; columns * rows],
+        }
+    }
+
+    /// Case de la grille contenant un point de l'arène
+    fn cell_of(&self, pos: Vec2) -> Option<(usize, usize)> {
+        let cell = (pos / self.cell).floor();
+        if cell.x < 0. || cell.y < 0. {
+            return None;
+        }
+        let (column, row) = (cell.x as usize, cell.y as usize);
+        (column < self.columns && row < self.rows).then_some((column, row))
+    }
+
+    /// Écrit un caractère dans une case
+    fn set(&mut self, column: usize, row: usize, c: char) {
+        if column < self.columns && row < self.rows {
+            self.cells[row * self.columns + column] = c;
+        }
+    }
+
+    /// Écrit un caractère dans la case contenant un point de l'arène
+    fn plot(&mut self, pos: Vec2, c: char) {
+        if let Some((column, row)) = self.cell_of(pos) {
+            self.set(column, row, c);
+        }
+    }
+
+    /// Centre d'une case, en pixels de l'arène
+    fn cell_center(&self, column: usize, row: usize) -> Vec2 {
+        (vec2(column as f32, row as f32) + 0.5) * self.cell
+    }
+}
+
+impl Renderer for TextRenderer {
+    fn draw_background(&mut self) {
+        self.cells.fill(' ');
+    }
+
+    /// Les joueurs ne sont pas distingués
+    fn draw_spaceship(&mut self, spaceship: &Spaceship, _player: usize) {
+        self.plot(
+            spaceship.get_position(),
+            direction_char(spaceship.get_orientation()),
+        );
+    }
+
+    /// Remplit les cases dont le centre est dans le cercle de collision
+    fn draw_asteroid(&mut self, asteroid: &Asteroid) {
+        let pos = asteroid.get_position();
+        let radius = asteroid.get_width();
+        for row in 0..self.rows {
+            for column in 0..self.columns {
+                if self.cell_center(column, row).distance(pos) <= radius {
+                    self.set(column, row, '#');
+                }
+            }
+        }
+        // Un petit astéroïde occupe toujours au moins une case
+        self.plot(pos, '#');
+    }
+
+    fn draw_bullet(&mut self, bullet: &Bullet) {
+        self.plot(bullet.get_position(), '*');
+    }
+
+    /// Chaque débris est un point
+    fn draw_explosion(&mut self, center: Vec2, progress: f32, _player: usize) {
+        for debris in explosion_debris(center, progress) {
+            self.plot(debris, '.');
+        }
+    }
+
+    /// Le texte est écrit sur la ligne du milieu de sa hauteur, la couleur est ignorée
+    fn draw_text(&mut self, text: &str, pos: Vec2, font_size: u16, _color: Color) {
+        let middle = vec2(pos.x, pos.y - font_size as f32 / 2.);
+        if let Some((column, row)) = self.cell_of(middle) {
+            for (i, c) in text.chars().enumerate() {
+                self.set(column + i, row, c);
+            }
+        }
+    }
+
+    /// La jauge est une suite de `=` entre crochets, sur la ligne de son centre
+    fn draw_gauge(&mut self, rect: Rect, ratio: f32, _color: Color) {
+        let Some((column, row)) = self.cell_of(vec2(rect.x, rect.center().y)) else {
+            return;
+        };
+        let width = ((rect.w / self.cell.x).round() as usize).max(3);
+        let filled = ((width - 2) as f32 * ratio.clamp(0., 1.)).round() as usize;
+        self.set(column, row, '[');
+        for i in 1..width - 1 {
+            self.set(column + i, row, if i <= filled { '=' } else { ' ' });
+        }
+        self.set(column + width - 1, row, ']');
+    }
+
+    fn measure_text(&self, text: &str, _font_size: u16) -> f32 {
+        text.chars().count() as f32 * self.cell.x
+    }
+}
+
+impl fmt::Display for TextRenderer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.cells.chunks(self.columns).enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", row.iter().collect::<String>().trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Flèche représentant une orientation (0 = vers le haut, sens horaire)
+fn direction_char(orientation: f32) -> char {
+    let angle = orientation.rem_euclid(2. * PI);
+    match ((angle + FRAC_PI_4) / (PI / 2.)) as u32 % 4 {
+        0 => '^',
+        1 => '>',
+        2 => 'v',
+        _ => '<',
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::locale::Strings;
+    use crate::render::draw_world;
+    use crate::world::World;
+    use crate::Difficulty;
+
+    #[test]
+    fn orientation_fleches() {
+        assert_eq!(direction_char(0.), '^');
+        assert_eq!(direction_char(PI / 2.), '>');
+        assert_eq!(direction_char(PI), 'v');
+        assert_eq!(direction_char(-PI / 2.), '<');
+    }
+
+    #[test]
+    fn scene_en_texte() {
+        let mut rng = ::rand::thread_rng();
+        let mut world = World::new(0, Difficulty::Easy, vec2(800., 400.));
+        world.asteroids = vec![Asteroid::new2(&mut rng, vec2(460., 150.), 25.)];
+        world.bullets = vec![Bullet::new(vec2(305., 145.), vec2(0., 0.), 0)];
+
+        let mut renderer = TextRenderer::new(80, 20, world.size);
+        draw_world(&mut renderer, &world, &Strings::default());
+
+        let expected = [
+            "",
+            "",
+            "",
+            "  Score : 0                                                     Astéroïdes : 1",
+            "",
+            "",
+            "                                            ####",
+            "                              *            ######",
+            "                                            ####",
+            "",
+            "                                        ^",
+            "",
+            "",
+            "",
+            "                                                      Vaisseaux : 3",
+            "",
+            "                                                      Hyperespace [==========]",
+            "                                                      Tir         [==========]",
+            "                                                      Bouclier    [==========]",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(renderer.to_string(), expected);
+    }
+}
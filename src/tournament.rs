@@ -0,0 +1,376 @@
+//! Module organisant des tournois entre bots.
+//!
+//! Avec `--tournament N`, chaque bot donné par `--bot` joue N parties sans
+//! fenêtre dans chaque difficulté, avec les mêmes graines pour tous les bots.
+//! Un bot est un programme qui parle le protocole de l'API de pilotage (voir
+//! le module `api`) sur son entrée et sa sortie standard : il reçoit l'état de
+//! la partie à chaque frame et répond par une commande. Le nom `autopilot`
+//! désigne le pilote automatique du jeu, qui sert de référence.
+//!
+//! Les bots sont classés par score moyen puis par temps de survie moyen, dans
+//! un rapport CSV ou JSON (`--report`).
+
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use macroquad::prelude::*;
+use serde::{Serialize, Serializer};
+
+use crate::api::{error_message, parse_request, state_message};
+use crate::autopilot::Autopilot;
+use crate::cli::Options;
+use crate::input::{Controller, Input};
+use crate::locale::Strings;
+use crate::world::{GameState, World, FRAME_TIME};
+use crate::Difficulty;
+
+/// Nom du pilote automatique du jeu parmi les bots
+pub const BUILTIN_BOT: &str = "autopilot";
+
+/// Temps laissé à un bot pour répondre à chaque frame
+pub const BOT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// En-tête du rapport CSV
+pub const CSV_HEADER: &str = "rank,bot,difficulty,games,won,lost,score,survival,accuracy";
+
+/// Programme de bot lancé pour une partie
+///
+/// # Champs
+/// * `child` - Processus du bot
+/// * `stdin` - Entrée standard du bot, où sont écrits les états
+/// * `lines` - Lignes écrites par le bot, lues par un thread
+struct BotProcess {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+}
+
+impl BotProcess {
+    /// Lance le programme d'un bot (`programme arguments...`)
+    fn spawn(command: &str) -> Result<Self, String> {
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or("commande de bot vide")?;
+        let mut child = Command::new(program)
+            .args(words)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .map_err(|e| format!("impossible de lancer le bot '{command}' ({e})"))?;
+        let stdin = child.stdin.take().ok_or("entrée du bot indisponible")?;
+        let stdout = child.stdout.take().ok_or("sortie du bot indisponible")?;
+
+        // La lecture se fait dans un thread pour pouvoir abandonner un bot muet
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+        })
+    }
+
+    /// Envoie une ligne au bot
+    fn send(&mut self, line: &str) -> Result<(), String> {
+        writeln!(self.stdin, "{line}")
+            .and_then(|()| self.stdin.flush())
+            .map_err(|e| format!("le bot s'est arrêté ({e})"))
+    }
+
+    /// Envoie l'état de la partie et attend la commande du bot
+    fn input(&mut self, world: &World) -> Result<Input, String> {
+        self.send(&state_message(world, 0))?;
+        loop {
+            let line = match self.lines.recv_timeout(BOT_TIMEOUT) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => return Err("le bot ne répond plus".to_string()),
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err("le bot s'est arrêté".to_string())
+                }
+            };
+            match parse_request(&line) {
+                Ok(Some(input)) => return Ok(input),
+                Ok(None) => self.send(&state_message(world, 0))?,
+                Err(e) => self.send(&error_message(e))?,
+            }
+        }
+    }
+}
+
+impl Drop for BotProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Pilote d'une partie du tournoi
+enum Pilot {
+    Builtin(Autopilot),
+    Program(BotProcess),
+}
+
+impl Pilot {
+    fn new(bot: &str) -> Result<Self, String> {
+        if bot == BUILTIN_BOT {
+            Ok(Pilot::Builtin(Autopilot::new()))
+        } else {
+            BotProcess::spawn(bot).map(Pilot::Program)
+        }
+    }
+
+    fn input(&mut self, world: &World) -> Result<Input, String> {
+        match self {
+            Pilot::Builtin(autopilot) => Ok(autopilot.input(world, 0)),
+            Pilot::Program(process) => process.input(world),
+        }
+    }
+}
+
+/// Joue une partie sans fenêtre avec un bot
+///
+/// Si le bot s'arrête ou ne répond plus, la partie s'arrête là et compte
+/// avec son score du moment, et l'erreur est signalée avec les textes donnés.
+///
+/// # Retourne
+/// La partie terminée, ou une erreur si le bot n'a pas pu être lancé
+fn play(
+    bot: &str,
+    seed: u64,
+    difficulty: Difficulty,
+    size: Vec2,
+    frames: u64,
+    strings: &Strings,
+) -> Result<World, String> {
+    let mut pilot = Pilot::new(bot)?;
+    let mut world = World::new(seed, difficulty, size);
+    while world.frame < frames && !world.is_over() {
+        match pilot.input(&world) {
+            Ok(input) => world.step(&input),
+            Err(e) => {
+                eprintln!(
+                    "{}",
+                    strings.format("tournament.bot_error", &[&bot, &seed, &e])
+                );
+                break;
+            }
+        }
+    }
+    // Le bot reçoit l'état final, s'il écoute encore
+    if let Pilot::Program(process) = &mut pilot {
+        let _ = process.send(&state_message(&world, 0));
+    }
+    Ok(world)
+}
+
+/// Résultats d'un bot dans une difficulté
+///
+/// # Champs
+/// * `rank` - Classement du bot, toutes difficultés confondues
+/// * `bot` - Commande du bot
+/// * `difficulty` - Difficulté jouée
+/// * `games` - Nombre de parties jouées
+/// * `won` / `lost` - Parties gagnées et perdues (les autres ont atteint la limite de frames)
+/// * `score` - Score moyen
+/// * `survival` - Durée moyenne d'une partie, en secondes
+/// * `accuracy` - Part des missiles tirés ayant touché un astéroïde
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Standing {
+    pub rank: usize,
+    pub bot: String,
+    #[serde(serialize_with = "difficulty_name")]
+    pub difficulty: Difficulty,
+    pub games: u32,
+    pub won: u32,
+    pub lost: u32,
+    pub score: f32,
+    pub survival: f32,
+    pub accuracy: f32,
+}
+
+/// Écrit la difficulté sous le nom utilisé en ligne de commande
+fn difficulty_name<S: Serializer>(
+    difficulty: &Difficulty,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(difficulty.name())
+}
+
+/// Fait jouer `games` parties de chaque difficulté à chaque bot de `--bot`
+///
+/// Les parties utilisent les graines `seed`, `seed + 1`... pour que tous les
+/// bots affrontent les mêmes vagues d'astéroïdes.
+///
+/// # Retourne
+/// Le classement, ou une erreur si un bot n'a pas pu être lancé
+pub fn run(options: &Options, games: u32, seed: u64) -> Result<Vec<Standing>, String> {
+    if options.bots.is_empty() {
+        return Err("aucun bot pour le tournoi (--bot COMMANDE)".to_string());
+    }
+    let size = vec2(options.width as f32, options.height as f32);
+    let strings = Strings::load(options.language, options.data_dir.as_deref());
+    let mut standings = Vec::new();
+    for bot in &options.bots {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard] {
+            let (mut won, mut lost, mut score, mut frames) = (0, 0, 0, 0);
+            let (mut shots, mut hits) = (0, 0);
+            for game in 0..games {
+                let world = play(
+                    bot,
+                    seed.wrapping_add(game as u64),
+                    difficulty,
+                    size,
+                    options.frames,
+                    &strings,
+                )?;
+                match world.state {
+                    GameState::Won => won += 1,
+                    GameState::Lost => lost += 1,
+                    GameState::Running => {}
+                }
+                score += world.score() as u64;
+                frames += world.frame;
+                shots += world.players[0].shots;
+                hits += world.players[0].hits;
+            }
+            let count = games.max(1) as f32;
+            standings.push(Standing {
+                rank: 0,
+                bot: bot.clone(),
+                difficulty,
+                games,
+                won,
+                lost,
+                score: score as f32 / count,
+                survival: frames as f32 * FRAME_TIME / count,
+                accuracy: if shots == 0 {
+                    0.
+                } else {
+                    hits as f32 / shots as f32
+                },
+            });
+        }
+    }
+    rank(&mut standings);
+    Ok(standings)
+}
+
+/// Classe les bots par score moyen, puis par temps de survie moyen
+fn rank(standings: &mut [Standing]) {
+    // Moyennes de chaque bot sur toutes les difficultés
+    let mut bots: Vec<(String, f32, f32)> = Vec::new();
+    for standing in standings.iter() {
+        if bots.iter().any(|(bot, ..)| *bot == standing.bot) {
+            continue;
+        }
+        let rows: Vec<&Standing> = standings.iter().filter(|s| s.bot == standing.bot).collect();
+        let count = rows.len() as f32;
+        let score = rows.iter().map(|s| s.score).sum::<f32>() / count;
+        let survival = rows.iter().map(|s| s.survival).sum::<f32>() / count;
+        bots.push((standing.bot.clone(), score, survival));
+    }
+    bots.sort_by(|a, b| b.1.total_cmp(&a.1).then(b.2.total_cmp(&a.2)));
+
+    for standing in standings.iter_mut() {
+        standing.rank = 1 + bots
+            .iter()
+            .position(|(bot, ..)| *bot == standing.bot)
+            .unwrap_or_default();
+    }
+    standings.sort_by_key(|standing| standing.rank);
+}
+
+/// Rapport CSV du tournoi
+pub fn to_csv(standings: &[Standing]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    for s in standings {
+        // La commande du bot peut contenir des virgules ou des guillemets
+        let bot = format!("\"{}\"", s.bot.replace('"', "\"\""));
+        csv += &format!(
+            "\n{},{},{},{},{},{},{:.1},{:.2},{:.3}",
+            s.rank,
+            bot,
+            s.difficulty.name(),
+            s.games,
+            s.won,
+            s.lost,
+            s.score,
+            s.survival,
+            s.accuracy
+        );
+    }
+    csv
+}
+
+/// Enregistre le rapport du tournoi, en JSON si le fichier finit par `.json`, en CSV sinon
+pub fn save_report(standings: &[Standing], path: &str) -> Result<(), String> {
+    let report = if path.ends_with(".json") {
+        serde_json::to_string_pretty(standings).map_err(|e| e.to_string())?
+    } else {
+        to_csv(standings)
+    };
+    std::fs::write(path, report + "\n")
+        .map_err(|e| format!("impossible d'écrire le rapport '{path}' ({e})"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn classement_des_bots() {
+        // Un bot qui se contente d'accélérer, et un autre qui s'arrête aussitôt
+        let script =
+            std::env::temp_dir().join(format!("asteroid-bot-tournoi-{}.sh", std::process::id()));
+        std::fs::write(
+            &script,
+            "while read state; do echo '{\"cmd\": \"command\", \"thrust\": 1}'; done\n",
+        )
+        .unwrap();
+        let idle = format!("sh {}", script.display());
+        let options = Options {
+            frames: 300,
+            bots: vec![idle.clone(), BUILTIN_BOT.to_string(), "true".to_string()],
+            ..Default::default()
+        };
+        let standings = run(&options, 1, 3);
+        // Le script est supprimé avant les vérifications, qui peuvent échouer
+        std::fs::remove_file(&script).unwrap();
+        let standings = standings.unwrap();
+        assert_eq!(standings.len(), 9);
+
+        // Le pilote automatique marque des points, pas les autres
+        assert!(standings[..3]
+            .iter()
+            .all(|s| s.bot == BUILTIN_BOT && s.rank == 1));
+        assert!(standings[0].score > 0. && standings[0].accuracy > 0.);
+        let idle_row = standings.iter().find(|s| s.bot == idle).unwrap();
+        assert_eq!(idle_row.rank, 2);
+        assert_eq!(idle_row.score, 0.);
+        // Le bot qui s'arrête ne survit pas au-delà de la première frame
+        assert_eq!(standings[8].bot, "true");
+        assert_eq!(standings[8].survival, 0.);
+
+        let csv = to_csv(&standings);
+        assert!(csv.starts_with(CSV_HEADER));
+        assert!(csv.contains("\n1,\"autopilot\",easy,1,"));
+    }
+
+    #[test]
+    fn bot_introuvable() {
+        let options = Options {
+            bots: vec!["/bot/introuvable".to_string()],
+            ..Default::default()
+        };
+        assert!(run(&options, 1, 0).is_err());
+        assert!(run(&Options::default(), 1, 0).is_err());
+    }
+}
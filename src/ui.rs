@@ -0,0 +1,834 @@
+//! Module regroupant les widgets de l'interface utilisateur.
+//!
+//! En plus des boutons (module `button`), l'interface dispose de libellés,
+//! de curseurs, d'interrupteurs, de listes déroulantes et de champs de texte.
+//! Chaque widget occupe un rectangle calculé par une disposition (`Column`)
+//! plutôt qu'écrit en dur, et recalculé quand la fenêtre change de taille
+//! (`Viewport`). Les widgets interactifs ont une méthode `update`,
+//! appelée une fois par frame, qui gère la souris et le clavier et indique si
+//! la valeur a changé, et une méthode `draw`.
+//!
+//! Les menus se parcourent aussi au clavier et à la manette : les flèches ou
+//! la croix directionnelle déplacent le focus (`Focus`), Entrée ou le bouton A
+//! activent l'élément qui l'a, signalé par un cadre blanc.
+
+use macroquad::prelude::*;
+
+use crate::gamepad::{Gamepad, PadButton};
+
+/// Taille du texte des widgets
+pub const TEXT_SIZE: u16 = 20;
+
+/// Écart horizontal entre deux widgets d'une même ligne
+pub const CELL_GAP: f32 = 20.0;
+
+/// Fond des champs (curseur, liste déroulante, champ de texte)
+const FIELD_COLOR: Color = Color::new(0.1, 0.1, 0.15, 0.9);
+
+/// Couleur des parties actives (poignée, case cochée, champ sélectionné)
+const ACCENT_COLOR: Color = SKYBLUE;
+
+/// Alignement horizontal d'un texte dans son rectangle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Center,
+}
+
+/// Dessine un texte centré verticalement dans un rectangle
+///
+/// # Arguments
+/// * `text` - Texte à écrire
+/// * `rect` - Rectangle contenant le texte
+/// * `font_size` - Taille du texte
+/// * `color` - Couleur du texte
+/// * `align` - Alignement horizontal
+/// * `font` - Police (`None` pour la police par défaut)
+pub fn draw_text_in(
+    text: &str,
+    rect: Rect,
+    font_size: u16,
+    color: Color,
+    align: Align,
+    font: Option<&Font>,
+) {
+    let size = measure_text(text, font, font_size, 1.0);
+    let x = match align {
+        Align::Left => rect.x,
+        Align::Center => rect.x + (rect.w - size.width) / 2.,
+    };
+    draw_text_ex(
+        text,
+        x,
+        rect.y + (rect.h + size.offset_y) / 2.,
+        TextParams {
+            font_size,
+            font,
+            color,
+            ..Default::default()
+        },
+    );
+}
+
+/// Action de navigation dans un menu au clavier ou à la manette
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    Activate,
+}
+
+/// Actions de navigation d'une frame
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Navigation {
+    actions: Vec<NavAction>,
+}
+
+impl Navigation {
+    /// Lit les touches et les boutons de la manette enfoncés pendant la frame
+    pub fn read(gamepad: &Gamepad) -> Self {
+        let inputs = [
+            (NavAction::Up, KeyCode::Up, PadButton::Up),
+            (NavAction::Down, KeyCode::Down, PadButton::Down),
+            (NavAction::Left, KeyCode::Left, PadButton::Left),
+            (NavAction::Right, KeyCode::Right, PadButton::Right),
+            (NavAction::Activate, KeyCode::Enter, PadButton::A),
+        ];
+        Self {
+            actions: inputs
+                .into_iter()
+                .filter(|&(_, key, button)| is_key_pressed(key) || gamepad.is_pressed(button))
+                .map(|(action, ..)| action)
+                .collect(),
+        }
+    }
+
+    /// Vérifie si une action a été demandée pendant la frame
+    pub fn pressed(&self, action: NavAction) -> bool {
+        self.actions.contains(&action)
+    }
+
+    /// Déplacement demandé entre deux actions opposées : -1, 0 ou 1
+    pub fn step(&self, previous: NavAction, next: NavAction) -> isize {
+        self.pressed(next) as isize - self.pressed(previous) as isize
+    }
+}
+
+/// Élément d'un menu ayant le focus du clavier et de la manette
+///
+/// Aucun élément n'a le focus tant que le joueur n'a pas navigué, pour ne
+/// pas encadrer de bouton quand il joue à la souris.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Focus<T> {
+    current: Option<T>,
+}
+
+impl<T: Copy + PartialEq> Focus<T> {
+    /// Crée un focus qui n'est sur aucun élément
+    pub fn new() -> Self {
+        Self { current: None }
+    }
+
+    /// Élément ayant le focus, `None` tant que le joueur n'a pas navigué
+    pub fn current(&self) -> Option<T> {
+        self.current
+    }
+
+    /// Vérifie si l'élément a le focus
+    pub fn is(&self, item: T) -> bool {
+        self.current == Some(item)
+    }
+
+    /// Déplace le focus parmi les éléments donnés dans l'ordre, en bouclant
+    ///
+    /// # Arguments
+    /// * `items` - Éléments pouvant recevoir le focus
+    /// * `step` - Déplacement : -1 vers l'élément précédent, 1 vers le suivant
+    pub fn navigate(&mut self, items: &[T], step: isize) {
+        if step == 0 || items.is_empty() {
+            return;
+        }
+        let len = items.len() as isize;
+        let next = match self
+            .current
+            .and_then(|c| items.iter().position(|&i| i == c))
+        {
+            Some(i) => (i as isize + step).rem_euclid(len),
+            // Le premier appui donne le focus au premier (ou au dernier) élément
+            None if step > 0 => 0,
+            None => len - 1,
+        };
+        self.current = Some(items[next as usize]);
+    }
+}
+
+/// Taille de la fenêtre pour laquelle un écran a placé ses widgets
+///
+/// Un écran garde sa disposition tant que la fenêtre garde la même taille,
+/// et replace ses widgets sinon.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Viewport {
+    size: Vec2,
+}
+
+impl Viewport {
+    /// Vérifie si la fenêtre a changé de taille depuis le dernier appel
+    pub fn resized(&mut self) -> bool {
+        let size = vec2(screen_width(), screen_height());
+        let resized = size != self.size;
+        self.size = size;
+        resized
+    }
+
+    /// Rectangle de la fenêtre
+    pub fn rect(&self) -> Rect {
+        Rect::new(0., 0., self.size.x, self.size.y)
+    }
+}
+
+/// Encadre l'élément qui a le focus
+pub fn draw_focus(rect: Rect) {
+    draw_rectangle_lines(
+        rect.x - 4.,
+        rect.y - 4.,
+        rect.w + 8.,
+        rect.h + 8.,
+        3.,
+        WHITE,
+    );
+}
+
+/// Vérifie si la souris est dans un rectangle
+fn is_hovered(rect: Rect) -> bool {
+    rect.contains(mouse_position().into())
+}
+
+/// Vérifie si un rectangle vient d'être cliqué
+fn is_clicked(rect: Rect) -> bool {
+    is_mouse_button_pressed(MouseButton::Left) && is_hovered(rect)
+}
+
+/// Texte non interactif
+///
+/// # Champs
+/// * `rect` - Rectangle du texte
+/// * `text` - Texte affiché
+/// * `font_size` - Taille du texte
+/// * `color` - Couleur du texte
+/// * `align` - Alignement horizontal
+pub struct Label {
+    rect: Rect,
+    text: String,
+    font_size: u16,
+    color: Color,
+    align: Align,
+}
+
+impl Label {
+    /// Crée un libellé blanc centré dans le rectangle
+    pub fn new(rect: Rect, text: &str) -> Self {
+        Self {
+            rect,
+            text: text.to_string(),
+            font_size: TEXT_SIZE,
+            color: WHITE,
+            align: Align::Center,
+        }
+    }
+
+    /// Déplace le widget, quand la fenêtre change de taille
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    /// Change la taille du texte
+    pub fn with_size(mut self, font_size: u16) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+    /// Change l'alignement du texte
+    pub fn with_align(mut self, align: Align) -> Self {
+        self.align = align;
+        self
+    }
+
+    /// Change le texte et sa couleur
+    pub fn set_text(&mut self, text: &str, color: Color) {
+        self.text = text.to_string();
+        self.color = color;
+    }
+
+    /// Dessine le libellé
+    pub fn draw(&self, font: Option<&Font>) {
+        draw_text_in(
+            &self.text,
+            self.rect,
+            self.font_size,
+            self.color,
+            self.align,
+            font,
+        );
+    }
+}
+
+/// Curseur choisissant une valeur entre deux bornes, par pas réguliers
+///
+/// # Champs
+/// * `rect` - Rectangle du curseur, le libellé à gauche et la glissière à droite
+/// * `label` - Nom de la valeur
+/// * `min` / `max` - Bornes de la valeur
+/// * `step` - Pas entre deux valeurs possibles
+/// * `value` - Valeur choisie
+/// * `dragging` - La poignée est tirée à la souris
+/// * `focused` - Le curseur a le focus : les flèches gauche et droite changent la valeur
+pub struct Slider {
+    rect: Rect,
+    label: String,
+    min: f32,
+    max: f32,
+    step: f32,
+    value: f32,
+    dragging: bool,
+    focused: bool,
+}
+
+impl Slider {
+    /// Crée un curseur
+    pub fn new(rect: Rect, label: &str, min: f32, max: f32, step: f32, value: f32) -> Self {
+        let mut slider = Self {
+            rect,
+            label: label.to_string(),
+            min,
+            max,
+            step,
+            value: min,
+            dragging: false,
+            focused: false,
+        };
+        slider.set_value(value);
+        slider
+    }
+
+    /// Déplace le widget, quand la fenêtre change de taille
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    /// Valeur choisie
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    /// Change la valeur, ramenée entre les bornes et sur le pas le plus proche
+    pub fn set_value(&mut self, value: f32) {
+        let steps = ((value - self.min) / self.step).round();
+        self.value = (self.min + steps * self.step).clamp(self.min, self.max);
+    }
+
+    /// Glissière, sur la moitié droite du rectangle
+    fn track(&self) -> Rect {
+        Rect::new(
+            self.rect.x + self.rect.w / 2.,
+            self.rect.y,
+            self.rect.w / 2.,
+            self.rect.h,
+        )
+    }
+
+    /// Donne ou retire le focus
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Suit la souris pendant que la poignée est tirée, ou les flèches s'il a le focus
+    ///
+    /// # Retourne
+    /// `true` si la valeur a changé
+    pub fn update(&mut self, nav: &Navigation) -> bool {
+        let step = nav.step(NavAction::Left, NavAction::Right);
+        if self.focused && step != 0 {
+            let previous = self.value;
+            self.set_value(self.value + step as f32 * self.step);
+            return self.value != previous;
+        }
+        let track = self.track();
+        if is_clicked(track) {
+            self.dragging = true;
+        }
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = false;
+        }
+        if !self.dragging {
+            return false;
+        }
+        let ratio = ((mouse_position().0 - track.x) / track.w).clamp(0., 1.);
+        let previous = self.value;
+        self.set_value(self.min + ratio * (self.max - self.min));
+        self.value != previous
+    }
+
+    /// Dessine le libellé, la glissière et la poignée
+    pub fn draw(&self, font: Option<&Font>) {
+        let text = format!("{} : {}", self.label, self.value);
+        draw_text_in(&text, self.rect, TEXT_SIZE, WHITE, Align::Left, font);
+
+        let track = self.track();
+        let y = track.y + track.h / 2.;
+        draw_line(track.x, y, track.x + track.w, y, 4., FIELD_COLOR);
+        let ratio = if self.max > self.min {
+            (self.value - self.min) / (self.max - self.min)
+        } else {
+            0.
+        };
+        let x = track.x + ratio * track.w;
+        draw_line(track.x, y, x, y, 4., ACCENT_COLOR);
+        draw_circle(x, y, track.h / 4., ACCENT_COLOR);
+        if self.focused {
+            draw_focus(self.rect);
+        }
+    }
+}
+
+/// Interrupteur à deux états, activé par un clic
+///
+/// # Champs
+/// * `rect` - Rectangle de l'interrupteur
+/// * `label` - Texte à droite de la case
+/// * `on` - État de l'interrupteur
+/// * `focused` - L'interrupteur a le focus
+pub struct Toggle {
+    rect: Rect,
+    label: String,
+    on: bool,
+    focused: bool,
+}
+
+impl Toggle {
+    /// Crée un interrupteur
+    pub fn new(rect: Rect, label: &str, on: bool) -> Self {
+        Self {
+            rect,
+            label: label.to_string(),
+            on,
+            focused: false,
+        }
+    }
+
+    /// Déplace le widget, quand la fenêtre change de taille
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    /// État de l'interrupteur
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Change l'état de l'interrupteur
+    pub fn set_on(&mut self, on: bool) {
+        self.on = on;
+    }
+
+    /// Donne ou retire le focus
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Bascule l'interrupteur au clic, ou à une flèche ou Entrée s'il a le focus
+    ///
+    /// # Retourne
+    /// `true` si l'état a changé
+    pub fn update(&mut self, nav: &Navigation) -> bool {
+        let keys = [NavAction::Left, NavAction::Right, NavAction::Activate];
+        if is_clicked(self.rect) || (self.focused && keys.iter().any(|&a| nav.pressed(a))) {
+            self.on = !self.on;
+            return true;
+        }
+        false
+    }
+
+    /// Dessine la case et le libellé
+    pub fn draw(&self, font: Option<&Font>) {
+        let size = self.rect.h * 0.6;
+        let (x, y) = (self.rect.x, self.rect.y + (self.rect.h - size) / 2.);
+        draw_rectangle(x, y, size, size, FIELD_COLOR);
+        draw_rectangle_lines(x, y, size, size, 2., ACCENT_COLOR);
+        if self.on {
+            draw_rectangle(x + 4., y + 4., size - 8., size - 8., ACCENT_COLOR);
+        }
+        let text = Rect::new(
+            x + size + 10.,
+            self.rect.y,
+            self.rect.w - size - 10.,
+            self.rect.h,
+        );
+        draw_text_in(&self.label, text, TEXT_SIZE, WHITE, Align::Left, font);
+        if self.focused {
+            draw_focus(self.rect);
+        }
+    }
+}
+
+/// Liste déroulante : un choix parmi plusieurs, la liste s'ouvrant sous le champ
+///
+/// # Champs
+/// * `rect` - Rectangle du champ fermé
+/// * `choices` - Textes des choix
+/// * `selected` - Indice du choix sélectionné
+/// * `open` - La liste des choix est affichée
+/// * `focused` - La liste a le focus : les flèches gauche et droite changent le choix
+pub struct Dropdown {
+    rect: Rect,
+    choices: Vec<String>,
+    selected: usize,
+    open: bool,
+    focused: bool,
+}
+
+impl Dropdown {
+    /// Crée une liste déroulante
+    pub fn new(rect: Rect, choices: &[&str], selected: usize) -> Self {
+        Self {
+            rect,
+            choices: choices.iter().map(|choice| choice.to_string()).collect(),
+            selected: selected.min(choices.len().saturating_sub(1)),
+            open: false,
+            focused: false,
+        }
+    }
+
+    /// Déplace le widget, quand la fenêtre change de taille
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    /// Indice du choix sélectionné
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Sélectionne un choix
+    pub fn set_selected(&mut self, selected: usize) {
+        if selected < self.choices.len() {
+            self.selected = selected;
+        }
+    }
+
+    /// Vérifie si la liste est ouverte : elle recouvre alors les widgets du dessous
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Rectangle du choix `i` dans la liste ouverte
+    fn choice_rect(&self, i: usize) -> Rect {
+        self.rect.offset(vec2(0., self.rect.h * (i + 1) as f32))
+    }
+
+    /// Donne ou retire le focus
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Ouvre ou ferme la liste et sélectionne le choix cliqué ; avec le focus,
+    /// les flèches gauche et droite et Entrée passent d'un choix à l'autre
+    ///
+    /// # Retourne
+    /// `true` si la sélection a changé
+    pub fn update(&mut self, nav: &Navigation) -> bool {
+        let step = match nav.step(NavAction::Left, NavAction::Right) {
+            0 if nav.pressed(NavAction::Activate) => 1,
+            step => step,
+        };
+        if self.focused && !self.open && step != 0 && !self.choices.is_empty() {
+            let len = self.choices.len() as isize;
+            self.selected = (self.selected as isize + step).rem_euclid(len) as usize;
+            return true;
+        }
+        if !is_mouse_button_pressed(MouseButton::Left) {
+            return false;
+        }
+        if !self.open {
+            self.open = is_hovered(self.rect);
+            return false;
+        }
+        // Un clic ailleurs ferme la liste sans rien changer
+        self.open = false;
+        let clicked = (0..self.choices.len()).find(|&i| is_hovered(self.choice_rect(i)));
+        match clicked {
+            Some(i) if i != self.selected => {
+                self.selected = i;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Dessine le champ, et la liste des choix si elle est ouverte
+    pub fn draw(&self, font: Option<&Font>) {
+        let rect = self.rect;
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, FIELD_COLOR);
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2., ACCENT_COLOR);
+        let text = &self.choices[self.selected];
+        draw_text_in(text, rect, TEXT_SIZE, WHITE, Align::Center, font);
+        // Flèche indiquant la liste
+        let (x, y) = (rect.x + rect.w - 20., rect.y + rect.h / 2.);
+        draw_triangle(
+            vec2(x - 6., y - 3.),
+            vec2(x + 6., y - 3.),
+            vec2(x, y + 4.),
+            ACCENT_COLOR,
+        );
+
+        if self.open {
+            for (i, choice) in self.choices.iter().enumerate() {
+                let rect = self.choice_rect(i);
+                let color = if is_hovered(rect) {
+                    ACCENT_COLOR
+                } else {
+                    Color::new(0.1, 0.1, 0.15, 1.)
+                };
+                draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+                draw_text_in(choice, rect, TEXT_SIZE, WHITE, Align::Center, font);
+            }
+        } else if self.focused {
+            draw_focus(rect);
+        }
+    }
+}
+
+/// Champ de texte sur une ligne, qui reçoit le clavier une fois cliqué
+///
+/// # Champs
+/// * `rect` - Rectangle du champ
+/// * `placeholder` - Texte grisé affiché quand le champ est vide
+/// * `text` - Texte saisi
+/// * `max_len` - Nombre maximal de caractères
+/// * `accept` - Caractères autorisés
+/// * `focused` - Le champ reçoit le clavier
+pub struct TextInput {
+    rect: Rect,
+    placeholder: String,
+    text: String,
+    max_len: usize,
+    accept: fn(char) -> bool,
+    focused: bool,
+}
+
+impl TextInput {
+    /// Crée un champ de texte vide acceptant tous les caractères imprimables
+    pub fn new(rect: Rect, placeholder: &str, max_len: usize) -> Self {
+        Self {
+            rect,
+            placeholder: placeholder.to_string(),
+            text: String::new(),
+            max_len,
+            accept: |c| !c.is_control(),
+            focused: false,
+        }
+    }
+
+    /// Déplace le widget, quand la fenêtre change de taille
+    pub fn set_rect(&mut self, rect: Rect) {
+        self.rect = rect;
+    }
+
+    /// Restreint les caractères acceptés
+    pub fn with_filter(mut self, accept: fn(char) -> bool) -> Self {
+        self.accept = accept;
+        self
+    }
+
+    /// Donne ou retire le clavier au champ
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Texte saisi
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Remplace le texte saisi, coupé au nombre maximal de caractères
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.chars().take(self.max_len).collect();
+    }
+
+    /// Ajoute un caractère à la fin du texte s'il est accepté et qu'il reste de la place
+    ///
+    /// # Retourne
+    /// `true` si le caractère a été ajouté
+    pub fn insert(&mut self, c: char) -> bool {
+        if !(self.accept)(c) || self.text.chars().count() >= self.max_len {
+            return false;
+        }
+        self.text.push(c);
+        true
+    }
+
+    /// Efface le dernier caractère
+    ///
+    /// # Retourne
+    /// `true` si un caractère a été effacé
+    pub fn backspace(&mut self) -> bool {
+        self.text.pop().is_some()
+    }
+
+    /// Prend le clavier au clic dans le champ et le rend au clic ailleurs,
+    /// puis ajoute les caractères tapés
+    ///
+    /// # Retourne
+    /// `true` si le texte a changé
+    pub fn update(&mut self) -> bool {
+        if is_mouse_button_pressed(MouseButton::Left) {
+            self.focused = is_hovered(self.rect);
+        }
+        if !self.focused {
+            return false;
+        }
+        let mut changed = false;
+        while let Some(c) = get_char_pressed() {
+            changed |= self.insert(c);
+        }
+        if is_key_pressed(KeyCode::Backspace) {
+            changed |= self.backspace();
+        }
+        changed
+    }
+
+    /// Dessine le champ et le texte saisi, avec un curseur clignotant s'il a le clavier
+    pub fn draw(&self, font: Option<&Font>) {
+        let rect = self.rect;
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, FIELD_COLOR);
+        let border = if self.focused { ACCENT_COLOR } else { GRAY };
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 2., border);
+
+        let inner = Rect::new(rect.x + 10., rect.y, rect.w - 20., rect.h);
+        if self.text.is_empty() && !self.focused {
+            draw_text_in(&self.placeholder, inner, TEXT_SIZE, GRAY, Align::Left, font);
+            return;
+        }
+        let blink = self.focused && get_time().fract() < 0.5;
+        let text = format!("{}{}", self.text, if blink { "|" } else { "" });
+        draw_text_in(&text, inner, TEXT_SIZE, WHITE, Align::Left, font);
+    }
+}
+
+/// Disposition des widgets en lignes de même hauteur, centrées horizontalement
+///
+/// # Champs
+/// * `center_x` - Abscisse du centre des lignes
+/// * `top` - Ordonnée du haut de la première ligne
+/// * `width` - Largeur d'une ligne
+/// * `row_height` - Hauteur d'une ligne
+/// * `spacing` - Écart vertical entre deux lignes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Column {
+    pub center_x: f32,
+    pub top: f32,
+    pub width: f32,
+    pub row_height: f32,
+    pub spacing: f32,
+}
+
+impl Column {
+    /// Crée une disposition en colonne
+    pub fn new(center_x: f32, top: f32, width: f32, row_height: f32, spacing: f32) -> Self {
+        Self {
+            center_x,
+            top,
+            width,
+            row_height,
+            spacing,
+        }
+    }
+
+    /// Rectangle de la ligne `i`
+    pub fn row(&self, i: usize) -> Rect {
+        Rect::new(
+            self.center_x - self.width / 2.,
+            self.top + (self.row_height + self.spacing) * i as f32,
+            self.width,
+            self.row_height,
+        )
+    }
+
+    /// Rectangle de la case `j` quand la ligne `i` est partagée en `count` cases
+    pub fn cell(&self, i: usize, j: usize, count: usize) -> Rect {
+        let row = self.row(i);
+        let count = count.max(1) as f32;
+        let width = (row.w - CELL_GAP * (count - 1.)) / count;
+        Rect::new(row.x + (width + CELL_GAP) * j as f32, row.y, width, row.h)
+    }
+
+    /// Hauteur occupée par `rows` lignes
+    pub fn height(&self, rows: usize) -> f32 {
+        (self.row_height + self.spacing) * rows as f32 - self.spacing
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn disposition_en_colonne() {
+        let column = Column::new(400., 100., 200., 40., 20.);
+        assert_eq!(column.row(0), Rect::new(300., 100., 200., 40.));
+        assert_eq!(column.row(2), Rect::new(300., 220., 200., 40.));
+        assert_eq!(column.cell(1, 1, 2), Rect::new(410., 160., 90., 40.));
+        assert_eq!(column.height(3), 160.);
+    }
+
+    #[test]
+    fn valeurs_des_widgets() {
+        let rect = Rect::new(0., 0., 100., 20.);
+        let mut slider = Slider::new(rect, "Manches", 1., 5., 2., 4.2);
+        assert_eq!(slider.value(), 5.);
+        slider.set_value(-3.);
+        assert_eq!(slider.value(), 1.);
+
+        let mut input = TextInput::new(rect, "Graine", 3).with_filter(|c| c.is_ascii_digit());
+        assert!(input.insert('4') && !input.insert('x'));
+        assert!(input.insert('2') && input.insert('0') && !input.insert('1'));
+        assert_eq!(input.text(), "420");
+        assert!(input.backspace());
+        assert_eq!(input.text(), "42");
+
+        let mut dropdown = Dropdown::new(rect, &["a", "b"], 5);
+        assert_eq!(dropdown.selected(), 1);
+        dropdown.set_selected(0);
+        assert_eq!(dropdown.selected(), 0);
+        assert!(!dropdown.is_open());
+    }
+
+    #[test]
+    fn navigation_au_clavier() {
+        let mut focus = Focus::new();
+        let items = [1, 2, 3];
+        focus.navigate(&items, -1);
+        assert!(focus.is(3));
+        focus.navigate(&items, 1);
+        assert!(focus.is(1));
+        focus.navigate(&[2, 3], 1);
+        assert!(focus.is(2));
+
+        // Les flèches ne changent la valeur que d'un widget qui a le focus
+        let right = Navigation {
+            actions: vec![NavAction::Right],
+        };
+        let rect = Rect::new(0., 0., 100., 20.);
+        let mut slider = Slider::new(rect, "Manches", 1., 5., 2., 3.);
+        slider.set_focused(true);
+        assert!(slider.update(&right));
+        assert!(!slider.update(&right));
+        assert_eq!(slider.value(), 5.);
+
+        let mut dropdown = Dropdown::new(rect, &["a", "b"], 1);
+        dropdown.set_focused(true);
+        assert!(dropdown.update(&right));
+        assert_eq!(dropdown.selected(), 0);
+    }
+}
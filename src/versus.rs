@@ -0,0 +1,88 @@
+//! Module gérant un duel en plusieurs manches.
+//!
+//! Chaque manche est une partie `World` en mode `Versus` ; le `Match` compte
+//! les manches gagnées par chaque joueur jusqu'à ce que l'un d'eux en ait
+//! remporté la majorité (au meilleur de N manches).
+
+use serde::{Deserialize, Serialize};
+
+use crate::locale::Strings;
+
+/// Nombres de manches proposés dans le menu
+pub const BEST_OF_CHOICES: [u32; 3] = [1, 3, 5];
+
+/// Représente un duel au meilleur de N manches
+///
+/// # Champs
+/// * `best_of` - Nombre maximal de manches
+/// * `wins` - Manches gagnées par chaque joueur
+/// * `rounds` - Manches jouées, égalités comprises
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Match {
+    best_of: u32,
+    pub wins: [u32; 2],
+    pub rounds: u32,
+}
+
+impl Match {
+    /// Crée un duel au meilleur de `best_of` manches
+    pub fn new(best_of: u32) -> Self {
+        Self {
+            best_of: best_of.max(1),
+            wins: [0; 2],
+            rounds: 0,
+        }
+    }
+
+    /// Nombre de manches à gagner pour remporter le duel
+    pub fn wins_needed(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    /// Enregistre le résultat d'une manche
+    ///
+    /// # Arguments
+    /// * `winner` - Gagnant de la manche, `None` en cas d'égalité (manche rejouée)
+    pub fn record(&mut self, winner: Option<usize>) {
+        self.rounds += 1;
+        if let Some(win) = winner.and_then(|player| self.wins.get_mut(player)) {
+            *win += 1;
+        }
+    }
+
+    /// Gagnant du duel, `None` tant qu'il n'est pas terminé
+    pub fn winner(&self) -> Option<usize> {
+        self.wins
+            .iter()
+            .position(|&wins| wins >= self.wins_needed())
+    }
+
+    /// Vérifie si le duel est terminé
+    pub fn is_over(&self) -> bool {
+        self.winner().is_some()
+    }
+
+    /// Texte du score du duel (`J1 2 - 1 J2`)
+    pub fn score_text(&self, strings: &Strings) -> String {
+        strings.format("game.duel_score", &[&self.wins[0], &self.wins[1]])
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn meilleur_de_trois() {
+        let mut duel = Match::new(3);
+        assert_eq!(duel.wins_needed(), 2);
+        duel.record(Some(0));
+        duel.record(None);
+        duel.record(Some(1));
+        assert!(!duel.is_over());
+        duel.record(Some(1));
+        assert_eq!(duel.winner(), Some(1));
+        assert_eq!(duel.rounds, 4);
+        assert_eq!(duel.score_text(&Strings::default()), "J1 1 - 2 J2");
+    }
+}
@@ -0,0 +1,1055 @@
+//! Module contenant la simulation du jeu, indépendante de la fenêtre.
+//!
+//! Ce module regroupe l'état d'une partie (vaisseau, astéroïdes, missiles)
+//! et la logique d'une frame : déplacements, tirs, collisions, vagues
+//! d'astéroïdes et fin de partie.
+//! Il n'utilise aucune fonction de fenêtre de Macroquad, ce qui permet de
+//! l'exécuter en mode headless.
+
+use ::rand::Rng;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::asteroid::Asteroid;
+use crate::bullet::Bullet;
+use crate::input::Input;
+use crate::rng::{self, GameRng};
+use crate::spaceship::Spaceship;
+use crate::stellarobject::StellarObject;
+use crate::Difficulty;
+
+/// Durée simulée d'une frame (en secondes)
+pub const FRAME_TIME: f32 = 1.0 / 60.0;
+
+/// Temps minimal entre deux tirs (en secondes)
+pub const FIRE_COOLDOWN: f32 = 0.5;
+
+/// Temps minimal entre deux sauts en hyperespace (en secondes)
+pub const HYPERSPACE_COOLDOWN: f32 = 2.0;
+
+/// Nombre maximal de joueurs sur un même clavier
+pub const MAX_PLAYERS: usize = 2;
+
+/// Points gagnés en duel à chaque missile touchant l'adversaire
+pub const VERSUS_HIT_POINTS: u32 = 100;
+
+/// Délai avant la réapparition d'un joueur détruit (en secondes)
+pub const RESPAWN_DELAY: f32 = 3.0;
+
+/// Durée de l'explosion d'un vaisseau détruit (en secondes)
+pub const EXPLOSION_DURATION: f32 = 1.0;
+
+/// Nombre de vaisseaux de chaque joueur en début de partie
+pub const START_LIVES: u32 = 3;
+
+/// Un vaisseau supplémentaire est gagné à chaque multiple de ce score
+pub const EXTRA_LIFE_SCORE: u32 = 1000;
+
+/// Distance minimale entre un vaisseau qui réapparaît et le bord des astéroïdes
+pub const SAFE_RADIUS: f32 = 120.0;
+
+/// Vitesse des missiles (en pixels par frame)
+pub const BULLET_SPEED: f32 = 4.0;
+
+/// Dimensions par défaut de l'arène (taille de la fenêtre Macroquad)
+pub const DEFAULT_ARENA: Vec2 = vec2(800.0, 600.0);
+
+/// État d'une partie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameState {
+    Running,
+    Won,
+    Lost,
+}
+
+impl GameState {
+    /// Nom de l'état utilisé dans les résumés et les protocoles
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameState::Running => "running",
+            GameState::Won => "won",
+            GameState::Lost => "lost",
+        }
+    }
+}
+
+/// Règles de la partie
+///
+/// # Variantes
+/// * `Classic` - Les joueurs détruisent les vagues d'astéroïdes, seuls ou à deux
+/// * `Versus` - Duel : les missiles touchent le vaisseau adverse et les
+///   astéroïdes sont des obstacles indestructibles
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    #[default]
+    Classic,
+    Versus,
+}
+
+/// Représente un joueur de la partie
+///
+/// # Champs
+/// * `spaceship` - Vaisseau du joueur
+/// * `score` - Points gagnés en détruisant des astéroïdes
+/// * `shots` - Nombre de missiles tirés
+/// * `hits` - Nombre de missiles ayant touché un astéroïde ou un adversaire
+/// * `last_shot` - Frame du dernier tir
+/// * `last_jump` - Frame du dernier saut en hyperespace
+/// * `respawn_at` - Frame de réapparition d'un joueur détruit, `None` s'il est en jeu
+/// * `lives` - Vaisseaux restants, y compris celui en jeu
+/// * `extra_lives` - Vaisseaux déjà gagnés grâce au score (voir `EXTRA_LIFE_SCORE`)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Player {
+    pub spaceship: Spaceship,
+    pub score: u32,
+    #[serde(default)]
+    pub shots: u32,
+    #[serde(default)]
+    pub hits: u32,
+    last_shot: Option<u64>,
+    #[serde(default)]
+    last_jump: Option<u64>,
+    respawn_at: Option<u64>,
+    #[serde(default = "default_lives")]
+    pub lives: u32,
+    #[serde(default)]
+    extra_lives: u32,
+}
+
+impl Player {
+    /// Crée un joueur dont le vaisseau est à la position donnée
+    ///
+    /// # Arguments
+    /// * `position` - Position de départ du vaisseau
+    /// * `shield` - Bouclier du vaisseau, selon la difficulté
+    pub fn new(position: Vec2, shield: u8) -> Self {
+        Self {
+            spaceship: Spaceship::new(position).with_shield(shield),
+            score: 0,
+            shots: 0,
+            hits: 0,
+            last_shot: None,
+            last_jump: None,
+            respawn_at: None,
+            lives: START_LIVES,
+            extra_lives: 0,
+        }
+    }
+
+    /// Vérifie si le vaisseau du joueur est en jeu
+    pub fn is_alive(&self) -> bool {
+        self.respawn_at.is_none()
+    }
+
+    /// Avancement de l'explosion du vaisseau détruit, de 0 à 1
+    ///
+    /// # Retourne
+    /// `None` si le vaisseau est en jeu ou si son explosion est terminée
+    pub fn explosion(&self, frame: u64) -> Option<f32> {
+        let destroyed_at = self
+            .respawn_at?
+            .saturating_sub((RESPAWN_DELAY / FRAME_TIME) as u64);
+        let elapsed = frame.saturating_sub(destroyed_at) as f32 * FRAME_TIME;
+        (elapsed < EXPLOSION_DURATION).then_some(elapsed / EXPLOSION_DURATION)
+    }
+}
+
+/// Représente une partie en cours
+///
+/// # Champs
+/// * `players` - Joueurs de la partie (un seul, ou deux en coopération et en duel)
+/// * `asteroids` - Astéroïdes présents dans l'arène
+/// * `bullets` - Missiles tirés
+/// * `size` - Dimensions de l'arène
+/// * `difficulty` - Difficulté de la partie
+/// * `sandbox` - Mode test : pas d'astéroïdes ni de fin de partie
+/// * `mode` - Règles de la partie
+/// * `friendly_fire` - Les missiles d'un joueur touchent aussi ses coéquipiers
+/// * `wave` - Numéro de la vague d'astéroïdes en cours (à partir de 1)
+/// * `frame` - Nombre de frames simulées
+/// * `state` - État de la partie
+/// * `rng` - Générateur aléatoire de la partie, enregistré avec elle
+#[derive(Clone, Serialize, Deserialize)]
+pub struct World {
+    pub players: Vec<Player>,
+    pub asteroids: Vec<Asteroid>,
+    pub bullets: Vec<Bullet>,
+    #[serde(with = "crate::save::vec2")]
+    pub size: Vec2,
+    pub difficulty: Difficulty,
+    pub sandbox: bool,
+    #[serde(default)]
+    pub mode: GameMode,
+    pub friendly_fire: bool,
+    pub wave: u32,
+    pub frame: u64,
+    pub state: GameState,
+    rng: GameRng,
+}
+
+impl World {
+    /// Crée une nouvelle partie à un joueur
+    ///
+    /// # Arguments
+    /// * `seed` - Graine du générateur aléatoire
+    /// * `difficulty` - Difficulté qui détermine le nombre d'astéroïdes
+    /// * `size` - Dimensions de l'arène
+    pub fn new(seed: u64, difficulty: Difficulty, size: Vec2) -> Self {
+        Self::with_players(seed, difficulty, size, 1)
+    }
+
+    /// Crée une nouvelle partie avec plusieurs joueurs sur le même champ d'astéroïdes
+    ///
+    /// # Arguments
+    /// * `seed` - Graine du générateur aléatoire
+    /// * `difficulty` - Difficulté qui détermine le nombre d'astéroïdes
+    /// * `size` - Dimensions de l'arène
+    /// * `players` - Nombre de joueurs (au moins un)
+    pub fn with_players(seed: u64, difficulty: Difficulty, size: Vec2, players: usize) -> Self {
+        let mut rng = rng::from_seed(seed);
+        let asteroids = create_asteroids(&mut rng, difficulty, 1, size);
+        let count = players.max(1);
+        let shield = difficulty.params().shield as u8;
+        Self {
+            players: (0..count)
+                .map(|i| Player::new(spawn_position(i, count, size), shield))
+                .collect(),
+            asteroids,
+            bullets: Vec::new(),
+            size,
+            difficulty,
+            sandbox: false,
+            mode: GameMode::Classic,
+            friendly_fire: false,
+            wave: 1,
+            frame: 0,
+            state: GameState::Running,
+            rng,
+        }
+    }
+
+    /// Crée une manche de duel entre deux joueurs
+    ///
+    /// Les astéroïdes, dont le nombre dépend de la difficulté, servent d'obstacles.
+    pub fn new_versus(seed: u64, difficulty: Difficulty, size: Vec2) -> Self {
+        let mut world = Self::with_players(seed, difficulty, size, 2);
+        world.mode = GameMode::Versus;
+        world
+    }
+
+    /// Crée une partie de test du vaisseau, sans astéroïdes
+    pub fn new_sandbox(seed: u64, size: Vec2) -> Self {
+        let mut world = Self::new(seed, Difficulty::Easy, size);
+        world.asteroids.clear();
+        world.sandbox = true;
+        world
+    }
+
+    /// Vaisseau du premier joueur
+    pub fn spaceship(&self) -> &Spaceship {
+        &self.players[0].spaceship
+    }
+
+    /// Score total des joueurs
+    pub fn score(&self) -> u32 {
+        self.players.iter().map(|player| player.score).sum()
+    }
+
+    /// Vérifie si la partie est terminée
+    pub fn is_over(&self) -> bool {
+        self.state != GameState::Running
+    }
+
+    /// Gagnant d'une manche de duel terminée, `None` en cas d'égalité ou hors duel
+    pub fn winner(&self) -> Option<usize> {
+        if self.mode != GameMode::Versus || self.state != GameState::Won {
+            return None;
+        }
+        self.players
+            .iter()
+            .position(|player| player.spaceship.get_shield() > 0)
+    }
+
+    /// Change les dimensions de l'arène (redimensionnement de la fenêtre)
+    pub fn set_size(&mut self, size: Vec2) {
+        self.size = size;
+    }
+
+    /// Simule une frame de jeu à un joueur
+    ///
+    /// # Arguments
+    /// * `input` - Commandes du joueur pour cette frame
+    pub fn step(&mut self, input: &Input) {
+        self.step_players(std::slice::from_ref(input));
+    }
+
+    /// Simule une frame de jeu
+    ///
+    /// # Arguments
+    /// * `inputs` - Commandes de chaque joueur pour cette frame (aucune touche si absentes)
+    pub fn step_players(&mut self, inputs: &[Input]) {
+        if self.is_over() {
+            return;
+        }
+        self.frame += 1;
+
+        self.respawn_players();
+
+        // Fait diminuer le temps d'invicibilité à chaque frame
+        for player in &mut self.players {
+            if player.spaceship.get_invicibility() > 0.0 {
+                player.spaceship.set_invicibility(FRAME_TIME);
+            }
+        }
+
+        // On fait bouger tous les asteroides encore présent et remets leur collision à faux
+        for asteroid in &mut self.asteroids {
+            asteroid.set_collision(false);
+            asteroid.move_object();
+            asteroid.bound_pos(self.size);
+        }
+
+        for i in 0..self.players.len() {
+            if self.players[i].is_alive() {
+                let input = inputs.get(i).copied().unwrap_or_default();
+                self.update_spaceship(i, &input);
+                self.hyperspace(i, &input);
+                self.fire(i, &input);
+            }
+        }
+        self.update_bullets();
+
+        if self.sandbox {
+            return;
+        }
+
+        self.spawn_enemies();
+        self.handle_collisions();
+        self.award_extra_lives();
+        self.handle_destroyed_players();
+
+        // Regarde si la partie est gagné ou perdu
+        if !self.is_over() && self.mode == GameMode::Classic && self.asteroids.is_empty() {
+            if self.difficulty.params().has_next_wave(self.wave) {
+                self.next_wave();
+            } else {
+                self.state = GameState::Won;
+            }
+        }
+        if self.is_over() {
+            self.asteroids.clear();
+            self.bullets.clear();
+        }
+    }
+
+    /// Lance la vague suivante, avec plus d'astéroïdes
+    ///
+    /// Les vaisseaux sont brièvement invincibles pour ne pas être touchés par
+    /// un astéroïde apparu sur eux.
+    fn next_wave(&mut self) {
+        self.wave += 1;
+        self.asteroids = create_asteroids(&mut self.rng, self.difficulty, self.wave, self.size);
+        for player in &mut self.players {
+            player.spaceship.init_invicibility();
+        }
+    }
+
+    /// Fait arriver un astéroïde par le bord de l'arène, au rythme donné par
+    /// la difficulté (`CustomDifficulty::enemy_frequency`)
+    fn spawn_enemies(&mut self) {
+        let params = self.difficulty.params();
+        if params.enemy_frequency == 0 || self.mode != GameMode::Classic {
+            return;
+        }
+        let interval = (60.0 / FRAME_TIME) as u64 / params.enemy_frequency as u64;
+        if !self.frame.is_multiple_of(interval.max(1)) {
+            return;
+        }
+        // Le bord gauche ou le bord haut, l'arène se repliant sur elle-même
+        let position = if self.rng.gen() {
+            vec2(0., self.rng.gen_range(0.0..self.size.y))
+        } else {
+            vec2(self.rng.gen_range(0.0..self.size.x), 0.)
+        };
+        let asteroid = Asteroid::new2(&mut self.rng, position, Asteroid::ASTEROID_INIT_SIZE);
+        self.asteroids
+            .push(asteroid.with_speed_factor(params.speed_factor()));
+    }
+
+    /// Fait réapparaître les joueurs détruits dont le délai est écoulé
+    ///
+    /// Un vaisseau ne réapparaît qu'à l'écart des astéroïdes (voir `is_safe`) :
+    /// sinon, il attend qu'ils s'éloignent.
+    fn respawn_players(&mut self) {
+        let count = self.players.len();
+        let shield = self.difficulty.params().shield as u8;
+        for i in 0..count {
+            let player = &self.players[i];
+            let due = player.respawn_at.is_some_and(|frame| frame <= self.frame);
+            let position = spawn_position(i, count, self.size);
+            if !due || player.lives == 0 || !self.is_safe(position) {
+                continue;
+            }
+            let player = &mut self.players[i];
+            player.spaceship = Spaceship::new(position).with_shield(shield);
+            player.spaceship.init_invicibility();
+            player.respawn_at = None;
+        }
+    }
+
+    /// Vérifie que le bord de chaque astéroïde est à au moins `SAFE_RADIUS` d'une position
+    fn is_safe(&self, position: Vec2) -> bool {
+        self.asteroids.iter().all(|asteroid| {
+            let distance = wrapped_delta(position, asteroid.get_position(), self.size).length();
+            distance >= SAFE_RADIUS + asteroid.get_width()
+        })
+    }
+
+    /// Donne un vaisseau supplémentaire à chaque multiple de `EXTRA_LIFE_SCORE`
+    fn award_extra_lives(&mut self) {
+        if self.mode != GameMode::Classic {
+            return;
+        }
+        for player in &mut self.players {
+            let earned = player.score / EXTRA_LIFE_SCORE;
+            if earned > player.extra_lives {
+                player.lives += earned - player.extra_lives;
+                player.extra_lives = earned;
+            }
+        }
+    }
+
+    /// Retire les vaisseaux sans bouclier
+    ///
+    /// Un joueur détruit perd un vaisseau et réapparaît après `RESPAWN_DELAY`
+    /// s'il lui en reste ; la partie est perdue quand plus aucun joueur n'en a.
+    /// En duel, la manche est gagnée par le dernier vaisseau en jeu.
+    fn handle_destroyed_players(&mut self) {
+        let destroyed = |player: &Player| player.is_alive() && player.spaceship.get_shield() == 0;
+        if !self.players.iter().any(destroyed) {
+            return;
+        }
+        if self.mode == GameMode::Versus {
+            let survivors = self
+                .players
+                .iter()
+                .filter(|p| p.is_alive() && !destroyed(p))
+                .count();
+            self.state = if survivors == 1 {
+                GameState::Won
+            } else {
+                GameState::Lost
+            };
+            return;
+        }
+        let respawn_at = self.frame + (RESPAWN_DELAY / FRAME_TIME) as u64;
+        for player in self.players.iter_mut() {
+            if destroyed(player) {
+                player.lives = player.lives.saturating_sub(1);
+                player.respawn_at = Some(respawn_at);
+            }
+        }
+        if self.players.iter().all(|player| player.lives == 0) {
+            self.state = GameState::Lost;
+        }
+    }
+
+    /// Applique les commandes à un vaisseau et le déplace
+    fn update_spaceship(&mut self, player: usize, input: &Input) {
+        steer_spaceship(&mut self.players[player].spaceship, input, self.size);
+    }
+
+    /// Lance un missile si le joueur tire et que le délai depuis son dernier tir est écoulé
+    fn fire(&mut self, player: usize, input: &Input) {
+        if !input.fire || !self.can_fire(player) {
+            return;
+        }
+        let spaceship = &self.players[player].spaceship;
+        // Calcul de la direction du missile
+        let rot_vec = Vec2::new(
+            spaceship.get_orientation().sin(),
+            -spaceship.get_orientation().cos(),
+        );
+        self.bullets.push(Bullet::new(
+            spaceship.get_position() + rot_vec * spaceship.get_height() / 2.,
+            rot_vec * BULLET_SPEED,
+            player,
+        ));
+        self.players[player].last_shot = Some(self.frame);
+        self.players[player].shots += 1;
+    }
+
+    /// Envoie le vaisseau en hyperespace : il réapparaît à une position
+    /// aléatoire de l'arène, au risque de tomber sur un astéroïde
+    fn hyperspace(&mut self, player: usize, input: &Input) {
+        if !input.hyperspace || !self.can_jump(player) {
+            return;
+        }
+        let position = vec2(
+            self.rng.gen_range(0.0..self.size.x),
+            self.rng.gen_range(0.0..self.size.y),
+        );
+        self.players[player].spaceship.position = position;
+        self.players[player].last_jump = Some(self.frame);
+    }
+
+    /// Déplace les missiles
+    fn update_bullets(&mut self) {
+        // Bouger un missile
+        for bullet in self.bullets.iter_mut() {
+            bullet.move_object();
+        }
+
+        // Si le missile dépasse les bords de l'arène il disparait
+        let size = self.size;
+        self.bullets.retain(|bullet| !bullet.is_out(size));
+    }
+
+    /// Vérifie si le délai entre deux tirs d'un joueur est écoulé
+    pub fn can_fire(&self, player: usize) -> bool {
+        match self.players[player].last_shot {
+            Some(frame) => {
+                (self.frame - frame) as f32 * FRAME_TIME
+                    > self.difficulty.params().fire_cooldown_secs()
+            }
+            None => true,
+        }
+    }
+
+    /// Vérifie si le délai entre deux sauts en hyperespace d'un joueur est écoulé
+    pub fn can_jump(&self, player: usize) -> bool {
+        match self.players[player].last_jump {
+            Some(frame) => (self.frame - frame) as f32 * FRAME_TIME > HYPERSPACE_COOLDOWN,
+            None => true,
+        }
+    }
+
+    /// Part écoulée du délai entre deux tirs d'un joueur, de 0 à 1
+    pub fn fire_charge(&self, player: usize) -> f32 {
+        let cooldown = self.difficulty.params().fire_cooldown_secs();
+        self.charge(self.players[player].last_shot, cooldown)
+    }
+
+    /// Part écoulée du délai entre deux sauts en hyperespace d'un joueur, de 0 à 1
+    pub fn jump_charge(&self, player: usize) -> f32 {
+        self.charge(self.players[player].last_jump, HYPERSPACE_COOLDOWN)
+    }
+
+    /// Part écoulée d'un délai depuis une frame, pleine si l'action n'a jamais eu lieu
+    fn charge(&self, since: Option<u64>, cooldown: f32) -> f32 {
+        match since {
+            Some(frame) => ((self.frame - frame) as f32 * FRAME_TIME / cooldown).min(1.0),
+            None => 1.0,
+        }
+    }
+
+    /// Gère toutes les collisions de la frame
+    fn handle_collisions(&mut self) {
+        // Collision astéroide / astéroide
+        for i in 0..self.asteroids.len() {
+            for j in (i + 1)..self.asteroids.len() {
+                let (asteroid1, asteroid2) = {
+                    let (left, right) = self.asteroids.split_at_mut(j);
+                    (&mut left[i], &mut right[0])
+                };
+                asteroid1.collided(asteroid2);
+            }
+        }
+
+        // Collision entre un astéroide et les vaisseaux en jeu
+        for player in self.players.iter_mut().filter(|p| p.is_alive()) {
+            for asteroid in self.asteroids.iter_mut() {
+                player.spaceship.collided(asteroid);
+            }
+        }
+
+        // Tir allié ou duel : un missile touche le vaisseau d'un autre joueur
+        let versus = self.mode == GameMode::Versus;
+        if self.friendly_fire || versus {
+            for bullet in self.bullets.iter_mut() {
+                for i in 0..self.players.len() {
+                    let player = &mut self.players[i];
+                    if i != bullet.get_owner()
+                        && player.is_alive()
+                        && !bullet.get_touched()
+                        && bullet.hits(&player.spaceship)
+                    {
+                        bullet.handle_collision();
+                        let spaceship = &mut player.spaceship;
+                        spaceship.knock(
+                            bullet.get_position() - spaceship.get_position(),
+                            bullet.get_speed() - spaceship.get_speed(),
+                        );
+                        spaceship.hit();
+                        if versus {
+                            let owner = &mut self.players[bullet.get_owner()];
+                            owner.score += VERSUS_HIT_POINTS;
+                            owner.hits += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        // En duel, les astéroïdes arrêtent les missiles sans être détruits
+        if versus {
+            let asteroids = &self.asteroids;
+            for bullet in self.bullets.iter_mut() {
+                if asteroids
+                    .iter()
+                    .any(|asteroid| bullet.touches(asteroid.get_position(), asteroid.get_width()))
+                {
+                    bullet.handle_collision();
+                }
+            }
+            self.bullets.retain(|bullet| !bullet.get_touched());
+            return;
+        }
+
+        // Collision missile avec un astéroide
+        let speed_factor = self.difficulty.params().speed_factor();
+        let mut new_asteroids: Vec<Asteroid> = Vec::new();
+        for asteroid in self.asteroids.iter_mut() {
+            for bullet in self.bullets.iter_mut() {
+                if !bullet.get_touched() && bullet.collided(asteroid) {
+                    if let Some(player) = self.players.get_mut(bullet.get_owner()) {
+                        player.score += points(asteroid.get_width());
+                        player.hits += 1;
+                    }
+                    if asteroid.get_width() > 15.0 {
+                        // On unwrap car l'erreur ne peut pas se produire
+                        let (a1, a2) =
+                            divide(&mut self.rng, asteroid.get_position(), asteroid.get_width())
+                                .unwrap();
+                        new_asteroids.push(a1.with_speed_factor(speed_factor));
+                        new_asteroids.push(a2.with_speed_factor(speed_factor));
+                    }
+                }
+            }
+        }
+
+        self.asteroids.append(&mut new_asteroids);
+
+        // Supprime les astéroides et les projectiles qui ont été touchés
+        self.asteroids.retain(|asteroid| !asteroid.get_touched());
+        self.bullets.retain(|bullet| !bullet.get_touched());
+    }
+}
+
+/// Applique les commandes à un vaisseau et le déplace
+///
+/// Les commandes sont appliquées sans collisions : c'est ce que fait la partie
+/// pour chaque vaisseau, et ce que rejoue un client réseau pour prédire le sien.
+///
+/// # Arguments
+/// * `spaceship` - Vaisseau à déplacer
+/// * `input` - Commandes de la frame
+/// * `arena` - Dimensions de l'arène
+pub fn steer_spaceship(spaceship: &mut Spaceship, input: &Input, arena: Vec2) {
+    // Tourner (orientation)
+    if input.right {
+        spaceship.set_orientation('R');
+    } else if input.left {
+        spaceship.set_orientation('L');
+    }
+
+    // Faire en sorte que le vaisseau ne s'arrête jamais
+    // si aucune touche n'est pressée
+    if input.is_idle() {
+        spaceship.min_speed();
+    }
+
+    // Avancer
+    if input.up {
+        let acc = Vec2::new(
+            spaceship.get_orientation().sin(),
+            -spaceship.get_orientation().cos(),
+        ) / 30.0;
+        spaceship.set_speed(acc);
+    } else if input.down {
+        let acc = Vec2::new(
+            -spaceship.get_orientation().sin(),
+            spaceship.get_orientation().cos(),
+        ) / 40.0;
+        spaceship.set_speed(acc);
+    }
+
+    //On deplace la position du vaisseau
+    spaceship.move_object();
+    spaceship.max_speed(); //force a ne pas depasser le vmax
+
+    // Fait réapparaitre le vaisseau à l'opposé de l'arène lorsque l'on touche une limite
+    spaceship.bound_pos(arena);
+}
+
+/// Position de départ d'un vaisseau, les joueurs étant répartis sur une ligne horizontale
+///
+/// # Arguments
+/// * `player` - Numéro du joueur
+/// * `count` - Nombre de joueurs
+/// * `arena` - Dimensions de l'arène
+pub fn spawn_position(player: usize, count: usize, arena: Vec2) -> Vec2 {
+    let x = arena.x * (player + 1) as f32 / (count + 1) as f32;
+    vec2(x, arena.y / 2.)
+}
+
+/// Nombre de vaisseaux des joueurs d'une sauvegarde antérieure aux vies
+fn default_lives() -> u32 {
+    START_LIVES
+}
+
+/// Nombre de points rapportés par la destruction d'un astéroïde
+///
+/// Comme dans le jeu d'arcade, les petits astéroïdes rapportent plus.
+pub fn points(width: f32) -> u32 {
+    if width <= 15.0 {
+        100
+    } else if width <= 25.0 {
+        50
+    } else {
+        20
+    }
+}
+
+/// Plus court vecteur entre deux points d'une arène qui se replie sur elle-même
+pub fn wrapped_delta(from: Vec2, to: Vec2, arena: Vec2) -> Vec2 {
+    let delta = to - from;
+    delta - arena * (delta / arena).round()
+}
+
+/// Initialise le vecteur d'astéroïdes au début d'une vague
+///
+/// # Arguments
+/// * `rng` - Générateur aléatoire de la partie
+/// * `difficulty` - Niveau de difficulté choisi qui détermine le nombre et la vitesse des astéroïdes
+/// * `wave` - Numéro de la vague, chaque vague ajoute des astéroïdes (voir `CustomDifficulty::wave_asteroids`)
+/// * `arena` - Dimensions de l'arène
+///
+/// # Retourne
+/// Un vecteur contenant les astéroïdes de la vague selon la difficulté choisie
+pub fn create_asteroids(
+    rng: &mut impl Rng,
+    difficulty: Difficulty,
+    wave: u32,
+    arena: Vec2,
+) -> Vec<Asteroid> {
+    let params = difficulty.params();
+    let asteroid_count = params.wave_asteroids(wave);
+
+    (0..asteroid_count)
+        .map(|_| Asteroid::new(rng, arena).with_speed_factor(params.speed_factor()))
+        .collect()
+}
+
+/// Gère la division d'un astéroïde après une collision
+/// Crée deux astéroïdes fils après la division d'un astéroïde parent
+///
+/// # Arguments
+/// * `rng` - Générateur aléatoire de la partie
+/// * `position` - La position de l'astéroïde à diviser
+/// * `width` - Taille de l'astéroïde à diviser
+///
+/// # Retourne
+/// Deux astéroïdes de tailles inférieurs ou erreur
+pub fn divide(
+    rng: &mut impl Rng,
+    position: Vec2,
+    width: f32,
+) -> Result<(Asteroid, Asteroid), String> {
+    match width {
+        25.0 => Ok((
+            Asteroid::new2(rng, vec2(position.x + 1., position.y + 1.), 15.0),
+            Asteroid::new2(rng, vec2(position.x - 1., position.y - 1.), 15.0),
+        )),
+        50.0 => Ok((
+            Asteroid::new2(rng, vec2(position.x + 1., position.y + 1.), 25.0),
+            Asteroid::new2(rng, vec2(position.x - 1., position.y - 1.), 25.0),
+        )),
+        _ => Err("Taille non gérée".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ::rand::rngs::SmallRng;
+    use ::rand::SeedableRng;
+
+    use super::*;
+    use crate::custom::{CustomDifficulty, DEFAULT_WAVE_GROWTH};
+
+    #[test]
+    fn creation_asteroide() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let asteroids = create_asteroids(&mut rng, Difficulty::Easy, 1, DEFAULT_ARENA);
+        assert_eq!(asteroids.len(), 3);
+        let asteroids = create_asteroids(&mut rng, Difficulty::Easy, 3, DEFAULT_ARENA);
+        assert_eq!(asteroids.len() as u32, 3 + 2 * DEFAULT_WAVE_GROWTH);
+    }
+
+    #[test]
+    fn division_asteroide() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let (a1, a2) = divide(&mut rng, vec2(0., 0.), 50.0).unwrap();
+        assert_eq!(a1.get_width(), 25.0);
+        assert_eq!(a2.get_width(), 25.0);
+    }
+
+    #[test]
+    fn simulation_deterministe() {
+        let input = Input {
+            right: true,
+            fire: true,
+            ..Default::default()
+        };
+        let mut w1 = World::new(42, Difficulty::Medium, DEFAULT_ARENA);
+        let mut w2 = World::new(42, Difficulty::Medium, DEFAULT_ARENA);
+        for _ in 0..600 {
+            w1.step(&input);
+            w2.step(&input);
+        }
+        assert_eq!(w1.score(), w2.score());
+        assert_eq!(w1.asteroids.len(), w2.asteroids.len());
+        assert_eq!(w1.spaceship().get_position(), w2.spaceship().get_position());
+    }
+
+    #[test]
+    fn vague_suivante() {
+        // Une difficulté prédéfinie est gagnée dès que l'arène est vidée
+        let mut world = World::new(1, Difficulty::Easy, DEFAULT_ARENA);
+        world.asteroids.clear();
+        world.step(&Input::default());
+        assert_eq!(world.state, GameState::Won);
+
+        let custom = CustomDifficulty {
+            asteroids: 3,
+            waves: 3,
+            ..Default::default()
+        };
+        let mut world = World::new(1, Difficulty::Custom(custom), DEFAULT_ARENA);
+        world.asteroids.clear();
+        world.step(&Input::default());
+        assert_eq!(world.wave, 2);
+        assert_eq!(world.asteroids.len() as u32, 3 + DEFAULT_WAVE_GROWTH);
+        assert_eq!(world.state, GameState::Running);
+
+        world.wave = 3;
+        world.asteroids.clear();
+        world.step(&Input::default());
+        assert_eq!(world.state, GameState::Won);
+    }
+
+    #[test]
+    fn difficulte_personnalisee() {
+        let custom = CustomDifficulty {
+            asteroids: 4,
+            speed: 200,
+            shield: 3,
+            fire_cooldown: 1000,
+            enemy_frequency: 60,
+            wave_growth: 0,
+            waves: 1,
+        };
+        let mut world = World::new(2, Difficulty::Custom(custom), DEFAULT_ARENA);
+        assert_eq!(world.asteroids.len(), 4);
+        assert_eq!(world.asteroids[0].get_speed().length().round(), 2.);
+        assert_eq!(world.spaceship().get_shield(), 3);
+
+        let fire = Input {
+            fire: true,
+            ..Default::default()
+        };
+        world.step(&fire);
+        for _ in 0..(0.5 / FRAME_TIME) as u32 {
+            world.step(&Input::default());
+        }
+        assert!(!world.can_fire(0));
+
+        // Un astéroïde arrive chaque seconde par le bord de l'arène
+        let mut world = World::new(2, Difficulty::Custom(custom), DEFAULT_ARENA);
+        for _ in 0..(1.0 / FRAME_TIME) as u32 {
+            world.step(&Input::default());
+        }
+        assert_eq!(world.asteroids.len(), 5);
+    }
+
+    #[test]
+    fn saut_hyperespace() {
+        let jump = Input {
+            hyperspace: true,
+            ..Default::default()
+        };
+        let mut world = World::new_sandbox(5, DEFAULT_ARENA);
+        let start = world.spaceship().get_position();
+        world.step(&jump);
+        let landing = world.spaceship().get_position();
+        assert!(landing.distance(start) > 10.);
+        assert!(!world.can_jump(0));
+        assert!(world.jump_charge(0) < 0.1);
+
+        // Un second saut n'est possible qu'après le délai
+        world.step(&jump);
+        assert!(world.spaceship().get_position().distance(landing) < 5.);
+        for _ in 0..=(HYPERSPACE_COOLDOWN / FRAME_TIME) as u32 {
+            world.step(&Input::default());
+        }
+        assert!(world.can_jump(0));
+        assert_eq!(world.jump_charge(0), 1.0);
+    }
+
+    #[test]
+    fn reapparition_cooperation() {
+        let mut world = World::with_players(3, Difficulty::Easy, DEFAULT_ARENA, 2);
+        while world.players[1].spaceship.get_shield() > 1 {
+            world.players[1].spaceship.set_shield();
+        }
+        world.players[1].spaceship.hit();
+        world.step_players(&[]);
+
+        // Le second joueur est détruit mais la partie continue
+        assert!(!world.players[1].is_alive());
+        assert_eq!(world.state, GameState::Running);
+        for _ in 0..(RESPAWN_DELAY / FRAME_TIME) as u32 {
+            world.step_players(&[]);
+        }
+        assert!(world.players[1].is_alive());
+        assert_eq!(world.players[1].spaceship.get_shield(), 10);
+    }
+
+    #[test]
+    fn vies_et_reapparition() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut world = World::new(3, Difficulty::Easy, DEFAULT_ARENA);
+        let center = spawn_position(0, 1, DEFAULT_ARENA);
+        // Un astéroïde immobile sur le point de réapparition, un autre dans un coin
+        world.asteroids = vec![
+            Asteroid::new2(&mut rng, center, 15.).with_speed_factor(0.),
+            Asteroid::new2(&mut rng, vec2(20., 20.), 15.).with_speed_factor(0.),
+        ];
+        let destroy = |world: &mut World| {
+            while world.players[0].spaceship.get_shield() > 1 {
+                world.players[0].spaceship.set_shield();
+            }
+            world.players[0].spaceship.hit();
+            world.step(&Input::default());
+        };
+        destroy(&mut world);
+        assert!(!world.players[0].is_alive());
+        assert_eq!(world.players[0].lives, START_LIVES - 1);
+        assert_eq!(world.state, GameState::Running);
+        assert_eq!(world.players[0].explosion(world.frame), Some(0.));
+
+        // Le vaisseau attend que le centre soit dégagé pour réapparaître, invincible
+        for _ in 0..(RESPAWN_DELAY / FRAME_TIME) as u32 {
+            world.step(&Input::default());
+        }
+        assert!(!world.players[0].is_alive());
+        assert_eq!(world.players[0].explosion(world.frame), None);
+        world.asteroids.remove(0);
+
+        // Un gros astéroïde dont le centre est hors de `SAFE_RADIUS` mais pas le bord
+        let edge = center + vec2(SAFE_RADIUS + 10., 0.);
+        let big = Asteroid::new2(&mut rng, edge, 40.).with_speed_factor(0.);
+        world.asteroids.push(big);
+        world.step(&Input::default());
+        assert!(!world.players[0].is_alive());
+        world.asteroids.pop();
+        world.step(&Input::default());
+        assert!(world.players[0].is_alive());
+        assert!(world.spaceship().get_invicibility() > 0.);
+
+        // Un vaisseau est gagné à chaque palier de score
+        world.players[0].score = EXTRA_LIFE_SCORE;
+        world.step(&Input::default());
+        assert_eq!(world.players[0].lives, START_LIVES);
+
+        // La partie est perdue avec le dernier vaisseau, une fois l'invincibilité passée
+        while world.spaceship().get_invicibility() > 0. {
+            world.step(&Input::default());
+        }
+        world.players[0].lives = 1;
+        destroy(&mut world);
+        assert_eq!(world.state, GameState::Lost);
+    }
+
+    #[test]
+    fn choc_fait_tourner() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut world = World::new(3, Difficulty::Easy, DEFAULT_ARENA);
+        // Un astéroïde immobile un peu à gauche de la trajectoire du vaisseau
+        let position = world.spaceship().get_position() + vec2(-10., -40.);
+        world.asteroids = vec![Asteroid::new2(&mut rng, position, 15.).with_speed_factor(0.)];
+        for _ in 0..5 {
+            world.step(&Input::default());
+        }
+        assert!(world.spaceship().get_shield() < Spaceship::SPACESHIP_SHIELD);
+        assert!(world.spaceship().get_spin() < 0.);
+        assert!(world.spaceship().get_orientation() < 0.);
+    }
+
+    #[test]
+    fn manche_de_duel() {
+        let fire = Input {
+            fire: true,
+            ..Default::default()
+        };
+        let mut world = World::new_versus(3, Difficulty::Easy, DEFAULT_ARENA);
+        world.asteroids.clear();
+        // Le second vaisseau, presque détruit, est juste devant le premier
+        world.players[1].spaceship.position =
+            world.players[0].spaceship.get_position() - vec2(0., 60.);
+        while world.players[1].spaceship.get_shield() > 1 {
+            world.players[1].spaceship.set_shield();
+        }
+        world.step_players(&[fire]);
+        for _ in 0..20 {
+            world.step_players(&[]);
+        }
+
+        // Sans astéroïdes, pas de vague suivante : la manche se joue entre vaisseaux
+        assert_eq!(world.state, GameState::Won);
+        assert_eq!(world.winner(), Some(0));
+        assert_eq!(world.players[0].score, VERSUS_HIT_POINTS);
+        assert_eq!((world.players[0].shots, world.players[0].hits), (1, 1));
+    }
+
+    #[test]
+    fn asteroides_obstacles_en_duel() {
+        let mut rng = SmallRng::seed_from_u64(0);
+        let mut world = World::new_versus(3, Difficulty::Easy, DEFAULT_ARENA);
+        let position = world.players[0].spaceship.get_position() - vec2(0., 80.);
+        world.asteroids = vec![Asteroid::new2(&mut rng, position, 15.)];
+        world.step_players(&[Input {
+            fire: true,
+            ..Default::default()
+        }]);
+        for _ in 0..20 {
+            world.step_players(&[]);
+        }
+        assert_eq!(world.asteroids.len(), 1);
+        assert!(world.bullets.is_empty());
+    }
+
+    #[test]
+    fn tir_allie() {
+        let fire = Input {
+            fire: true,
+            ..Default::default()
+        };
+        for friendly_fire in [false, true] {
+            let mut world = World::with_players(3, Difficulty::Easy, DEFAULT_ARENA, 2);
+            // Un seul astéroïde, loin des vaisseaux, pour ne pas finir la vague
+            world.asteroids.truncate(1);
+            world.asteroids[0].position = vec2(20., 20.);
+            world.friendly_fire = friendly_fire;
+            // Le second vaisseau est juste devant le premier
+            world.players[1].spaceship.position =
+                world.players[0].spaceship.get_position() - vec2(0., 60.);
+            world.step_players(&[fire]);
+            for _ in 0..20 {
+                world.step_players(&[]);
+            }
+            let shield = world.players[1].spaceship.get_shield();
+            assert_eq!(shield < 10, friendly_fire);
+        }
+    }
+}